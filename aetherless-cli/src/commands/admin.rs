@@ -0,0 +1,447 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Ankit Kumar Pandey
+
+//! HTTP admin API of a running orchestrator.
+//!
+//! Enabled by `orchestrator.admin_listen`. Each route maps onto a
+//! [`ControlRequest`] and is answered the same way as on the control socket:
+//!
+//! | Route | Request |
+//! |-------|---------|
+//! | `GET /admin/functions` | `ListFunctions` |
+//! | `GET /admin/functions/{id}` | `GetFunction` |
+//! | `POST /admin/functions/{id}/snapshot` | `Snapshot` |
+//! | `POST /admin/functions/{id}/restore` | `Restore` |
+//! | `POST /admin/shutdown` | `Shutdown` |
+//!
+//! Replies are JSON: the request's data with 200, or `{"error": "…"}`. With
+//! `orchestrator.admin_token` set, `POST` routes require
+//! `Authorization: Bearer <token>`. Connections are closed after one reply.
+
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::control::{ControlRequest, ControlResponse};
+
+/// Longest a client may take to send its request head.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest accepted request head (request line plus headers).
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Method, path and headers of a request; bodies are not used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A reply that is not the handler's own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rejection {
+    status: u16,
+    message: String,
+    /// Extra header, e.g. `Allow` or `WWW-Authenticate`
+    header: Option<(&'static str, &'static str)>,
+}
+
+impl Rejection {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            header: None,
+        }
+    }
+
+    fn with_header(mut self, name: &'static str, value: &'static str) -> Self {
+        self.header = Some((name, value));
+        self
+    }
+}
+
+/// Map a method and path onto a control request.
+fn route(method: &str, path: &str) -> Result<ControlRequest, Rejection> {
+    // Queries are not used by any route
+    let path = path.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path
+        .strip_prefix("/admin/")
+        .map(|rest| rest.trim_end_matches('/').split('/').collect())
+        .unwrap_or_default();
+
+    let (expected, request) = match segments.as_slice() {
+        ["functions"] => ("GET", ControlRequest::ListFunctions),
+        ["functions", id] if !id.is_empty() => (
+            "GET",
+            ControlRequest::GetFunction {
+                function_id: id.to_string(),
+            },
+        ),
+        ["functions", id, "snapshot"] if !id.is_empty() => (
+            "POST",
+            ControlRequest::Snapshot {
+                function_id: id.to_string(),
+            },
+        ),
+        ["functions", id, "restore"] if !id.is_empty() => (
+            "POST",
+            ControlRequest::Restore {
+                function_id: id.to_string(),
+            },
+        ),
+        ["shutdown"] => ("POST", ControlRequest::Shutdown),
+        _ => return Err(Rejection::new(404, format!("No route for {}", path))),
+    };
+
+    if method != expected {
+        return Err(
+            Rejection::new(405, format!("{} {} is not supported", method, path))
+                .with_header("Allow", expected),
+        );
+    }
+    Ok(request)
+}
+
+/// Whether a request may run. Only `POST` routes change anything, so
+/// only they need the token.
+fn authorize(request: &HttpRequest, token: Option<&str>) -> Result<(), Rejection> {
+    let Some(token) = token else { return Ok(()) };
+    if request.method != "POST" {
+        return Ok(());
+    }
+
+    let presented = request
+        .header("Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    match presented {
+        Some(presented) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => Ok(()),
+        _ => Err(Rejection::new(401, "Missing or invalid bearer token")
+            .with_header("WWW-Authenticate", "Bearer")),
+    }
+}
+
+/// Compare without an early exit, so timing does not reveal how much of
+/// the token matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Status code for a handler's reply. Reads can only fail on an unknown
+/// function; actions fail when the function is in the wrong state for them.
+fn status_for(request: &ControlRequest, response: &ControlResponse) -> u16 {
+    match (request, response) {
+        (_, ControlResponse::Ok { .. }) => 200,
+        (ControlRequest::GetFunction { .. }, ControlResponse::Error { .. }) => 404,
+        (_, ControlResponse::Error { .. }) => 409,
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        431 => "Request Header Fields Too Large",
+        _ => "Error",
+    }
+}
+
+/// Read the request head. `Ok(None)` if the client closed without sending.
+async fn read_request(stream: &mut BufReader<TcpStream>) -> io::Result<Option<HttpRequest>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let mut head_bytes = 0;
+    let request_line = read_head_line(stream, &mut head_bytes).await?;
+    if request_line.is_empty() {
+        return Ok(None);
+    }
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("malformed request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_head_line(stream, &mut head_bytes).await?;
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("malformed header"))?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    Ok(Some(HttpRequest {
+        method,
+        path,
+        headers,
+    }))
+}
+
+/// Read one line of the request head, counting it against [`MAX_HEAD_BYTES`].
+async fn read_head_line(
+    stream: &mut BufReader<TcpStream>,
+    head_bytes: &mut usize,
+) -> io::Result<String> {
+    let mut line = String::new();
+    // Bound the read itself so one endless line cannot grow without limit
+    let remaining = (MAX_HEAD_BYTES + 1).saturating_sub(*head_bytes) as u64;
+    let read = (&mut *stream).take(remaining).read_line(&mut line).await?;
+    *head_bytes += read;
+    if *head_bytes > MAX_HEAD_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::OutOfMemory,
+            "request head too large",
+        ));
+    }
+    Ok(line)
+}
+
+async fn write_response(
+    stream: &mut BufReader<TcpStream>,
+    status: u16,
+    header: Option<(&str, &str)>,
+    body: &Value,
+) -> io::Result<()> {
+    let body = serde_json::to_vec(body)?;
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        reason_phrase(status),
+        body.len()
+    );
+    if let Some((name, value)) = header {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let stream = stream.get_mut();
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await
+}
+
+/// Answer admin requests on `listener`, passing routed requests to `handle`.
+pub async fn serve<F, Fut>(listener: TcpListener, token: Option<String>, handle: F)
+where
+    F: Fn(ControlRequest) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = ControlResponse> + Send,
+{
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                tracing::warn!(error = %e, "Admin API accept failed");
+                continue;
+            }
+        };
+
+        let token = token.clone();
+        let handle = handle.clone();
+        tokio::spawn(async move {
+            let mut stream = BufReader::new(stream);
+            let request = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await
+            {
+                Ok(Ok(Some(request))) => request,
+                Ok(Ok(None)) | Err(_) => return,
+                Ok(Err(e)) => {
+                    let status = if e.kind() == io::ErrorKind::OutOfMemory {
+                        431
+                    } else {
+                        400
+                    };
+                    let body = serde_json::json!({ "error": e.to_string() });
+                    let _ = write_response(&mut stream, status, None, &body).await;
+                    return;
+                }
+            };
+
+            let routed = route(&request.method, &request.path).and_then(|control| {
+                authorize(&request, token.as_deref())?;
+                Ok(control)
+            });
+            let (status, header, body) = match routed {
+                Ok(control) => {
+                    tracing::info!(peer = %peer, method = %request.method, path = %request.path, "Admin request");
+                    let response = handle(control.clone()).await;
+                    let status = status_for(&control, &response);
+                    let body = match response {
+                        ControlResponse::Ok { data } => data,
+                        ControlResponse::Error { message } => {
+                            serde_json::json!({ "error": message })
+                        }
+                    };
+                    (status, None, body)
+                }
+                Err(rejection) => (
+                    rejection.status,
+                    rejection.header,
+                    serde_json::json!({ "error": rejection.message }),
+                ),
+            };
+
+            if let Err(e) = write_response(&mut stream, status, header, &body).await {
+                tracing::debug!(error = %e, "Admin client went away before the reply");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(
+            route("GET", "/admin/functions"),
+            Ok(ControlRequest::ListFunctions)
+        );
+        assert_eq!(
+            route("GET", "/admin/functions/hello/?verbose=1"),
+            Ok(ControlRequest::GetFunction {
+                function_id: "hello".to_string()
+            })
+        );
+        assert_eq!(
+            route("POST", "/admin/functions/hello/snapshot"),
+            Ok(ControlRequest::Snapshot {
+                function_id: "hello".to_string()
+            })
+        );
+        assert_eq!(
+            route("POST", "/admin/functions/hello/restore"),
+            Ok(ControlRequest::Restore {
+                function_id: "hello".to_string()
+            })
+        );
+        assert_eq!(
+            route("POST", "/admin/shutdown"),
+            Ok(ControlRequest::Shutdown)
+        );
+
+        assert_eq!(route("GET", "/admin/shutdown").unwrap_err().status, 405);
+        assert_eq!(
+            route("DELETE", "/admin/functions/hello")
+                .unwrap_err()
+                .header,
+            Some(("Allow", "GET"))
+        );
+        for missing in ["/", "/admin", "/admin/functions//restore", "/functions"] {
+            assert_eq!(
+                route("GET", missing).unwrap_err().status,
+                404,
+                "{}",
+                missing
+            );
+        }
+    }
+
+    #[test]
+    fn test_authorize() {
+        let request = |method: &str, authorization: Option<&str>| HttpRequest {
+            method: method.to_string(),
+            path: "/admin/shutdown".to_string(),
+            headers: authorization
+                .map(|value| vec![("authorization".to_string(), value.to_string())])
+                .unwrap_or_default(),
+        };
+
+        // Without a configured token everything is allowed
+        assert!(authorize(&request("POST", None), None).is_ok());
+
+        let token = Some("s3cret");
+        assert!(authorize(&request("GET", None), token).is_ok());
+        assert!(authorize(&request("POST", Some("Bearer s3cret")), token).is_ok());
+        for presented in [
+            None,
+            Some("Bearer wrong"),
+            Some("s3cret"),
+            Some("Bearer s3cre"),
+        ] {
+            let rejection = authorize(&request("POST", presented), token).unwrap_err();
+            assert_eq!(rejection.status, 401);
+            assert_eq!(rejection.header, Some(("WWW-Authenticate", "Bearer")));
+        }
+    }
+
+    async fn send(address: std::net::SocketAddr, request: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).await.unwrap();
+
+        let (head, body) = reply.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(serve(
+            listener,
+            Some("s3cret".to_string()),
+            |request| async move {
+                match request {
+                    ControlRequest::ListFunctions => ControlResponse::Ok {
+                        data: serde_json::json!([{ "id": "hello" }]),
+                    },
+                    ControlRequest::GetFunction { function_id } => ControlResponse::Error {
+                        message: format!("Function '{}' not found", function_id),
+                    },
+                    _ => ControlResponse::Ok {
+                        data: serde_json::json!("shutting_down"),
+                    },
+                }
+            },
+        ));
+
+        let (status, body) =
+            send(address, "GET /admin/functions HTTP/1.1\r\nHost: x\r\n\r\n").await;
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["id"], "hello");
+
+        let (status, body) = send(address, "GET /admin/functions/nope HTTP/1.1\r\n\r\n").await;
+        assert_eq!(status, 404);
+        assert_eq!(body["error"], "Function 'nope' not found");
+
+        let (status, _) = send(address, "POST /admin/shutdown HTTP/1.1\r\n\r\n").await;
+        assert_eq!(status, 401);
+
+        let (status, body) = send(
+            address,
+            "POST /admin/shutdown HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body, "shutting_down");
+
+        let (status, _) = send(address, "garbage\r\n\r\n").await;
+        assert_eq!(status, 400);
+    }
+}
@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Ankit Kumar Pandey
+
+//! Control socket between a running orchestrator and the other commands.
+//!
+//! `aether up` listens on [`CONTROL_SOCKET`]. The protocol is JSON lines:
+//! the client sends one [`ControlRequest`] line, the orchestrator answers
+//! with one [`ControlResponse`] line and closes the connection.
+
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use aetherless_core::{ExitReason, FunctionConfig};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+/// Path of the orchestrator's control socket.
+///
+/// Lives next to the PID file rather than in the handler socket directory,
+/// which `aether up` wipes on startup.
+pub const CONTROL_SOCKET: &str = "/run/aetherless/control.sock";
+
+/// Longest a client waits for the orchestrator to answer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Request sent by a client command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// List registered functions with their state and PID.
+    ListFunctions,
+    /// One function's config, state machine metrics and PID.
+    GetFunction { function_id: String },
+    /// State machine metrics, as printed by `aether stats --json`.
+    GetStats,
+    /// Every function's config and state, as a `RegistrySnapshot`.
+    GetSnapshot,
+    /// Dump a function's running handler with CRIU.
+    Snapshot { function_id: String },
+    /// Restore a function from its CRIU snapshot.
+    Restore { function_id: String },
+    /// Register a function, or update it if registered, and start its
+    /// handler if needed. With `force` an existing handler is always
+    /// replaced. Answered with a [`DeployOutcome`].
+    Deploy {
+        config: Box<FunctionConfig>,
+        force: bool,
+    },
+    /// Stop all handlers and exit.
+    Shutdown,
+}
+
+impl ControlRequest {
+    /// How long a client waits for the reply. A deploy may wait out the
+    /// handler's READY timeout on top.
+    pub fn timeout(&self) -> Duration {
+        match self {
+            ControlRequest::Deploy { config, .. } => {
+                REQUEST_TIMEOUT + Duration::from_millis(config.ready_timeout_ms)
+            }
+            _ => REQUEST_TIMEOUT,
+        }
+    }
+}
+
+/// Reply from the orchestrator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ControlResponse {
+    Ok { data: serde_json::Value },
+    Error { message: String },
+}
+
+/// One entry of a `ListFunctions` reply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionSummary {
+    pub id: String,
+    pub trigger_port: u16,
+    pub memory_limit_mb: u64,
+    pub state: String,
+    /// Handler PID, if a handler process is currently running.
+    pub pid: Option<u32>,
+    /// How the most recent handler ended, if one has.
+    #[serde(default)]
+    pub last_exit: Option<ExitReason>,
+}
+
+/// What a `Deploy` request did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployOutcome {
+    /// The function was new and its handler was started.
+    Created,
+    /// The config changed; the handler was restarted if its command, port
+    /// or environment changed.
+    Updated,
+    /// The function was already deployed with this config.
+    Unchanged,
+    /// `force` replaced the running handler with a new one.
+    Replaced,
+}
+
+/// Errors talking to the orchestrator.
+#[derive(Debug, Error)]
+pub enum ControlError {
+    #[error("orchestrator not running")]
+    NotRunning,
+
+    #[error("Orchestrator did not answer within {}ms", .0.as_millis())]
+    Timeout(Duration),
+
+    #[error("Control socket I/O failed: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid control message: {0}")]
+    Protocol(#[from] serde_json::Error),
+
+    #[error("Orchestrator error: {0}")]
+    Remote(String),
+}
+
+/// Send a request to the running orchestrator and return the reply data.
+pub async fn request(request: &ControlRequest) -> Result<serde_json::Value, ControlError> {
+    request_at(Path::new(CONTROL_SOCKET), request).await
+}
+
+/// Send a request over the control socket at `path`.
+///
+/// A missing socket, or one nobody is listening on, is reported as
+/// [`ControlError::NotRunning`].
+pub async fn request_at(
+    path: &Path,
+    request: &ControlRequest,
+) -> Result<serde_json::Value, ControlError> {
+    let stream = match UnixStream::connect(path).await {
+        Ok(stream) => stream,
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            return Err(ControlError::NotRunning)
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let exchange = async {
+        let (read, mut write) = stream.into_split();
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        write.write_all(line.as_bytes()).await?;
+
+        let mut reply = String::new();
+        BufReader::new(read).read_line(&mut reply).await?;
+        Ok::<_, ControlError>(serde_json::from_str::<ControlResponse>(&reply)?)
+    };
+
+    let timeout = request.timeout();
+    match tokio::time::timeout(timeout, exchange).await {
+        Ok(Ok(ControlResponse::Ok { data })) => Ok(data),
+        Ok(Ok(ControlResponse::Error { message })) => Err(ControlError::Remote(message)),
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(ControlError::Timeout(timeout)),
+    }
+}
+
+/// Read one request line from an accepted connection.
+pub async fn read_request(
+    stream: &mut BufReader<UnixStream>,
+) -> Result<ControlRequest, ControlError> {
+    let mut line = String::new();
+    stream.read_line(&mut line).await?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Write one response line to an accepted connection.
+pub async fn write_response(
+    stream: &mut BufReader<UnixStream>,
+    response: &ControlResponse,
+) -> Result<(), ControlError> {
+    let mut line = serde_json::to_string(response)?;
+    line.push('\n');
+    stream.get_mut().write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aetherless_core::ConfigLoader;
+    use tokio::net::UnixListener;
+
+    #[tokio::test]
+    async fn test_request_round_trip() {
+        let dir = std::env::temp_dir().join(format!("aether-control-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.ctl");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(
+            request_at(&path, &ControlRequest::GetStats).await,
+            Err(ControlError::NotRunning)
+        ));
+
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = tokio::spawn(async move {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut stream = BufReader::new(stream);
+                let response = match read_request(&mut stream).await.unwrap() {
+                    ControlRequest::GetStats => ControlResponse::Ok {
+                        data: serde_json::json!({ "function_count": 0 }),
+                    },
+                    other => ControlResponse::Error {
+                        message: format!("unsupported: {:?}", other),
+                    },
+                };
+                write_response(&mut stream, &response).await.unwrap();
+            }
+        });
+
+        let data = request_at(&path, &ControlRequest::GetStats).await.unwrap();
+        assert_eq!(data["function_count"], 0);
+        assert!(matches!(
+            request_at(&path, &ControlRequest::Shutdown).await,
+            Err(ControlError::Remote(_))
+        ));
+
+        server.await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_deploy_request_encoding() {
+        let config = ConfigLoader::load_string(
+            r#"
+functions:
+  - id: deployed
+    memory_limit_mb: 64
+    trigger_port: 8080
+    handler_path: /bin/true
+    ready_timeout_ms: 2000
+"#,
+        )
+        .unwrap()
+        .functions
+        .remove(0);
+        let request = ControlRequest::Deploy {
+            config: Box::new(config),
+            force: true,
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["type"], "deploy");
+        assert_eq!(json["force"], true);
+        let decoded: ControlRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, request);
+        assert_eq!(request.timeout(), REQUEST_TIMEOUT + Duration::from_secs(2));
+        assert_eq!(ControlRequest::GetStats.timeout(), REQUEST_TIMEOUT);
+
+        assert_eq!(
+            serde_json::to_value(DeployOutcome::Unchanged).unwrap(),
+            "unchanged"
+        );
+    }
+}
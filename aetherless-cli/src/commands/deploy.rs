@@ -1,33 +1,61 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright 2025 Ankit Kumar Pandey
 
-//! `aether deploy` command - Deploy function configuration.
+//! `aether deploy` command - Deploy functions to a running orchestrator.
 //!
-//! Validates configuration and provides deployment instructions.
+//! Validates the file, then sends each function over the control socket.
+//! The orchestrator registers new functions and starts their handlers,
+//! updates changed ones, and leaves identical ones alone. `--force` replaces
+//! the handler of a function that is already deployed.
+//!
+//! Deployed functions live until the orchestrator stops; with `up --watch`,
+//! the next reload removes functions its own config file does not list.
 
 use aetherless_core::ConfigLoader;
 
-pub async fn execute(file: &str, _force: bool) -> Result<(), Box<dyn std::error::Error>> {
-    tracing::info!(file = %file, "Validating function configuration for deployment");
+use super::control::{self, ControlError, ControlRequest, DeployOutcome};
+
+pub async fn execute(file: &str, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!(file = %file, force = force, "Deploying function configuration");
 
     // Load and validate the function configuration
     let config = ConfigLoader::load_file(file)?;
-
     println!("✓ Configuration validated successfully");
-    println!();
-    println!("Functions ready for deployment:");
-    for func in &config.functions {
-        println!(
-            "  • {} (port: {}, memory: {}, handler: {})",
-            func.id, func.trigger_port, func.memory_limit, func.handler_path
-        );
-    }
 
-    println!();
-    println!("To start these functions, run:");
-    println!();
-    println!("  aether -c {} up --foreground", file);
-    println!();
+    let mut failed = 0;
+    for func in config.functions {
+        let id = func.id.clone();
+        let request = ControlRequest::Deploy {
+            config: Box::new(func),
+            force,
+        };
+        match control::request(&request).await {
+            Ok(data) => {
+                let outcome: DeployOutcome = serde_json::from_value(data)?;
+                let message = match outcome {
+                    DeployOutcome::Created => "created",
+                    DeployOutcome::Updated => "updated",
+                    DeployOutcome::Unchanged => "unchanged",
+                    DeployOutcome::Replaced => "replaced",
+                };
+                println!("  • {}: {}", id, message);
+            }
+            Err(ControlError::NotRunning) => {
+                return Err(format!(
+                    "Cannot deploy: orchestrator not running (start it with: aether -c {} up)",
+                    file
+                )
+                .into());
+            }
+            Err(e) => {
+                println!("  ✗ {}: {}", id, e);
+                failed += 1;
+            }
+        }
+    }
 
+    if failed > 0 {
+        return Err(format!("{} function(s) failed to deploy", failed).into());
+    }
     Ok(())
 }
@@ -3,47 +3,72 @@
 
 //! `aether down` command - Stop the orchestrator.
 //!
-//! Stops a running orchestrator by sending SIGTERM to the process.
+//! Sends a `Shutdown` request over the control socket and waits for the
+//! orchestrator to finish tearing down. If the socket does not answer but
+//! the daemon's PID file names a live orchestrator, falls back to SIGTERM.
 
-use std::fs;
-use std::process::Command;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
-const PID_FILE: &str = "/tmp/aetherless/orchestrator.pid";
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+
+use super::control::{self, ControlError, ControlRequest, CONTROL_SOCKET};
+use crate::daemon;
+
+/// How long to wait for the orchestrator to stop
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub async fn execute() -> Result<(), Box<dyn std::error::Error>> {
-    // Check if PID file exists
-    if let Ok(pid_str) = fs::read_to_string(PID_FILE) {
-        if let Ok(pid) = pid_str.trim().parse::<u32>() {
-            println!("Stopping orchestrator (PID: {})...", pid);
-
-            let status = Command::new("kill")
-                .arg("-TERM")
-                .arg(pid.to_string())
-                .status();
-
-            match status {
-                Ok(s) if s.success() => {
-                    // Clean up PID file
-                    let _ = fs::remove_file(PID_FILE);
-                    println!("✓ Orchestrator stopped");
-                }
-                Ok(_) => {
-                    println!("✗ Failed to stop orchestrator (process may have already exited)");
-                    let _ = fs::remove_file(PID_FILE);
-                }
-                Err(e) => {
-                    println!("✗ Error stopping orchestrator: {}", e);
-                }
+    // `None` for a foreground orchestrator, which has no PID file
+    let pid = daemon::read_pid_file();
+
+    match control::request(&ControlRequest::Shutdown).await {
+        Ok(_) => {}
+        Err(e) => match pid {
+            Some(pid) => {
+                println!("Control socket unavailable ({}), sending SIGTERM", e);
+                kill(pid, Signal::SIGTERM)?;
             }
-        } else {
-            println!("✗ Invalid PID file");
-        }
-    } else {
-        println!("No orchestrator running (PID file not found)");
-        println!();
-        println!("If you started the orchestrator with --foreground,");
-        println!("use Ctrl+C to stop it.");
+            None if matches!(e, ControlError::NotRunning) => {
+                println!("Orchestrator not running");
+                return Ok(());
+            }
+            None => return Err(e.into()),
+        },
     }
 
+    println!("Stopping orchestrator...");
+    wait_for_exit(pid).await?;
+
+    // A daemon stopped by SIGTERM may not have removed its PID file
+    if pid.is_some() {
+        let _ = std::fs::remove_file(daemon::PID_FILE);
+    }
+
+    println!("✓ Orchestrator stopped");
     Ok(())
 }
+
+/// Wait until the daemon process exits or, without a PID, until teardown
+/// removes the control socket.
+async fn wait_for_exit(pid: Option<Pid>) -> Result<(), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    loop {
+        let running = match pid {
+            Some(pid) => kill(pid, None).is_ok(),
+            None => Path::new(CONTROL_SOCKET).exists(),
+        };
+        if !running {
+            return Ok(());
+        }
+        if started.elapsed() >= SHUTDOWN_TIMEOUT {
+            return Err(format!(
+                "Orchestrator did not stop within {}s",
+                SHUTDOWN_TIMEOUT.as_secs()
+            )
+            .into());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
@@ -0,0 +1,287 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Ankit Kumar Pandey
+
+//! `aether invoke` command - Call a running function over HTTP.
+//!
+//! Sends a single HTTP/1.1 request to the function's trigger port (or an
+//! explicit `--port`) and prints the status line, headers and body.
+
+use std::time::Duration;
+
+use aetherless_core::ConfigLoader;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Request options for `aether invoke`.
+pub struct InvokeArgs {
+    pub function_id: String,
+    pub method: String,
+    pub path: String,
+    pub body: Option<String>,
+    pub headers: Vec<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    pub timeout: Duration,
+}
+
+/// Parsed HTTP response.
+struct Response {
+    status: u16,
+    status_line: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+pub async fn execute(
+    config_path: &str,
+    args: InvokeArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let port = match args.port {
+        Some(port) => port,
+        None => {
+            let config = ConfigLoader::load_file(config_path)?;
+            config
+                .functions
+                .iter()
+                .find(|f| f.id.as_str() == args.function_id)
+                .map(|f| f.trigger_port.value())
+                .ok_or_else(|| {
+                    format!(
+                        "Function '{}' not found in {} (use --port to target it directly)",
+                        args.function_id, config_path
+                    )
+                })?
+        }
+    };
+
+    let request = build_request(&args)?;
+    let response = tokio::time::timeout(args.timeout, send(&args.host, port, &request))
+        .await
+        .map_err(|_| {
+            format!(
+                "Function '{}' did not respond within {}ms",
+                args.function_id,
+                args.timeout.as_millis()
+            )
+        })?
+        .map_err(|e| {
+            format!(
+                "Failed to reach function '{}' at {}:{}: {} (is `aether up` running?)",
+                args.function_id, args.host, port, e
+            )
+        })?;
+    let response = parse_response(&response)?;
+
+    println!("{}", response.status_line);
+    for (name, value) in &response.headers {
+        println!("{}: {}", name, value);
+    }
+    println!();
+    println!("{}", String::from_utf8_lossy(&response.body));
+
+    match response.status {
+        200..=299 => Ok(()),
+        404 => Err(format!(
+            "Function '{}' returned 404 Not Found for {}",
+            args.function_id, args.path
+        )
+        .into()),
+        status => Err(format!("Function '{}' returned HTTP {}", args.function_id, status).into()),
+    }
+}
+
+/// Serialize the request with `Connection: close` so the response ends at EOF.
+fn build_request(args: &InvokeArgs) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let path = if args.path.starts_with('/') {
+        args.path.clone()
+    } else {
+        format!("/{}", args.path)
+    };
+
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nUser-Agent: aether/{}\r\n",
+        args.method.to_ascii_uppercase(),
+        path,
+        args.host,
+        env!("CARGO_PKG_VERSION")
+    );
+    for header in &args.headers {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid header '{}' (expected 'Name: value')", header))?;
+        head.push_str(&format!("{}: {}\r\n", name.trim(), value.trim()));
+    }
+
+    let body = args.body.as_deref().unwrap_or("").as_bytes();
+    if args.body.is_some() {
+        head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    head.push_str("\r\n");
+
+    let mut request = head.into_bytes();
+    request.extend_from_slice(body);
+    Ok(request)
+}
+
+/// Send the request and read the whole response.
+async fn send(host: &str, port: u16, request: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut stream = TcpStream::connect((host, port)).await?;
+    stream.write_all(request).await?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+    Ok(response)
+}
+
+/// Split a raw response into status, headers and (de-chunked) body.
+fn parse_response(raw: &[u8]) -> Result<Response, Box<dyn std::error::Error>> {
+    let split = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("Malformed HTTP response: missing header terminator")?;
+    let head = String::from_utf8_lossy(&raw[..split]);
+    let mut body = raw[split + 4..].to_vec();
+
+    let mut lines = head.split("\r\n");
+    let status_line = lines.next().unwrap_or_default().to_string();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("Malformed HTTP status line: '{}'", status_line))?;
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let chunked = headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("transfer-encoding") && value.eq_ignore_ascii_case("chunked")
+    });
+    if chunked {
+        body = decode_chunked(&body)?;
+    }
+
+    Ok(Response {
+        status,
+        status_line,
+        headers,
+        body,
+    })
+}
+
+/// Decode a `Transfer-Encoding: chunked` body.
+fn decode_chunked(mut data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = data
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or("Malformed chunked body")?;
+        let size_field = String::from_utf8_lossy(&data[..line_end]);
+        let size_hex = size_field.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| format!("Malformed chunk size '{}'", size_hex))?;
+        data = &data[line_end + 2..];
+
+        if size == 0 {
+            return Ok(body);
+        }
+        if data.len() < size {
+            return Err("Truncated chunked body".into());
+        }
+        body.extend_from_slice(&data[..size]);
+        data = data.get(size + 2..).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(path: &str, body: Option<&str>, headers: &[&str]) -> InvokeArgs {
+        InvokeArgs {
+            function_id: "hello".to_string(),
+            method: "post".to_string(),
+            path: path.to_string(),
+            body: body.map(str::to_string),
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            host: "127.0.0.1".to_string(),
+            port: None,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[test]
+    fn test_build_request() {
+        let request = build_request(&args("items", Some("{}"), &["X-Trace:  abc "])).unwrap();
+        let request = String::from_utf8(request).unwrap();
+        assert!(request.starts_with("POST /items HTTP/1.1\r\nHost: 127.0.0.1\r\n"));
+        assert!(request.contains("\r\nX-Trace: abc\r\n"));
+        assert!(request.ends_with("Content-Length: 2\r\n\r\n{}"));
+
+        let request = build_request(&args("/", None, &[])).unwrap();
+        let request = String::from_utf8(request).unwrap();
+        assert!(request.starts_with("POST / HTTP/1.1\r\n"));
+        assert!(!request.contains("Content-Length"));
+
+        assert!(build_request(&args("/", None, &["no-colon"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let response =
+            parse_response(b"HTTP/1.1 201 Created\r\nContent-Type: text/plain\r\n\r\nhi").unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(response.status_line, "HTTP/1.1 201 Created");
+        assert_eq!(
+            response.headers,
+            vec![("Content-Type".to_string(), "text/plain".to_string())]
+        );
+        assert_eq!(response.body, b"hi");
+
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n").is_err());
+        assert!(parse_response(b"garbage\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn test_decode_chunked() {
+        let response = parse_response(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n1;ext=1\r\n \r\n5\r\nworld\r\n0\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(response.body, b"hello world");
+
+        assert!(decode_chunked(b"a\r\nshort").is_err());
+        assert!(decode_chunked(b"zz\r\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_an_error() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).await.unwrap();
+                assert!(n > 0);
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let mut args = args("/missing", None, &[]);
+        args.port = Some(port);
+        let error = execute("unused.yaml", args).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Function 'hello' returned 404 Not Found for /missing"
+        );
+        server.await.unwrap();
+    }
+}
@@ -1,15 +1,37 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright 2025 Ankit Kumar Pandey
 
-//! `aether list` command - List functions from configuration.
+//! `aether list` command - List functions.
 //!
-//! Lists functions defined in the configuration file.
+//! Lists the functions of a running orchestrator, or those defined in the
+//! configuration file if none is running.
 
 use aetherless_core::ConfigLoader;
 
-pub async fn execute(config_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+use super::control::{self, ControlError, ControlRequest, FunctionSummary};
+use super::OutputFormat;
+
+pub async fn execute(
+    config_path: &str,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match control::request(&ControlRequest::ListFunctions).await {
+        Ok(data) => {
+            let functions: Vec<FunctionSummary> = serde_json::from_value(data)?;
+            print_running(&functions, format)?;
+            return Ok(());
+        }
+        Err(ControlError::NotRunning) => {}
+        Err(e) => return Err(e.into()),
+    }
+
     let config = ConfigLoader::load_file(config_path)?;
 
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&config.functions)?);
+        return Ok(());
+    }
+
     if config.functions.is_empty() {
         println!("No functions defined in configuration.");
         return Ok(());
@@ -44,3 +66,40 @@ pub async fn execute(config_path: &str) -> Result<(), Box<dyn std::error::Error>
 
     Ok(())
 }
+
+/// Print the functions reported by a running orchestrator.
+fn print_running(
+    functions: &[FunctionSummary],
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(functions)?);
+        return Ok(());
+    }
+
+    println!("╔═══════════════════╦════════════╦═══════════════════╦══════════╦════════════════════════════════╗");
+    println!("║ ID                ║ Port       ║ State             ║ PID      ║ Last exit                      ║");
+    println!("╠═══════════════════╬════════════╬═══════════════════╬══════════╬════════════════════════════════╣");
+    for func in functions {
+        let pid = func
+            .pid
+            .map(|pid| pid.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let last_exit = func
+            .last_exit
+            .map(|reason| reason.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "║ {:<17} ║ {:<10} ║ {:<17} ║ {:<8} ║ {:<30} ║",
+            func.id, func.trigger_port, func.state, pid, last_exit
+        );
+    }
+    println!("╚═══════════════════╩════════════╩═══════════════════╩══════════╩════════════════════════════════╝");
+    println!();
+    println!(
+        "Total: {} function(s) registered in orchestrator",
+        functions.len()
+    );
+
+    Ok(())
+}
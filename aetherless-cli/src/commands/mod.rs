@@ -3,9 +3,32 @@
 
 //! CLI command modules.
 
+pub mod admin;
+pub mod control;
 pub mod deploy;
 pub mod down;
+pub mod invoke;
 pub mod list;
 pub mod stats;
 pub mod up;
 pub mod validate;
+
+/// Output format shared by commands that support `--json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable tables.
+    Human,
+    /// Pretty-printed JSON on stdout.
+    Json,
+}
+
+impl OutputFormat {
+    /// Select the format from a `--json` flag.
+    pub fn from_json_flag(json: bool) -> Self {
+        if json {
+            Self::Json
+        } else {
+            Self::Human
+        }
+    }
+}
@@ -5,9 +5,50 @@
 //!
 //! Displays runtime metrics when connected to the orchestrator.
 
+use std::time::Duration;
+
+use aetherless_core::{ConfigLoader, FunctionRegistry};
+
+use super::control::{self, ControlError, ControlRequest};
+use super::OutputFormat;
 use crate::tui;
 
-pub async fn execute(watch: bool, dashboard: bool) -> Result<(), Box<dyn std::error::Error>> {
+/// Refresh interval for `aether stats --watch`
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Fetch live stats, or `None` if no orchestrator is running.
+async fn live_stats() -> Result<Option<serde_json::Value>, ControlError> {
+    match control::request(&ControlRequest::GetStats).await {
+        Ok(data) => Ok(Some(data)),
+        Err(ControlError::NotRunning) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn execute(
+    config_path: &str,
+    watch: bool,
+    dashboard: bool,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if format == OutputFormat::Json {
+        if let Some(document) = live_stats().await? {
+            println!("{}", serde_json::to_string_pretty(&document)?);
+            return Ok(());
+        }
+
+        // Without a live orchestrator, report the configured functions in
+        // their initial state.
+        let config = ConfigLoader::load_file(config_path)?;
+        let registry = FunctionRegistry::new();
+        for function in config.functions {
+            registry.register(function)?;
+        }
+        let document = stats_document(&registry, false);
+        println!("{}", serde_json::to_string_pretty(&document)?);
+        return Ok(());
+    }
+
     if dashboard {
         // Run the TUI dashboard
         tui::run_dashboard().await?;
@@ -15,10 +56,24 @@ pub async fn execute(watch: bool, dashboard: bool) -> Result<(), Box<dyn std::er
     }
 
     if watch {
-        println!("Stats watch mode requires a running orchestrator.");
-        println!();
-        println!("Start the orchestrator first:");
-        println!("  aether -c config.yaml up --foreground");
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(document) = live_stats().await? else {
+                println!("Stats watch mode requires a running orchestrator.");
+                println!();
+                println!("Start the orchestrator first:");
+                println!("  aether -c config.yaml up --foreground");
+                return Ok(());
+            };
+            // Clear the screen before each refresh
+            print!("\x1B[2J\x1B[H");
+            print_stats(&document);
+        }
+    }
+
+    if let Some(document) = live_stats().await? {
+        print_stats(&document);
         return Ok(());
     }
 
@@ -35,3 +90,81 @@ pub async fn execute(watch: bool, dashboard: bool) -> Result<(), Box<dyn std::er
 
     Ok(())
 }
+
+/// Print a stats document as a table.
+fn print_stats(document: &serde_json::Value) {
+    let empty = Vec::new();
+    let functions = document["functions"].as_array().unwrap_or(&empty);
+
+    println!("╔═══════════════════╦═══════════════╦══════════════╦═════════════╦══════════╗");
+    println!("║ ID                ║ State         ║ In state     ║ Transitions ║ Restarts ║");
+    println!("╠═══════════════════╬═══════════════╬══════════════╬═════════════╬══════════╣");
+    for function in functions {
+        println!(
+            "║ {:<17} ║ {:<13} ║ {:>10}ms ║ {:>11} ║ {:>8} ║",
+            function["function_id"].as_str().unwrap_or("?"),
+            function["current_state"].as_str().unwrap_or("?"),
+            function["time_in_state_ms"].as_u64().unwrap_or(0),
+            function["transition_count"].as_u64().unwrap_or(0),
+            function["restart_count"].as_u64().unwrap_or(0),
+        );
+    }
+    println!("╚═══════════════════╩═══════════════╩══════════════╩═════════════╩══════════╝");
+    println!();
+    println!("Total: {} function(s)", functions.len());
+}
+
+/// JSON document printed by `aether stats --json`.
+pub fn stats_document(
+    registry: &FunctionRegistry,
+    orchestrator_running: bool,
+) -> serde_json::Value {
+    let mut functions = registry.metrics();
+    functions.sort_by(|a, b| a.function_id.cmp(&b.function_id));
+
+    serde_json::json!({
+        "orchestrator_running": orchestrator_running,
+        "function_count": functions.len(),
+        "functions": functions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_document_keys() {
+        let config = ConfigLoader::load_string(
+            r#"
+functions:
+  - id: api
+    memory_limit_mb: 64
+    trigger_port: 8080
+    handler_path: /bin/echo
+"#,
+        )
+        .unwrap();
+        let registry = FunctionRegistry::new();
+        for function in config.functions {
+            registry.register(function).unwrap();
+        }
+
+        let json = serde_json::to_string(&stats_document(&registry, false)).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed["orchestrator_running"], false);
+        assert_eq!(parsed["function_count"], 1);
+        let function = &parsed["functions"][0];
+        assert_eq!(function["function_id"], "api");
+        assert_eq!(function["current_state"], "Uninitialized");
+        for key in [
+            "time_in_state_ms",
+            "transition_count",
+            "restart_count",
+            "last_transition_secs_ago",
+        ] {
+            assert!(function.get(key).is_some(), "missing key {}", key);
+        }
+    }
+}
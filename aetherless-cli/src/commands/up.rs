@@ -4,33 +4,154 @@
 //! `aether up` command - Start the orchestrator.
 //!
 //! Spawns handler processes, creates Unix sockets, and waits for READY signals.
+//! Without `--foreground` this runs in the daemon started by `main`.
 
+use nix::errno::Errno;
+use nix::sys::prctl;
+use nix::sys::signal::{kill, Signal};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
 use std::collections::HashMap;
-use std::io::Read;
-use std::os::unix::net::UnixListener;
+use std::io::{BufRead, BufReader};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::ExitStatusExt;
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command, Stdio};
-use std::sync::Arc;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use tokio::io::AsyncReadExt;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{Mutex, Notify};
+use tracing::Instrument;
 
-use aetherless_core::{ConfigLoader, FunctionConfig, FunctionRegistry, FunctionState};
+use aetherless_core::cgroup::{CgroupParent, CGROUP_ROOT, CONTROLLERS};
+use aetherless_core::criu::{check_snapshot_dir, health_check_stream, SnapshotManager};
+use aetherless_core::{
+    ConfigLoader, Endpoint, ExitReason, FunctionConfig, FunctionId, FunctionRegistry,
+    FunctionState, HandshakeMode, ProcessId,
+};
 
-/// Timeout waiting for READY signal from handler
-const READY_TIMEOUT: Duration = Duration::from_secs(30);
+use super::admin;
+use super::control::{
+    self, ControlError, ControlRequest, ControlResponse, DeployOutcome, FunctionSummary,
+    CONTROL_SOCKET,
+};
+use super::stats::stats_document;
+use crate::daemon;
+
+/// How often the config file is polled for changes in watch mode
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the supervisor checks for exited handlers
+const SUPERVISE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Delay before the first restart of a handler; doubled on each further restart
+const RESTART_BACKOFF_BASE: Duration = Duration::from_millis(200);
+
+/// Longest a handler may take to answer a health ping
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long handlers get to finish in-flight requests after SIGTERM before
+/// they are killed
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Longest to wait for a killed handler to be reaped before giving up on it
+const REAP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often an exiting handler is polled while waiting to reap it
+const REAP_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Parent cgroup for handler groups, set up on first use; `None` if cgroup
+/// v2 is unavailable, in which case limits are not enforced
+static CGROUP_PARENT: OnceLock<Option<CgroupParent>> = OnceLock::new();
+
+/// A handler process the orchestrator can wait on.
+enum HandlerProcess {
+    /// Spawned by the orchestrator.
+    Spawned(Child),
+    /// Restored by CRIU; reparented to the orchestrator, which is a child
+    /// subreaper.
+    Restored(Pid),
+}
+
+impl HandlerProcess {
+    fn try_wait(&mut self) -> std::io::Result<Option<ExitStatus>> {
+        match self {
+            Self::Spawned(child) => child.try_wait(),
+            Self::Restored(pid) => wait_restored(*pid, Some(WaitPidFlag::WNOHANG)),
+        }
+    }
+
+    fn kill(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Spawned(child) => child.kill(),
+            Self::Restored(pid) => Ok(kill(*pid, Signal::SIGKILL)?),
+        }
+    }
+
+    fn id(&self) -> u32 {
+        match self {
+            Self::Spawned(child) => child.id(),
+            Self::Restored(pid) => pid.as_raw() as u32,
+        }
+    }
+
+    /// Poll for exit until `timeout` passes, without blocking the runtime.
+    ///
+    /// Returns `Ok(None)` if the process is still running at the deadline.
+    async fn wait_timeout(&mut self, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.try_wait()? {
+                None if Instant::now() < deadline => tokio::time::sleep(REAP_POLL_INTERVAL).await,
+                status => return Ok(status),
+            }
+        }
+    }
+
+    /// SIGKILL the process and reap it, giving up after [`REAP_TIMEOUT`].
+    async fn kill_and_reap(&mut self) {
+        let _ = self.kill();
+        match self.wait_timeout(REAP_TIMEOUT).await {
+            Ok(Some(_)) => {}
+            Ok(None) => tracing::warn!(pid = self.id(), "Killed handler did not exit in time"),
+            Err(e) => tracing::warn!(pid = self.id(), error = %e, "Failed to reap handler"),
+        }
+    }
+}
+
+/// `waitpid` for a restored handler, as an [`ExitStatus`].
+///
+/// If the process is not our child (subreaper unavailable) its exit status
+/// is unknown, and it is reported as a successful exit once it is gone.
+fn wait_restored(pid: Pid, flags: Option<WaitPidFlag>) -> std::io::Result<Option<ExitStatus>> {
+    match waitpid(pid, flags) {
+        Ok(WaitStatus::Exited(_, code)) => Ok(Some(ExitStatus::from_raw(code << 8))),
+        Ok(WaitStatus::Signaled(_, signal, _)) => Ok(Some(ExitStatus::from_raw(signal as i32))),
+        Ok(_) => Ok(None),
+        Err(Errno::ECHILD) if kill(pid, None).is_err() => Ok(Some(ExitStatus::default())),
+        Err(Errno::ECHILD) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
 
 #[allow(dead_code)]
 struct RunningProcess {
-    child: Child,
+    child: HandlerProcess,
     config: FunctionConfig,
     pid: u32,
+    /// Control socket kept open after READY (socket handshake only)
+    control: Option<UnixStream>,
+    /// The function's cgroup OOM kill count when the handler started
+    oom_kills: Option<u64>,
 }
 
 pub async fn execute(
     config_path: &str,
     foreground: bool,
+    watch: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    tracing::info!(config = %config_path, foreground = %foreground, "Starting orchestrator");
+    tracing::info!(config = %config_path, foreground = %foreground, watch = %watch, "Starting orchestrator");
 
     // Load and validate configuration - fail fast on invalid config
     let config = ConfigLoader::load_file(config_path)?;
@@ -40,9 +161,66 @@ pub async fn execute(
         "Configuration validated successfully"
     );
 
+    // Wiping the socket directory would cut a live orchestrator off from
+    // its handlers, so make sure there is none
+    match control::request(&ControlRequest::GetStats).await {
+        Ok(_) => {
+            return Err(format!(
+                "Orchestrator already running (control socket: {})",
+                CONTROL_SOCKET
+            )
+            .into())
+        }
+        Err(ControlError::NotRunning) => {}
+        Err(e) => {
+            return Err(format!("Cannot check for a running orchestrator: {}", e).into());
+        }
+    }
+
     // Create the function registry
     let registry = FunctionRegistry::new_shared();
 
+    // A slow snapshot filesystem only warns unless the config requires tmpfs
+    if config.orchestrator.require_memory_snapshot_dir {
+        check_snapshot_dir(&config.orchestrator.snapshot_dir).map_err(|e| e.to_string())?;
+    }
+
+    // Snapshots are optional: without CRIU, snapshot and restore requests
+    // are refused but everything else works
+    let snapshots = match SnapshotManager::new(
+        &config.orchestrator.snapshot_dir,
+        config.orchestrator.restore_timeout_ms,
+        config.orchestrator.criu.clone(),
+    ) {
+        Ok(manager) => {
+            let mut manager = match config.orchestrator.max_snapshot_bytes {
+                Some(budget) => manager.with_max_snapshot_bytes(budget),
+                None => manager,
+            };
+            // Probe now so lazy restores don't run `criu check` under the
+            // manager lock
+            tracing::info!(
+                lazy_pages = manager.probe_lazy_pages(),
+                "Probed CRIU restore support"
+            );
+            // Reuse snapshots left by a previous orchestrator
+            match manager.rehydrate() {
+                Ok(count) => tracing::info!(count, "Rehydrated snapshots"),
+                Err(e) => tracing::warn!(error = %e, "Failed to rehydrate snapshots"),
+            }
+            // Restored handlers are reparented to us so they can be reaped
+            if let Err(e) = prctl::set_child_subreaper(true) {
+                tracing::warn!(error = %e, "Failed to become child subreaper");
+            }
+            Some(manager)
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Snapshots unavailable");
+            None
+        }
+    };
+    let snapshots = Arc::new(Mutex::new(snapshots));
+
     // Create socket directory
     let socket_dir = PathBuf::from("/tmp/aetherless");
     if socket_dir.exists() {
@@ -50,6 +228,22 @@ pub async fn execute(
     }
     std::fs::create_dir_all(&socket_dir)?;
 
+    // Bind the listeners before spawning anything, so a failure leaves no
+    // handler running. Nobody answers on a leftover socket, per the check above.
+    std::fs::create_dir_all(daemon::RUN_DIR)?;
+    let _ = std::fs::remove_file(CONTROL_SOCKET);
+    let control_listener = tokio::net::UnixListener::bind(CONTROL_SOCKET)?;
+    let admin_listener = match config.orchestrator.admin_listen {
+        Some(address) => match tokio::net::TcpListener::bind(address).await {
+            Ok(listener) => Some((address, listener)),
+            Err(e) => {
+                let _ = std::fs::remove_file(CONTROL_SOCKET);
+                return Err(e.into());
+            }
+        },
+        None => None,
+    };
+
     // Track running processes
     let processes: Arc<Mutex<HashMap<String, RunningProcess>>> =
         Arc::new(Mutex::new(HashMap::new()));
@@ -59,14 +253,31 @@ pub async fn execute(
     println!("╚══════════════════════════════════════════════════════════════╝");
     println!();
 
+    // Register everything before spawning anything, so a conflict leaves
+    // no handler running
+    registry.register_all(config.functions.clone())?;
+
+    // Functions with a rehydrated snapshot start warm and are restored on
+    // demand instead of being spawned cold
+    if let Some(manager) = snapshots.lock().await.as_ref() {
+        for func_config in &config.functions {
+            if manager.has_snapshot(&func_config.id) {
+                registry.transition(&func_config.id, FunctionState::WarmSnapshot)?;
+            }
+        }
+    }
+
     // Spawn all function handlers
     for func_config in &config.functions {
+        if registry.get_state(&func_config.id).ok() == Some(FunctionState::WarmSnapshot) {
+            println!("❄ {} warm from snapshot", func_config.id);
+            continue;
+        }
         println!("▶ Spawning function: {}", func_config.id);
-        registry.register(func_config.clone())?;
 
         // Spawn the handler process with Unix socket handshake
-        match spawn_handler_with_socket(func_config, &socket_dir).await {
-            Ok((child, pid)) => {
+        match spawn_handler(func_config, &socket_dir).await {
+            Ok((child, pid, control)) => {
                 println!(
                     "  ✓ {} started (PID: {}, Port: {})",
                     func_config.id, pid, func_config.trigger_port
@@ -74,14 +285,17 @@ pub async fn execute(
 
                 // Update state to Running
                 registry.transition(&func_config.id, FunctionState::Running)?;
+                add_endpoint(&registry, func_config, pid);
 
                 // Track the process
                 processes.lock().await.insert(
                     func_config.id.to_string(),
                     RunningProcess {
-                        child,
+                        child: HandlerProcess::Spawned(child),
                         config: func_config.clone(),
                         pid,
+                        control,
+                        oom_kills: oom_kills(&func_config.id),
                     },
                 );
             }
@@ -126,51 +340,940 @@ pub async fn execute(
 
     println!("╚══════════════════════════════════════════════════════════════╝");
 
+    // Serializes deploys, reloads and restarts, so only one of them at a
+    // time can start a handler for a function
+    let deploying = Arc::new(Mutex::new(()));
+
+    tokio::spawn(supervise(
+        Arc::clone(&registry),
+        Arc::clone(&processes),
+        socket_dir.clone(),
+        Arc::clone(&deploying),
+    ));
+    tokio::spawn(monitor_health(
+        Arc::clone(&registry),
+        Arc::clone(&processes),
+    ));
+
+    if watch {
+        tokio::spawn(watch_config(
+            PathBuf::from(config_path),
+            Arc::clone(&registry),
+            Arc::clone(&processes),
+            socket_dir.clone(),
+            Arc::clone(&deploying),
+        ));
+        println!();
+        println!("Watching {} for changes", config_path);
+    }
+
+    let shutdown = Arc::new(Notify::new());
+    let control = Control {
+        registry: Arc::clone(&registry),
+        processes: Arc::clone(&processes),
+        snapshots,
+        socket_dir: socket_dir.clone(),
+        deploying,
+        shutdown: Arc::clone(&shutdown),
+    };
+    tokio::spawn(serve_control(control_listener, control.clone()));
+
+    if let Some((address, listener)) = admin_listener {
+        println!();
+        println!("Admin API listening on http://{}", address);
+        tokio::spawn(admin::serve(
+            listener,
+            config.orchestrator.admin_token.clone(),
+            move |request| {
+                let control = control.clone();
+                async move { control.handle(request).await }
+            },
+        ));
+    }
+
     if foreground {
         println!();
         println!("Press Ctrl+C to stop...");
         println!();
+    }
 
-        // Wait for shutdown signal
-        tokio::signal::ctrl_c().await?;
+    // Wait for SIGINT (Ctrl+C), SIGTERM (systemd, Docker, `aether down`
+    // fallback) or a Shutdown request (control socket or admin API)
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sigint = signal(SignalKind::interrupt())?;
+    let reason = tokio::select! {
+        _ = sigterm.recv() => "SIGTERM",
+        _ = sigint.recv() => "SIGINT",
+        _ = shutdown.notified() => "shutdown request",
+    };
 
-        println!();
-        println!("Shutting down...");
-        tracing::info!("Shutting down orchestrator");
+    println!();
+    println!("Shutting down...");
+    tracing::info!(reason = reason, "Shutting down orchestrator");
+
+    stop_handlers(&registry, &processes).await;
+
+    // Cleanup socket directory and control socket
+    let _ = std::fs::remove_dir_all(&socket_dir);
+    let _ = std::fs::remove_file(CONTROL_SOCKET);
+
+    println!();
+    println!("Orchestrator stopped.");
+
+    Ok(())
+}
+
+/// Stop all handlers gracefully.
+///
+/// Functions move to Draining and their handlers get SIGTERM, then up to
+/// [`SHUTDOWN_GRACE_PERIOD`] to finish in-flight requests and exit before
+/// being killed. Stopped functions end up Suspended.
+async fn stop_handlers(
+    registry: &FunctionRegistry,
+    processes: &Mutex<HashMap<String, RunningProcess>>,
+) {
+    // Take the handlers out of the map so the supervisor does not restart them
+    let mut stopping: Vec<(String, RunningProcess)> = processes.lock().await.drain().collect();
+    stopping.sort_by(|a, b| a.0.cmp(&b.0));
 
-        // Kill all child processes
-        let mut procs = processes.lock().await;
-        for (id, mut proc) in procs.drain() {
-            print!("  Stopping {}... ", id);
-            let _ = proc.child.kill();
-            let _ = proc.child.wait();
-            println!("done");
+    for (id, proc) in &stopping {
+        if registry.get_state(&proc.config.id).ok() == Some(FunctionState::Running) {
+            let _ = registry.transition(&proc.config.id, FunctionState::Draining);
         }
+        if let Err(e) = kill(Pid::from_raw(proc.pid as i32), Signal::SIGTERM) {
+            tracing::debug!(function_id = %id, error = %e, "Failed to send SIGTERM");
+        }
+    }
 
-        // Cleanup socket directory
-        let _ = std::fs::remove_dir_all(&socket_dir);
+    let deadline = Instant::now() + SHUTDOWN_GRACE_PERIOD;
+    while Instant::now() < deadline
+        && stopping
+            .iter_mut()
+            .any(|(_, proc)| matches!(proc.child.try_wait(), Ok(None)))
+    {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
 
-        println!();
-        println!("Orchestrator stopped.");
+    for (id, mut proc) in stopping {
+        print!("  Stopping {}... ", id);
+        let graceful = matches!(proc.child.try_wait(), Ok(Some(_)));
+        if !graceful {
+            proc.child.kill_and_reap().await;
+        }
+        if registry.get_state(&proc.config.id).ok() == Some(FunctionState::Draining) {
+            let _ = registry.transition(&proc.config.id, FunctionState::Suspended);
+        }
+        println!("{}", if graceful { "done" } else { "killed" });
+
+        // Empty now, so the kernel lets the group go
+        if let Some(Some(parent)) = CGROUP_PARENT.get() {
+            let _ = parent
+                .group(&proc.config.id)
+                .and_then(|group| group.remove());
+        }
     }
+}
 
-    Ok(())
+/// Everything control requests act on, shared by the control socket and
+/// the admin API.
+#[derive(Clone)]
+struct Control {
+    registry: Arc<FunctionRegistry>,
+    processes: Arc<Mutex<HashMap<String, RunningProcess>>>,
+    snapshots: Arc<Mutex<Option<SnapshotManager>>>,
+    socket_dir: PathBuf,
+    /// Deploys run one at a time so two of the same function cannot race
+    deploying: Arc<Mutex<()>>,
+    /// Woken by a `Shutdown` request, after it is acknowledged
+    shutdown: Arc<Notify>,
+}
+
+impl Control {
+    async fn handle(&self, request: ControlRequest) -> ControlResponse {
+        let (registry, processes) = (&self.registry, &self.processes);
+        let result = match request {
+            ControlRequest::ListFunctions => {
+                serde_json::to_value(list_functions(registry, processes).await)
+                    .map_err(|e| e.to_string())
+            }
+            ControlRequest::GetFunction { function_id } => {
+                function_details(&function_id, registry, processes).await
+            }
+            ControlRequest::GetStats => Ok(stats_document(registry, true)),
+            ControlRequest::GetSnapshot => {
+                serde_json::to_value(registry.snapshot()).map_err(|e| e.to_string())
+            }
+            ControlRequest::Snapshot { function_id } => {
+                let _deploying = self.deploying.lock().await;
+                snapshot_function(&function_id, registry, processes, &self.snapshots).await
+            }
+            ControlRequest::Restore { function_id } => {
+                restore_function(&function_id, registry, processes, &self.snapshots).await
+            }
+            ControlRequest::Deploy { config, force } => {
+                let _deploying = self.deploying.lock().await;
+                deploy_function(*config, force, registry, processes, &self.socket_dir)
+                    .await
+                    .map(|outcome| serde_json::json!(outcome))
+            }
+            ControlRequest::Shutdown => {
+                println!();
+                println!("Shutdown requested");
+                self.shutdown.notify_one();
+                Ok(serde_json::json!("shutting_down"))
+            }
+        };
+
+        match result {
+            Ok(data) => ControlResponse::Ok { data },
+            Err(message) => ControlResponse::Error { message },
+        }
+    }
+}
+
+/// Answer requests on the control socket.
+async fn serve_control(listener: tokio::net::UnixListener, control: Control) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                tracing::warn!(error = %e, "Control socket accept failed");
+                continue;
+            }
+        };
+
+        let control = control.clone();
+        tokio::spawn(async move {
+            let mut stream = tokio::io::BufReader::new(stream);
+            let response = match control::read_request(&mut stream).await {
+                Ok(request) => control.handle(request).await,
+                Err(e) => ControlResponse::Error {
+                    message: e.to_string(),
+                },
+            };
+
+            if let Err(e) = control::write_response(&mut stream, &response).await {
+                tracing::debug!(error = %e, "Control client went away before the reply");
+            }
+        });
+    }
+}
+
+/// Dump a running handler with CRIU and park the function in WarmSnapshot.
+///
+/// CRIU stops the handler as part of the dump, so it is taken out of
+/// supervision first; if the dump fails it is put back untouched. The caller
+/// holds the deploy lock so a reload or restart cannot swap the handler out
+/// from under the dump.
+async fn snapshot_function(
+    function_id: &str,
+    registry: &FunctionRegistry,
+    processes: &Mutex<HashMap<String, RunningProcess>>,
+    snapshots: &Mutex<Option<SnapshotManager>>,
+) -> Result<serde_json::Value, String> {
+    let mut snapshots = snapshots.lock().await;
+    let manager = snapshots
+        .as_mut()
+        .ok_or("Snapshots unavailable: CRIU not found")?;
+    let mut proc = processes
+        .lock()
+        .await
+        .remove(function_id)
+        .ok_or_else(|| format!("Function '{}' has no running handler", function_id))?;
+
+    // The handler may have been unregistered or suspended while we waited
+    // for the deploy lock
+    let id = proc.config.id.clone();
+    if registry.get_state(&id).ok() != Some(FunctionState::Running) {
+        processes.lock().await.insert(function_id.to_string(), proc);
+        return Err(format!("Function '{}' is not Running", function_id));
+    }
+
+    let dumped = manager.dump_async(&id, proc.pid).await;
+    release_evicted(manager, registry);
+    let metadata = match dumped {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            processes.lock().await.insert(function_id.to_string(), proc);
+            return Err(e.to_string());
+        }
+    };
+
+    proc.child.kill_and_reap().await;
+    let _ = registry.remove_endpoint(&id, proc.config.trigger_port);
+    registry
+        .transition(&id, FunctionState::WarmSnapshot)
+        .map_err(|e| e.to_string())?;
+
+    println!("  ❄ {} snapshotted ({} bytes)", id, metadata.size_bytes);
+    tracing::info!(function_id = %id, pid = proc.pid, size_bytes = metadata.size_bytes, "Handler snapshotted");
+    Ok(serde_json::json!({ "size_bytes": metadata.size_bytes }))
+}
+
+/// Move functions whose snapshots were evicted to fit the budget out of
+/// WarmSnapshot, since there is nothing left to restore them from.
+fn release_evicted(manager: &mut SnapshotManager, registry: &FunctionRegistry) {
+    for id in manager.take_evicted() {
+        if let Ok(true) = registry.transition_if(
+            &id,
+            FunctionState::WarmSnapshot,
+            FunctionState::Uninitialized,
+        ) {
+            println!("  ○ {} snapshot evicted", id);
+        }
+    }
+}
+
+/// Restore a function from its snapshot and resume supervising it.
+async fn restore_function(
+    function_id: &str,
+    registry: &FunctionRegistry,
+    processes: &Mutex<HashMap<String, RunningProcess>>,
+    snapshots: &Mutex<Option<SnapshotManager>>,
+) -> Result<serde_json::Value, String> {
+    let id = registry
+        .function_ids()
+        .into_iter()
+        .find(|id| id.as_str() == function_id)
+        .ok_or_else(|| format!("Function '{}' not found", function_id))?;
+    let config = registry.get_config(&id).map_err(|e| e.to_string())?;
+
+    // Claim the function so concurrent restore requests cannot both run CRIU
+    let claimed = registry
+        .transition_if(&id, FunctionState::WarmSnapshot, FunctionState::Running)
+        .map_err(|e| e.to_string())?;
+    if !claimed {
+        return Err(format!("Function '{}' is not in WarmSnapshot", function_id));
+    }
+
+    let restored = async {
+        // Release the manager before the CRIU call
+        let job = {
+            let mut snapshots = snapshots.lock().await;
+            let manager = snapshots
+                .as_mut()
+                .ok_or("Snapshots unavailable: CRIU not found")?;
+            manager.set_restore_mode(&id, config.restore_mode);
+            manager.prepare_restore(&id).map_err(|e| e.to_string())?
+        };
+        tracing::Span::current().record("mode", tracing::field::debug(job.mode()));
+        job.run().await.map_err(|e| e.to_string())
+    };
+    let span = tracing::info_span!(
+        "criu_restore",
+        function_id = %id,
+        mode = tracing::field::Empty,
+        pid = tracing::field::Empty,
+        elapsed_ms = tracing::field::Empty,
+    );
+    let start = Instant::now();
+    let restored = restored.instrument(span.clone()).await;
+    span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+    if let Ok(pid) = &restored {
+        span.record("pid", pid);
+    }
+    let pid = match restored {
+        Ok(pid) => pid,
+        Err(e) => {
+            let _ = registry.transition(&id, FunctionState::WarmSnapshot);
+            // A dump may have evicted the snapshot while we held the claim
+            if !snapshots
+                .lock()
+                .await
+                .as_ref()
+                .is_some_and(|manager| manager.has_snapshot(&id))
+            {
+                let _ = registry.transition(&id, FunctionState::Uninitialized);
+            }
+            return Err(e);
+        }
+    };
+
+    add_endpoint(registry, &config, pid);
+    processes.lock().await.insert(
+        function_id.to_string(),
+        RunningProcess {
+            child: HandlerProcess::Restored(Pid::from_raw(pid as i32)),
+            oom_kills: oom_kills(&config.id),
+            config,
+            pid,
+            control: None,
+        },
+    );
+
+    println!("  ☀ {} restored (PID: {})", id, pid);
+    tracing::info!(function_id = %id, pid = pid, "Handler restored");
+    Ok(serde_json::json!({ "pid": pid }))
+}
+
+/// Register or update a function sent by `aether deploy` and start its
+/// handler if it needs one.
+///
+/// A function whose handler fails to start is unregistered again if the
+/// deploy created it; an updated function keeps its new config, as a
+/// reload would.
+async fn deploy_function(
+    config: FunctionConfig,
+    force: bool,
+    registry: &FunctionRegistry,
+    processes: &Mutex<HashMap<String, RunningProcess>>,
+    socket_dir: &Path,
+) -> Result<DeployOutcome, String> {
+    let id = config.id.clone();
+
+    let outcome = match registry.get_config(&id) {
+        Err(_) => {
+            registry
+                .register(config.clone())
+                .map_err(|e| e.to_string())?;
+            DeployOutcome::Created
+        }
+        Ok(current) if force => {
+            if current != config {
+                registry
+                    .update_config(config.clone())
+                    .map_err(|e| e.to_string())?;
+            }
+            DeployOutcome::Replaced
+        }
+        Ok(current) if current == config => return Ok(DeployOutcome::Unchanged),
+        Ok(current) => {
+            registry
+                .update_config(config.clone())
+                .map_err(|e| e.to_string())?;
+            if !needs_respawn(&current, &config) {
+                println!("  ~ {} updated by deploy", id);
+                return Ok(DeployOutcome::Updated);
+            }
+            DeployOutcome::Updated
+        }
+    };
+
+    match replace_handler(&config, registry, processes, socket_dir).await {
+        Ok(pid) => {
+            println!("  ✓ {} deployed (PID: {}, {:?})", id, pid, outcome);
+            tracing::info!(function_id = %id, pid = pid, outcome = ?outcome, "Function deployed");
+            Ok(outcome)
+        }
+        Err(e) => {
+            if outcome == DeployOutcome::Created {
+                let _ = registry.unregister(&id);
+            }
+            println!("  ✗ {} deploy failed: {}", id, e);
+            Err(format!("Handler for '{}' failed to start: {}", id, e))
+        }
+    }
+}
+
+/// A function's config, state machine metrics and handler PID.
+async fn function_details(
+    function_id: &str,
+    registry: &FunctionRegistry,
+    processes: &Mutex<HashMap<String, RunningProcess>>,
+) -> Result<serde_json::Value, String> {
+    let not_found = || format!("Function '{}' not found", function_id);
+    let id = registry
+        .function_ids()
+        .into_iter()
+        .find(|id| id.as_str() == function_id)
+        .ok_or_else(not_found)?;
+    let config = registry.get_config(&id).map_err(|_| not_found())?;
+    let metrics = registry
+        .metrics()
+        .into_iter()
+        .find(|metrics| metrics.function_id == function_id)
+        .ok_or_else(not_found)?;
+    let pid = processes.lock().await.get(function_id).map(|proc| proc.pid);
+    let last_exit = registry.last_exit(&id).ok().flatten();
+
+    Ok(serde_json::json!({
+        "config": config,
+        "metrics": metrics,
+        "pid": pid,
+        "last_exit": last_exit,
+    }))
+}
+
+/// Summaries of all registered functions, sorted by ID.
+async fn list_functions(
+    registry: &FunctionRegistry,
+    processes: &Mutex<HashMap<String, RunningProcess>>,
+) -> Vec<FunctionSummary> {
+    let procs = processes.lock().await;
+    let mut functions: Vec<FunctionSummary> = registry
+        .function_ids()
+        .into_iter()
+        .filter_map(|id| {
+            let config = registry.get_config(&id).ok()?;
+            let state = registry.get_state(&id).ok()?;
+            Some(FunctionSummary {
+                id: id.to_string(),
+                trigger_port: config.trigger_port.value(),
+                memory_limit_mb: config.memory_limit.megabytes(),
+                state: state.name().to_string(),
+                pid: procs.get(id.as_str()).map(|proc| proc.pid),
+                last_exit: registry.last_exit(&id).ok().flatten(),
+            })
+        })
+        .collect();
+    functions.sort_by(|a, b| a.id.cmp(&b.id));
+    functions
 }
 
-/// Spawn a handler process with Unix socket handshake
-async fn spawn_handler_with_socket(
+/// Watch running handlers and apply each function's restart policy when one exits.
+async fn supervise(
+    registry: Arc<FunctionRegistry>,
+    processes: Arc<Mutex<HashMap<String, RunningProcess>>>,
+    socket_dir: PathBuf,
+    deploying: Arc<Mutex<()>>,
+) {
+    let mut interval = tokio::time::interval(SUPERVISE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        // Collect exited handlers without holding the lock across restarts
+        let exited: Vec<(RunningProcess, std::process::ExitStatus)> = {
+            let mut procs = processes.lock().await;
+            let ids: Vec<(String, std::process::ExitStatus)> = procs
+                .iter_mut()
+                .filter_map(|(id, proc)| match proc.child.try_wait() {
+                    Ok(Some(status)) => Some((id.clone(), status)),
+                    _ => None,
+                })
+                .collect();
+            ids.into_iter()
+                .filter_map(|(id, status)| procs.remove(&id).map(|proc| (proc, status)))
+                .collect()
+        };
+
+        for (proc, status) in exited {
+            let config = proc.config;
+            let _ = registry.remove_endpoint(&config.id, config.trigger_port);
+            let _ = registry.transition(&config.id, FunctionState::Suspended);
+
+            // The OOM killer uses SIGKILL and bumps the group's counter
+            let oom_killed = status.signal() == Some(Signal::SIGKILL as i32)
+                && matches!(
+                    (proc.oom_kills, oom_kills(&config.id)),
+                    (Some(before), Some(after)) if after > before
+                );
+            let reason = if oom_killed {
+                ExitReason::OutOfMemory
+            } else {
+                ExitReason::from_status(status)
+            };
+            let _ = registry.record_exit(&config.id, reason);
+
+            if oom_killed {
+                println!(
+                    "  ! {} killed: out of memory (limit {})",
+                    config.id, config.memory_limit
+                );
+                tracing::error!(
+                    function_id = %config.id,
+                    pid = proc.pid,
+                    memory_limit = %config.memory_limit,
+                    policy = ?config.restart_policy,
+                    "Handler OOM-killed"
+                );
+            } else {
+                println!("  ! {} {}", config.id, reason);
+                tracing::warn!(
+                    function_id = %config.id,
+                    pid = proc.pid,
+                    reason = %reason,
+                    policy = ?config.restart_policy,
+                    "Handler exited"
+                );
+            }
+
+            if config.restart_policy.should_restart(!reason.is_failure()) {
+                tokio::spawn(restart_handler(
+                    config,
+                    Arc::clone(&registry),
+                    Arc::clone(&processes),
+                    socket_dir.clone(),
+                    Arc::clone(&deploying),
+                ));
+            } else if reason.is_failure() {
+                let _ = registry.transition(&config.id, FunctionState::Failed);
+            }
+        }
+    }
+}
+
+/// Ping handlers that enable health checks and suspend the ones that stop
+/// answering. A suspended handler that answers again is marked Running.
+async fn monitor_health(
+    registry: Arc<FunctionRegistry>,
+    processes: Arc<Mutex<HashMap<String, RunningProcess>>>,
+) {
+    let mut interval = tokio::time::interval(SUPERVISE_INTERVAL);
+    let mut last_checked: HashMap<String, Instant> = HashMap::new();
+
+    loop {
+        interval.tick().await;
+
+        // Clone the control sockets that are due so no lock is held while pinging
+        let due: Vec<(FunctionConfig, u32, UnixStream)> = {
+            let procs = processes.lock().await;
+            procs
+                .values()
+                .filter_map(|proc| {
+                    let every = Duration::from_millis(proc.config.health_check_interval_ms?);
+                    let control = proc.control.as_ref()?;
+                    if last_checked
+                        .get(proc.config.id.as_str())
+                        .is_some_and(|checked| checked.elapsed() < every)
+                    {
+                        return None;
+                    }
+                    let stream = control.try_clone().ok()?;
+                    Some((proc.config.clone(), proc.pid, stream))
+                })
+                .collect()
+        };
+
+        let checks: Vec<_> = due
+            .into_iter()
+            .map(|(config, pid, mut stream)| {
+                last_checked.insert(config.id.to_string(), Instant::now());
+                let every = Duration::from_millis(config.health_check_interval_ms.unwrap_or(0));
+                let timeout = HEALTH_CHECK_TIMEOUT.min(every);
+                tokio::task::spawn_blocking(move || {
+                    let result = health_check_stream(&mut stream, timeout);
+                    (config, pid, result)
+                })
+            })
+            .collect();
+
+        for check in checks {
+            let Ok((config, pid, result)) = check.await else {
+                continue;
+            };
+
+            // Ignore results for a handler that was replaced meanwhile
+            let current = processes
+                .lock()
+                .await
+                .get(config.id.as_str())
+                .map(|proc| proc.pid);
+            if current != Some(pid) {
+                continue;
+            }
+
+            let state = registry.get_state(&config.id).ok();
+            match result {
+                Err(e) if state == Some(FunctionState::Running) => {
+                    let _ = registry.transition(&config.id, FunctionState::Suspended);
+                    println!("  ! {} unhealthy: {}", config.id, e);
+                    tracing::warn!(
+                        function_id = %config.id,
+                        pid = pid,
+                        error = %e,
+                        "Health check failed, function suspended"
+                    );
+                }
+                Ok(()) if state == Some(FunctionState::Suspended) => {
+                    let _ = registry.transition(&config.id, FunctionState::Running);
+                    println!("  ✓ {} healthy again", config.id);
+                    tracing::info!(function_id = %config.id, pid = pid, "Health check recovered");
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Restart a handler with exponential backoff until it comes up or the
+/// function's restart limit is exhausted, in which case it is marked Failed.
+async fn restart_handler(
+    mut config: FunctionConfig,
+    registry: Arc<FunctionRegistry>,
+    processes: Arc<Mutex<HashMap<String, RunningProcess>>>,
+    socket_dir: PathBuf,
+    deploying: Arc<Mutex<()>>,
+) {
+    loop {
+        let max_backoff = Duration::from_millis(config.restart_backoff_max_ms);
+
+        let restarts = match registry.restart_count(&config.id) {
+            Ok(restarts) => restarts,
+            // Function was removed (e.g. by a reload) while we were backing off
+            Err(_) => return,
+        };
+
+        if restarts >= config.max_restarts {
+            let _ = registry.transition(&config.id, FunctionState::Failed);
+            println!(
+                "  ✗ {} failed: restart limit ({}) exhausted",
+                config.id, config.max_restarts
+            );
+            tracing::error!(
+                function_id = %config.id,
+                restarts = restarts,
+                "Restart limit exhausted, marking function Failed"
+            );
+            return;
+        }
+
+        let backoff = RESTART_BACKOFF_BASE
+            .saturating_mul(1 << restarts.min(16))
+            .min(max_backoff);
+        tokio::time::sleep(backoff).await;
+
+        let _deploying = deploying.lock().await;
+        // A deploy or reload may have replaced the config, or started a
+        // handler of its own, while we were backing off
+        config = match registry.get_config(&config.id) {
+            Ok(current) => current,
+            Err(_) => return,
+        };
+        if processes.lock().await.contains_key(config.id.as_str()) {
+            return;
+        }
+        let attempt = registry.record_restart(&config.id).unwrap_or(restarts + 1);
+
+        match spawn_handler(&config, &socket_dir).await {
+            Ok((child, pid, control)) => {
+                let _ = registry.transition(&config.id, FunctionState::Running);
+                add_endpoint(&registry, &config, pid);
+                println!(
+                    "  ↻ {} restarted (PID: {}, attempt {})",
+                    config.id, pid, attempt
+                );
+                tracing::info!(
+                    function_id = %config.id,
+                    pid = pid,
+                    attempt = attempt,
+                    backoff_ms = backoff.as_millis(),
+                    "Handler restarted"
+                );
+                processes.lock().await.insert(
+                    config.id.to_string(),
+                    RunningProcess {
+                        child: HandlerProcess::Spawned(child),
+                        oom_kills: oom_kills(&config.id),
+                        config,
+                        pid,
+                        control,
+                    },
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::error!(
+                    function_id = %config.id,
+                    attempt = attempt,
+                    error = %e,
+                    "Handler restart failed"
+                );
+            }
+        }
+    }
+}
+
+/// Poll the config file and hot-reload functions whenever it changes.
+async fn watch_config(
+    config_path: PathBuf,
+    registry: Arc<FunctionRegistry>,
+    processes: Arc<Mutex<HashMap<String, RunningProcess>>>,
+    socket_dir: PathBuf,
+    deploying: Arc<Mutex<()>>,
+) {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut last_modified = modified(&config_path);
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let current = modified(&config_path);
+        if current.is_none() || current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        tracing::info!(config = %config_path.display(), "Config file changed, reloading");
+        let _deploying = deploying.lock().await;
+        reload_config(&config_path, &registry, &processes, &socket_dir).await;
+    }
+}
+
+/// Record a spawned handler as the endpoint on its function's trigger port.
+fn add_endpoint(registry: &FunctionRegistry, config: &FunctionConfig, pid: u32) {
+    let result = ProcessId::new(pid).map_err(Into::into).and_then(|pid| {
+        registry.add_endpoint(
+            &config.id,
+            Endpoint {
+                port: config.trigger_port,
+                pid,
+            },
+        )
+    });
+    if let Err(e) = result {
+        tracing::warn!(function_id = %config.id, error = %e, "Failed to record endpoint");
+    }
+}
+
+/// Re-read the config and apply the difference to the running set.
+///
+/// Unchanged functions are left running. Changed functions get their config
+/// updated and are respawned only if the handler command, port, or
+/// environment changed. If the new config is invalid nothing is touched.
+async fn reload_config(
+    config_path: &Path,
+    registry: &FunctionRegistry,
+    processes: &Mutex<HashMap<String, RunningProcess>>,
+    socket_dir: &Path,
+) {
+    let config = match ConfigLoader::load_file(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("✗ Reload rejected, keeping current functions: {}", e);
+            tracing::error!(error = %e, "Config reload failed validation");
+            return;
+        }
+    };
+
+    // Remove functions that are no longer configured
+    for id in registry.function_ids() {
+        if config.functions.iter().any(|f| f.id == id) {
+            continue;
+        }
+        let removed = processes.lock().await.remove(id.as_str());
+        if let Some(mut proc) = removed {
+            let _ = registry.transition(&id, FunctionState::Draining);
+            proc.child.kill_and_reap().await;
+        }
+        let _ = registry.unregister(&id);
+        println!("  − {} removed", id);
+        tracing::info!(function_id = %id, "Function removed by reload");
+    }
+
+    for func_config in &config.functions {
+        let respawn = match registry.get_config(&func_config.id) {
+            Ok(current) if current == *func_config => continue,
+            Ok(current) => {
+                let respawn = needs_respawn(&current, func_config);
+                if let Err(e) = registry.update_config(func_config.clone()) {
+                    tracing::error!(function_id = %func_config.id, error = %e, "Config update failed");
+                    continue;
+                }
+                println!("  ~ {} updated", func_config.id);
+                tracing::info!(function_id = %func_config.id, respawn = respawn, "Function config updated");
+                respawn
+            }
+            Err(_) => {
+                if let Err(e) = registry.register(func_config.clone()) {
+                    tracing::error!(function_id = %func_config.id, error = %e, "Registration failed");
+                    continue;
+                }
+                println!("  + {} added", func_config.id);
+                true
+            }
+        };
+
+        if !respawn {
+            continue;
+        }
+
+        match replace_handler(func_config, registry, processes, socket_dir).await {
+            Ok(pid) => println!("  ✓ {} started (PID: {})", func_config.id, pid),
+            Err(e) => {
+                println!("  ✗ {} failed: {}", func_config.id, e);
+                tracing::error!(function_id = %func_config.id, error = %e, "Failed to spawn handler");
+            }
+        }
+    }
+}
+
+/// Whether a config change only takes effect in a new handler process.
+fn needs_respawn(current: &FunctionConfig, new: &FunctionConfig) -> bool {
+    current.handler_path != new.handler_path
+        || current.trigger_port != new.trigger_port
+        || current.environment != new.environment
+}
+
+/// Kill the function's running handler, if any, and start a new one with
+/// `config`. Returns the new handler's PID.
+async fn replace_handler(
+    config: &FunctionConfig,
+    registry: &FunctionRegistry,
+    processes: &Mutex<HashMap<String, RunningProcess>>,
+    socket_dir: &Path,
+) -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
+    let replaced = processes.lock().await.remove(config.id.as_str());
+    if let Some(mut proc) = replaced {
+        let _ = registry.remove_endpoint(&config.id, proc.config.trigger_port);
+        let _ = registry.transition(&config.id, FunctionState::Draining);
+        proc.child.kill_and_reap().await;
+        let _ = registry.transition(&config.id, FunctionState::Suspended);
+    }
+
+    let (child, pid, control) = spawn_handler(config, socket_dir).await?;
+    let _ = registry.transition(&config.id, FunctionState::Running);
+    add_endpoint(registry, config, pid);
+    processes.lock().await.insert(
+        config.id.to_string(),
+        RunningProcess {
+            child: HandlerProcess::Spawned(child),
+            config: config.clone(),
+            pid,
+            control,
+            oom_kills: oom_kills(&config.id),
+        },
+    );
+    Ok(pid)
+}
+
+/// Spawn a handler process and wait for its READY handshake
+///
+/// Returns the child, its PID and, for the socket handshake, the control
+/// socket the handler connected on.
+async fn spawn_handler(
+    config: &FunctionConfig,
+    socket_dir: &Path,
+) -> Result<(Child, u32, Option<UnixStream>), Box<dyn std::error::Error + Send + Sync>> {
+    let span = tracing::info_span!(
+        "spawn_handler",
+        function_id = %config.id,
+        handshake = ?config.handshake,
+        pid = tracing::field::Empty,
+        elapsed_ms = tracing::field::Empty,
+    );
+    let start = Instant::now();
+    let result = start_handler(config, socket_dir)
+        .instrument(span.clone())
+        .await;
+    span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+    if let Ok((_, pid, _)) = &result {
+        span.record("pid", pid);
+    }
+    result
+}
+
+/// Body of [`spawn_handler`], run inside its span.
+async fn start_handler(
     config: &FunctionConfig,
     socket_dir: &Path,
-) -> Result<(Child, u32), Box<dyn std::error::Error>> {
+) -> Result<(Child, u32, Option<UnixStream>), Box<dyn std::error::Error + Send + Sync>> {
     let handler_path = config.handler_path.as_path();
     let socket_path = socket_dir.join(format!("{}.sock", config.id));
 
-    // Remove old socket if exists
-    let _ = std::fs::remove_file(&socket_path);
-
     // Create Unix listener BEFORE spawning the process
-    let listener = UnixListener::bind(&socket_path)?;
-    listener.set_nonblocking(true)?;
+    let listener = match config.handshake {
+        HandshakeMode::Socket => {
+            // Remove old socket if exists
+            let _ = std::fs::remove_file(&socket_path);
+
+            let listener = UnixListener::bind(&socket_path)?;
+            listener.set_nonblocking(true)?;
+            Some(listener)
+        }
+        HandshakeMode::Stdout => None,
+    };
 
     // Determine how to run the handler
     let (program, args): (String, Vec<String>) =
@@ -187,20 +1290,31 @@ async fn spawn_handler_with_socket(
 
     // Build environment
     let mut env_vars: HashMap<String, String> = config.environment.clone();
-    env_vars.insert(
-        "AETHER_SOCKET".to_string(),
-        socket_path.to_string_lossy().to_string(),
-    );
+    if listener.is_some() {
+        env_vars.insert(
+            "AETHER_SOCKET".to_string(),
+            socket_path.to_string_lossy().to_string(),
+        );
+    }
     env_vars.insert("AETHER_FUNCTION_ID".to_string(), config.id.to_string());
     env_vars.insert(
         "AETHER_TRIGGER_PORT".to_string(),
         config.trigger_port.value().to_string(),
     );
+    env_vars.insert(
+        "AETHER_READY_TIMEOUT_MS".to_string(),
+        config.ready_timeout_ms.to_string(),
+    );
+    // Lets the handler continue the trace when OTLP export is on
+    if let Some(traceparent) = crate::otlp::current_traceparent() {
+        env_vars.insert("TRACEPARENT".to_string(), traceparent);
+    }
 
     tracing::debug!(
         program = %program,
         handler = %handler_path.display(),
         socket = %socket_path.display(),
+        handshake = ?config.handshake,
         "Spawning handler"
     );
 
@@ -209,10 +1323,13 @@ async fn spawn_handler_with_socket(
     cmd.args(&args)
         .envs(&env_vars)
         .stdin(Stdio::null())
-        .stdout(Stdio::inherit())
+        .stdout(match config.handshake {
+            HandshakeMode::Socket => Stdio::inherit(),
+            HandshakeMode::Stdout => Stdio::piped(),
+        })
         .stderr(Stdio::inherit());
 
-    let child = cmd.spawn().map_err(|e| {
+    let mut child = cmd.spawn().map_err(|e| {
         format!(
             "Failed to spawn '{}': {} (handler_path: {})",
             program,
@@ -222,32 +1339,115 @@ async fn spawn_handler_with_socket(
     })?;
 
     let pid = child.id();
+    apply_limits(config, pid);
 
     // Wait for READY signal from the handler
+    let ready_timeout = Duration::from_millis(config.ready_timeout_ms);
     let start = Instant::now();
-    let mut ready_received = false;
+    let ready = match &listener {
+        Some(listener) => wait_socket_ready(listener, &socket_path, start, ready_timeout)
+            .await
+            .map(Some),
+        None => wait_stdout_ready(&mut child, start, ready_timeout)
+            .await
+            .map(|()| None),
+    };
+
+    let control = match ready {
+        Ok(control) => control,
+        Err(e) => {
+            // Kill the process if it didn't send READY
+            HandlerProcess::Spawned(child).kill_and_reap().await;
+            return Err(e.into());
+        }
+    };
+
+    tracing::info!(
+        function_id = %config.id,
+        pid = pid,
+        elapsed_ms = start.elapsed().as_millis(),
+        "Handler sent READY signal"
+    );
+
+    Ok((child, pid, control))
+}
+
+/// Move a freshly spawned handler into its function's cgroup and apply the
+/// function's memory and CPU limits.
+///
+/// Without a usable cgroup v2 hierarchy (not mounted, controllers missing, or
+/// no permission) this warns once and handlers run unlimited.
+fn apply_limits(config: &FunctionConfig, pid: u32) {
+    let parent = CGROUP_PARENT.get_or_init(|| match CgroupParent::create(CGROUP_ROOT, CONTROLLERS) {
+        Ok(parent) => Some(parent),
+        Err(e) => {
+            tracing::warn!(error = %e, "cgroup v2 unavailable, resource limits will not be enforced");
+            None
+        }
+    });
+    let Some(parent) = parent else {
+        return;
+    };
 
-    while start.elapsed() < READY_TIMEOUT {
+    let result = parent.group(&config.id).and_then(|group| {
+        group.set_memory_limit(config.memory_limit)?;
+        group.set_cpu_limit(config.cpu_limit)?;
+        group.add_process(pid)
+    });
+    match result {
+        Ok(()) => tracing::debug!(
+            function_id = %config.id,
+            pid = pid,
+            memory_limit = %config.memory_limit,
+            cpu_limit = ?config.cpu_limit,
+            "Resource limits applied"
+        ),
+        Err(e) => {
+            tracing::warn!(function_id = %config.id, error = %e, "Failed to apply resource limits")
+        }
+    }
+}
+
+/// OOM kill count of a function's cgroup, if limits are enforced.
+fn oom_kills(function_id: &FunctionId) -> Option<u64> {
+    let parent = CGROUP_PARENT.get()?.as_ref()?;
+    parent.group(function_id).ok()?.oom_kills().ok()
+}
+
+/// Wait for a connection on the control socket that sends READY
+async fn wait_socket_ready(
+    listener: &UnixListener,
+    socket_path: &Path,
+    start: Instant,
+    ready_timeout: Duration,
+) -> Result<UnixStream, String> {
+    let mut connected = false;
+
+    while start.elapsed() < ready_timeout {
         match listener.accept() {
-            Ok((mut stream, _)) => {
-                stream.set_nonblocking(false)?;
-                stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+            Ok((stream, _)) => {
+                connected = true;
+                // Don't let a silent connection outlast the READY deadline
+                let remaining = ready_timeout
+                    .saturating_sub(start.elapsed())
+                    .clamp(Duration::from_millis(1), Duration::from_secs(5));
 
+                // Read through tokio so the wait does not block a worker thread
+                let mut stream = stream
+                    .set_nonblocking(true)
+                    .and_then(|()| tokio::net::UnixStream::from_std(stream))
+                    .map_err(|e| format!("Socket setup error: {}", e))?;
                 let mut buf = [0u8; 16];
-                match stream.read(&mut buf) {
-                    Ok(n) if n >= 5 => {
-                        if &buf[..5] == b"READY" {
-                            ready_received = true;
-                            tracing::info!(
-                                function_id = %config.id,
-                                pid = pid,
-                                elapsed_ms = start.elapsed().as_millis(),
-                                "Handler sent READY signal"
-                            );
-                            break;
-                        }
+                let read = tokio::time::timeout(remaining, stream.read(&mut buf)).await;
+                if let Ok(Ok(n)) = read {
+                    if n >= 5 && &buf[..5] == b"READY" {
+                        // Health checks use the stream with blocking reads
+                        let stream = stream
+                            .into_std()
+                            .and_then(|stream| stream.set_nonblocking(false).map(|()| stream))
+                            .map_err(|e| format!("Socket setup error: {}", e))?;
+                        return Ok(stream);
                     }
-                    _ => {}
                 }
             }
             Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
@@ -255,21 +1455,65 @@ async fn spawn_handler_with_socket(
                 tokio::time::sleep(Duration::from_millis(50)).await;
             }
             Err(e) => {
-                return Err(format!("Socket accept error: {}", e).into());
+                return Err(format!("Socket accept error: {}", e));
             }
         }
     }
 
-    if !ready_received {
-        // Kill the process if it didn't send READY
-        let mut child = child;
-        let _ = child.kill();
-        return Err(format!(
-            "Handler did not send READY within {}s",
-            READY_TIMEOUT.as_secs()
+    let waited_ms = start.elapsed().as_millis();
+    Err(if connected {
+        format!(
+            "Handler connected but did not send READY within {}ms",
+            waited_ms
         )
-        .into());
-    }
+    } else {
+        format!(
+            "Handler did not connect to {} within {}ms",
+            socket_path.display(),
+            waited_ms
+        )
+    })
+}
 
-    Ok((child, pid))
+/// Wait for a line equal to READY on the handler's stdout.
+///
+/// The handler's output keeps being forwarded to our stdout afterwards, as it
+/// would be if stdout were inherited.
+async fn wait_stdout_ready(
+    child: &mut Child,
+    start: Instant,
+    ready_timeout: Duration,
+) -> Result<(), String> {
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "Handler stdout was not captured".to_string())?;
+
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let mut ready_tx = Some(ready_tx);
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if line == "READY" {
+                if let Some(tx) = ready_tx.take() {
+                    let _ = tx.send(());
+                    continue;
+                }
+            }
+            println!("{}", line);
+        }
+    });
+
+    let remaining = ready_timeout.saturating_sub(start.elapsed());
+    match tokio::time::timeout(remaining, ready_rx).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(_)) => Err(format!(
+            "Handler closed stdout without sending READY after {}ms",
+            start.elapsed().as_millis()
+        )),
+        Err(_) => Err(format!(
+            "Handler did not print READY within {}ms",
+            start.elapsed().as_millis()
+        )),
+    }
 }
@@ -2,13 +2,29 @@
 // Copyright 2025 Ankit Kumar Pandey
 
 //! `aether validate` command - Validate configuration file.
+//!
+//! With `--strict`, handler paths must also exist and be executable, so CI
+//! catches a bad path before `aether up` does.
+//!
+//! With `--against-running`, the file is also compared with the functions of
+//! the running orchestrator, to catch edits that were never reloaded.
 
-use aetherless_core::ConfigLoader;
+use aetherless_core::{ConfigDiff, ConfigLoader, LoadOptions, RegistrySnapshot};
 
-pub async fn execute(file: &str) -> Result<(), Box<dyn std::error::Error>> {
-    tracing::info!(file = %file, "Validating configuration");
+use super::control::{self, ControlError, ControlRequest};
 
-    match ConfigLoader::load_file(file) {
+pub async fn execute(
+    file: &str,
+    strict: bool,
+    against_running: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!(file = %file, strict = strict, "Validating configuration");
+
+    let options = LoadOptions {
+        strict_paths: strict,
+        ..Default::default()
+    };
+    match ConfigLoader::load_file_with(file, &options) {
         Ok(config) => {
             println!("✓ Configuration is valid");
             println!();
@@ -37,6 +53,29 @@ pub async fn execute(file: &str) -> Result<(), Box<dyn std::error::Error>> {
                     func.id, func.trigger_port, func.memory_limit, func.timeout_ms
                 );
             }
+
+            if against_running {
+                let running = match control::request(&ControlRequest::GetSnapshot).await {
+                    Ok(data) => serde_json::from_value::<RegistrySnapshot>(data)?,
+                    Err(ControlError::NotRunning) => {
+                        eprintln!("✗ No orchestrator is running to compare against");
+                        std::process::exit(1);
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+
+                let diff = ConfigDiff::between(&running, &config.functions);
+                println!();
+                if diff.is_empty() {
+                    println!("✓ Matches the running orchestrator");
+                } else {
+                    println!("✗ Differs from the running orchestrator:");
+                    for line in diff_lines(&diff) {
+                        println!("  {}", line);
+                    }
+                    std::process::exit(1);
+                }
+            }
             Ok(())
         }
         Err(e) => {
@@ -46,3 +85,54 @@ pub async fn execute(file: &str) -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 }
+
+/// Render a diff for the terminal: `+` for functions only in the file, `-`
+/// for ones only running, `~` with indented fields for changed ones.
+fn diff_lines(diff: &ConfigDiff) -> Vec<String> {
+    let mut lines = Vec::new();
+    for id in &diff.added {
+        lines.push(format!("+ {} (in file, not running)", id));
+    }
+    for id in &diff.removed {
+        lines.push(format!("- {} (running, not in file)", id));
+    }
+    for change in &diff.changed {
+        lines.push(format!("~ {}", change.id));
+        for field in &change.fields {
+            lines.push(format!("    {}", field));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aetherless_core::{FieldChange, FunctionChange, FunctionId};
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = ConfigDiff {
+            added: vec![FunctionId::new("fresh").unwrap()],
+            removed: vec![FunctionId::new("dropped").unwrap()],
+            changed: vec![FunctionChange {
+                id: FunctionId::new("edited").unwrap(),
+                fields: vec![FieldChange {
+                    field: "timeout_ms".to_string(),
+                    running: "30000".to_string(),
+                    on_disk: "60000".to_string(),
+                }],
+            }],
+        };
+        assert_eq!(
+            diff_lines(&diff),
+            vec![
+                "+ fresh (in file, not running)",
+                "- dropped (running, not in file)",
+                "~ edited",
+                "    timeout_ms: 30000 -> 60000",
+            ]
+        );
+        assert!(diff_lines(&ConfigDiff::default()).is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Ankit Kumar Pandey
+
+//! Backgrounding for `aether up` without `--foreground`.
+//!
+//! The classic double fork: the first child calls `setsid` to leave the
+//! terminal's session, the second child can never reacquire a controlling
+//! terminal and becomes the daemon. Its stdio is redirected to the log files
+//! and its PID is recorded in [`PID_FILE`] for `aether down`.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::fd::{AsRawFd, RawFd};
+use std::path::Path;
+
+use nix::sys::signal::kill;
+use nix::sys::wait::waitpid;
+use nix::unistd::{dup2, fork, pipe, setsid, ForkResult, Pid};
+use thiserror::Error;
+
+/// Directory holding the PID file and the control socket
+pub const RUN_DIR: &str = "/run/aetherless";
+
+/// PID of the running daemon
+pub const PID_FILE: &str = "/run/aetherless/aether.pid";
+
+/// Directory holding the daemon's log files
+pub const LOG_DIR: &str = "/var/log/aetherless";
+
+/// Daemon stdout, including tracing output
+pub const STDOUT_LOG: &str = "/var/log/aetherless/stdout.log";
+
+/// Daemon stderr, including handler stderr
+pub const STDERR_LOG: &str = "/var/log/aetherless/stderr.log";
+
+/// Errors starting the daemon.
+#[derive(Debug, Error)]
+pub enum DaemonError {
+    #[error("Orchestrator already running (PID: {0})")]
+    AlreadyRunning(i32),
+
+    #[error("Daemon setup failed: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Failed to fork daemon: {0}")]
+    Fork(#[from] nix::Error),
+
+    #[error("Daemon exited during startup (see {STDERR_LOG})")]
+    StartFailed,
+}
+
+/// Detach from the terminal.
+///
+/// Returns only in the daemon; the invoking process prints the daemon's PID
+/// and exits. Must be called before any threads are started, which is why
+/// `main` builds the tokio runtime afterwards.
+///
+/// The working directory is kept so relative `handler_path`s still resolve.
+pub fn daemonize() -> Result<(), DaemonError> {
+    if let Some(pid) = read_pid_file() {
+        return Err(DaemonError::AlreadyRunning(pid.as_raw()));
+    }
+
+    // Set up everything that can fail while errors still reach the terminal
+    fs::create_dir_all(RUN_DIR)?;
+    fs::create_dir_all(LOG_DIR)?;
+    let stdin = File::open("/dev/null")?;
+    let stdout = open_log(STDOUT_LOG)?;
+    let stderr = open_log(STDERR_LOG)?;
+    let (ready_read, ready_write) = pipe()?;
+
+    // SAFETY: the process is still single-threaded at this point.
+    match unsafe { fork() }? {
+        ForkResult::Parent { child } => {
+            drop(ready_write);
+            waitpid(child, None)?;
+
+            // The daemon sends its PID once its stdio is redirected
+            let mut reply = String::new();
+            File::from(ready_read).read_to_string(&mut reply)?;
+            let pid: i32 = reply.trim().parse().map_err(|_| DaemonError::StartFailed)?;
+            fs::write(PID_FILE, format!("{}\n", pid))?;
+
+            println!("✓ Orchestrator started in background (PID: {})", pid);
+            println!("  Logs: {}, {}", STDOUT_LOG, STDERR_LOG);
+            println!("  Stop with: aether down");
+            std::process::exit(0);
+        }
+        ForkResult::Child => {}
+    }
+
+    drop(ready_read);
+    setsid()?;
+
+    // SAFETY: still single-threaded; the intermediate child only exits.
+    if let ForkResult::Parent { .. } = unsafe { fork() }? {
+        // SAFETY: `_exit` skips atexit handlers that belong to the parent.
+        unsafe { nix::libc::_exit(0) };
+    }
+
+    redirect(&stdin, 0)?;
+    redirect(&stdout, 1)?;
+    redirect(&stderr, 2)?;
+
+    let mut ready = File::from(ready_write);
+    write!(ready, "{}", std::process::id())?;
+    Ok(())
+}
+
+/// PID of the running daemon, if any.
+///
+/// A PID file whose process is gone, or whose PID now belongs to some other
+/// program, is stale and removed.
+pub fn read_pid_file() -> Option<Pid> {
+    let contents = fs::read_to_string(PID_FILE).ok()?;
+    let pid = contents
+        .trim()
+        .parse()
+        .ok()
+        .map(Pid::from_raw)
+        .filter(|&pid| is_aether_process(pid));
+
+    // Printed rather than logged: `daemonize` runs before tracing is set up
+    if pid.is_none() {
+        eprintln!("⚠ Removing stale PID file {}", PID_FILE);
+        let _ = fs::remove_file(PID_FILE);
+    }
+    pid
+}
+
+/// Remove the PID file if it records this process.
+pub fn remove_pid_file() {
+    let ours = fs::read_to_string(PID_FILE)
+        .map(|contents| contents.trim() == std::process::id().to_string())
+        .unwrap_or(false);
+    if ours {
+        let _ = fs::remove_file(PID_FILE);
+    }
+}
+
+/// Whether `pid` is alive and runs the same executable as this process.
+///
+/// Compares executable names rather than paths so an `aether` installed
+/// elsewhere, or rebuilt since the daemon started, still matches.
+fn is_aether_process(pid: Pid) -> bool {
+    if pid.as_raw() <= 0 || kill(pid, None).is_err() {
+        return false;
+    }
+
+    let exe_name = |path: &Path| {
+        path.file_name().map(|name| {
+            let name = name.to_string_lossy();
+            name.trim_end_matches(" (deleted)").to_string()
+        })
+    };
+    let theirs = fs::read_link(format!("/proc/{}/exe", pid));
+    let ours = std::env::current_exe();
+    match (theirs, ours) {
+        (Ok(theirs), Ok(ours)) => {
+            exe_name(&theirs).is_some() && exe_name(&theirs) == exe_name(&ours)
+        }
+        _ => false,
+    }
+}
+
+fn open_log(path: &str) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn redirect(file: &File, fd: RawFd) -> nix::Result<()> {
+    dup2(file.as_raw_fd(), fd).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_aether_process() {
+        assert!(is_aether_process(Pid::this()));
+        // Init never runs the test binary
+        assert!(!is_aether_process(Pid::from_raw(1)));
+        assert!(!is_aether_process(Pid::from_raw(0)));
+        assert!(!is_aether_process(Pid::from_raw(i32::MAX)));
+    }
+}
@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Ankit Kumar Pandey
+
+//! Log output formats.
+//!
+//! `text` is the human-readable `tracing_subscriber` format. `json` writes
+//! one object per line for log shippers such as Loki or Elasticsearch:
+//!
+//! ```text
+//! {"timestamp":"…","level":"INFO","target":"aether::commands::up","fields":{"message":"Handler sent READY signal","function_id":"hello","pid":4242},"spans":[{"name":"spawn_handler","function_id":"hello"}]}
+//! ```
+//!
+//! Event fields keep their types as JSON values, and `spans` lists the
+//! enclosing spans from the outermost in, each with its own fields.
+
+use std::fmt;
+
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::{FormatTime, SystemTime};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Environment variable read when `--log-format` is not given.
+pub const LOG_FORMAT_ENV: &str = "AETHER_LOG_FORMAT";
+
+/// How log lines are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+impl LogFormat {
+    /// The `--log-format` value, else [`LOG_FORMAT_ENV`], else text.
+    pub fn resolve(flag: Option<LogFormat>) -> Result<Self, String> {
+        if let Some(format) = flag {
+            return Ok(format);
+        }
+        match std::env::var(LOG_FORMAT_ENV) {
+            Ok(value) if !value.is_empty() => Self::from_str(&value, true).map_err(|_| {
+                format!(
+                    "Invalid {} '{}': expected text or json",
+                    LOG_FORMAT_ENV, value
+                )
+            }),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+/// Collects fields into a JSON object, keeping numbers and booleans typed.
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        // `%value` fields arrive here wrapped so Debug prints their Display
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+/// Span field formatter for [`JsonFormat`]: stores each span's fields as a
+/// JSON object so events can embed them.
+#[derive(Debug, Default)]
+pub struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut map = Map::new();
+        fields.record(&mut JsonVisitor(&mut map));
+        write!(writer, "{}", Value::Object(map))
+    }
+
+    fn add_fields(
+        &self,
+        current: &'writer mut FormattedFields<Self>,
+        fields: &tracing::span::Record<'_>,
+    ) -> fmt::Result {
+        let mut map = parse_object(&current.fields);
+        fields.record(&mut JsonVisitor(&mut map));
+        current.fields = Value::Object(map).to_string();
+        Ok(())
+    }
+}
+
+fn parse_object(fields: &str) -> Map<String, Value> {
+    match serde_json::from_str(fields) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Event formatter writing one JSON object per line.
+#[derive(Debug, Default)]
+pub struct JsonFormat;
+
+impl<S> FormatEvent<S, JsonFields> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, JsonFields>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut timestamp = String::new();
+        SystemTime.format_time(&mut Writer::new(&mut timestamp))?;
+
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+
+        let mut line = Map::new();
+        line.insert("timestamp".to_string(), timestamp.into());
+        line.insert(
+            "level".to_string(),
+            event.metadata().level().as_str().into(),
+        );
+        line.insert("target".to_string(), event.metadata().target().into());
+        line.insert("fields".to_string(), Value::Object(fields));
+
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<Value> = scope
+                .from_root()
+                .map(|span| {
+                    let mut entry = Map::new();
+                    entry.insert("name".to_string(), span.name().into());
+                    if let Some(stored) = span.extensions().get::<FormattedFields<JsonFields>>() {
+                        entry.extend(parse_object(&stored.fields));
+                    }
+                    Value::Object(entry)
+                })
+                .collect();
+            line.insert("spans".to_string(), spans.into());
+        }
+
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::fmt::MakeWriter;
+
+    /// Collects everything written by the formatter.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Capture {
+        type Writer = Capture;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::fmt()
+            .fmt_fields(JsonFields)
+            .event_format(JsonFormat)
+            .with_writer(capture.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(
+                "spawn_handler",
+                function_id = "hello",
+                pid = tracing::field::Empty
+            );
+            let _entered = span.enter();
+            span.record("pid", 4242u32);
+            tracing::info!(elapsed_ms = 12u64, ready = true, handler = %"./h.py", "Handler sent READY");
+            tracing::warn!("Second event");
+        });
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let first = &lines[0];
+        assert_eq!(first["level"], "INFO");
+        assert_eq!(first["target"], module_path!());
+        assert!(first["timestamp"].as_str().unwrap().contains('T'));
+        assert_eq!(
+            first["fields"],
+            serde_json::json!({
+                "message": "Handler sent READY",
+                "elapsed_ms": 12,
+                "ready": true,
+                "handler": "./h.py",
+            })
+        );
+        assert_eq!(
+            first["spans"],
+            serde_json::json!([{ "name": "spawn_handler", "function_id": "hello", "pid": 4242 }])
+        );
+        assert_eq!(lines[1]["level"], "WARN");
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            LogFormat::resolve(Some(LogFormat::Json)).unwrap(),
+            LogFormat::Json
+        );
+        assert_eq!(LogFormat::from_str("JSON", true), Ok(LogFormat::Json));
+        assert!(LogFormat::from_str("xml", true).is_err());
+    }
+}
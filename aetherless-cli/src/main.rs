@@ -6,8 +6,14 @@
 //! Command-line interface for the Aetherless serverless platform.
 
 use clap::{Parser, Subcommand};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
 
 mod commands;
+mod daemon;
+mod logging;
+mod otlp;
 mod tui;
 
 /// Aetherless - High-performance serverless function orchestrator
@@ -23,6 +29,15 @@ pub struct Cli {
     #[arg(short, long)]
     pub verbose: bool,
 
+    /// Log output format (default: $AETHER_LOG_FORMAT, else text)
+    #[arg(long, value_enum)]
+    pub log_format: Option<logging::LogFormat>,
+
+    /// Export traces to this OTLP/HTTP collector, e.g. http://localhost:4318
+    /// (default: $OTEL_EXPORTER_OTLP_ENDPOINT, unset disables export)
+    #[arg(long)]
+    pub otlp_endpoint: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -34,6 +49,10 @@ pub enum Commands {
         /// Run in foreground (don't daemonize)
         #[arg(short, long)]
         foreground: bool,
+
+        /// Watch the config file and hot-reload functions on change
+        #[arg(short, long)]
+        watch: bool,
     },
 
     /// Deploy a function configuration
@@ -55,10 +74,18 @@ pub enum Commands {
         /// Watch mode - continuously update stats
         #[arg(short, long)]
         watch: bool,
+
+        /// Print stats as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// List registered functions
-    List,
+    List {
+        /// Print functions as JSON
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Stop the orchestrator
     Down,
@@ -67,24 +94,159 @@ pub enum Commands {
     Validate {
         /// Path to the configuration file
         file: String,
+
+        /// Also require handler paths to exist and be executable
+        #[arg(long)]
+        strict: bool,
+
+        /// Report how the file differs from the running orchestrator's
+        /// functions, exiting non-zero if it does
+        #[arg(long)]
+        against_running: bool,
+    },
+
+    /// Send an HTTP request to a running function and print the response
+    Invoke {
+        /// Function ID (resolved to its trigger port from the config)
+        function_id: String,
+
+        /// HTTP method
+        #[arg(short = 'X', long, default_value = "GET")]
+        method: String,
+
+        /// Request path
+        #[arg(short, long, default_value = "/")]
+        path: String,
+
+        /// Request body
+        #[arg(short, long)]
+        body: Option<String>,
+
+        /// Extra request header, as "Name: value" (repeatable)
+        #[arg(short = 'H', long = "header")]
+        headers: Vec<String>,
+
+        /// Host to connect to
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Port to connect to instead of the function's trigger port
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// Request timeout in milliseconds
+        #[arg(short, long, default_value_t = 30000)]
+        timeout: u64,
     },
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
-    // Initialize logging
+    let daemonize = matches!(
+        cli.command,
+        Commands::Up {
+            foreground: false,
+            ..
+        }
+    );
+    if daemonize {
+        // Validate before detaching so config errors reach the terminal
+        aetherless_core::ConfigLoader::load_file(&cli.config)?;
+        daemon::daemonize()?;
+    }
+
+    // Initialize logging, plus trace export if a collector is configured
     let log_level = if cli.verbose { "debug" } else { "info" };
-    tracing_subscriber::fmt().with_env_filter(log_level).init();
+    let otlp_endpoint = cli.otlp_endpoint.clone().or_else(|| {
+        std::env::var(otlp::ENDPOINT_ENV)
+            .ok()
+            .filter(|endpoint| !endpoint.is_empty())
+    });
+    let (otlp_layer, _otlp_guard) = match otlp_endpoint {
+        Some(endpoint) => {
+            let (layer, guard) = otlp::init(&endpoint)?;
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+    let log_format = logging::LogFormat::resolve(cli.log_format)?;
+    let (text_layer, json_layer) = match log_format {
+        logging::LogFormat::Text => (
+            Some(tracing_subscriber::fmt::layer().with_ansi(!daemonize)),
+            None,
+        ),
+        logging::LogFormat::Json => (
+            None,
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .fmt_fields(logging::JsonFields)
+                    .event_format(logging::JsonFormat),
+            ),
+        ),
+    };
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(log_level))
+        .with(text_layer)
+        .with(json_layer)
+        .with(otlp_layer)
+        .init();
+
+    // The runtime is built after daemonizing: forking once worker threads
+    // exist is unsound.
+    let result = tokio::runtime::Runtime::new()?.block_on(run(cli));
+    if daemonize {
+        daemon::remove_pid_file();
+    }
+    result
+}
 
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     // Dispatch to command handlers
     match cli.command {
-        Commands::Up { foreground } => commands::up::execute(&cli.config, foreground).await,
+        Commands::Up { foreground, watch } => {
+            commands::up::execute(&cli.config, foreground, watch).await
+        }
         Commands::Deploy { file, force } => commands::deploy::execute(&file, force).await,
-        Commands::Stats { dashboard, watch } => commands::stats::execute(watch, dashboard).await,
-        Commands::List => commands::list::execute(&cli.config).await,
+        Commands::Stats {
+            dashboard,
+            watch,
+            json,
+        } => {
+            let format = commands::OutputFormat::from_json_flag(json);
+            commands::stats::execute(&cli.config, watch, dashboard, format).await
+        }
+        Commands::List { json } => {
+            let format = commands::OutputFormat::from_json_flag(json);
+            commands::list::execute(&cli.config, format).await
+        }
         Commands::Down => commands::down::execute().await,
-        Commands::Validate { file } => commands::validate::execute(&file).await,
+        Commands::Validate {
+            file,
+            strict,
+            against_running,
+        } => commands::validate::execute(&file, strict, against_running).await,
+        Commands::Invoke {
+            function_id,
+            method,
+            path,
+            body,
+            headers,
+            host,
+            port,
+            timeout,
+        } => {
+            let args = commands::invoke::InvokeArgs {
+                function_id,
+                method,
+                path,
+                body,
+                headers,
+                host,
+                port,
+                timeout: std::time::Duration::from_millis(timeout),
+            };
+            commands::invoke::execute(&cli.config, args).await
+        }
     }
 }
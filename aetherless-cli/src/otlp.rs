@@ -0,0 +1,585 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Ankit Kumar Pandey
+
+//! OTLP trace export.
+//!
+//! [`OtlpLayer`] is an optional `tracing_subscriber` layer that turns closed
+//! spans into OTLP/HTTP JSON and posts them in batches to a collector's
+//! `/v1/traces`. Export runs on a background thread; when its queue is full
+//! spans are dropped rather than slowing the orchestrator down. Only plain
+//! `http://` endpoints are supported.
+//!
+//! Trace context follows W3C Trace Context: a root span gets a random trace
+//! ID and children inherit it. [`current_traceparent`] renders the current
+//! span as a `traceparent` value for passing downstream.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use thiserror::Error;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+/// Environment variable read when `--otlp-endpoint` is not given.
+pub const ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Reported as the `service.name` resource attribute.
+const SERVICE_NAME: &str = "aetherless";
+
+/// Port of an OTLP/HTTP collector when the endpoint names none.
+const DEFAULT_PORT: u16 = 4318;
+
+/// Path traces are posted to, appended to the endpoint's own path.
+const TRACES_PATH: &str = "/v1/traces";
+
+/// Spans queued for export before new ones are dropped.
+const QUEUE_CAPACITY: usize = 4096;
+
+/// Most spans sent in one request.
+const BATCH_SIZE: usize = 512;
+
+/// Longest a finished span waits before being sent.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Connect, write and read timeout for one export request, and how long
+/// [`OtlpGuard`] waits for the final flush.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors setting up the exporter.
+#[derive(Debug, Error)]
+pub enum OtlpError {
+    #[error("Invalid OTLP endpoint '{0}': expected http://host[:port][/path]")]
+    InvalidEndpoint(String),
+
+    #[error("Failed to start OTLP exporter: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Where export requests go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Endpoint {
+    /// `host:port`, for connecting and the `Host` header
+    authority: String,
+    /// Request path, ending in [`TRACES_PATH`]
+    path: String,
+}
+
+impl Endpoint {
+    /// Parse `http://host[:port][/path]`. As with the OpenTelemetry SDKs'
+    /// base endpoint, [`TRACES_PATH`] is appended unless already present.
+    fn parse(endpoint: &str) -> Result<Self, OtlpError> {
+        let invalid = || OtlpError::InvalidEndpoint(endpoint.to_string());
+        let rest = endpoint.strip_prefix("http://").ok_or_else(invalid)?;
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => rest.split_at(slash),
+            None => (rest, ""),
+        };
+        if authority.is_empty() {
+            return Err(invalid());
+        }
+
+        // A bracketed IPv6 address has colons of its own
+        let has_port = match authority.rfind(']') {
+            Some(bracket) => authority[bracket..].contains(':'),
+            None => authority.contains(':'),
+        };
+        let authority = if has_port {
+            authority.to_string()
+        } else {
+            format!("{}:{}", authority, DEFAULT_PORT)
+        };
+
+        let path = path.trim_end_matches('/');
+        let path = if path.ends_with(TRACES_PATH) {
+            path.to_string()
+        } else {
+            format!("{}{}", path, TRACES_PATH)
+        };
+        Ok(Self { authority, path })
+    }
+}
+
+/// Trace and span ID of a span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SpanContext {
+    trace_id: u128,
+    span_id: u64,
+}
+
+impl SpanContext {
+    /// The W3C `traceparent` value, always flagged as sampled.
+    fn traceparent(&self) -> String {
+        format!("00-{:032x}-{:016x}-01", self.trace_id, self.span_id)
+    }
+}
+
+/// An OTLP attribute value.
+#[derive(Debug, Clone, PartialEq)]
+enum AttributeValue {
+    String(String),
+    Int(i64),
+    Bool(bool),
+    Double(f64),
+}
+
+impl AttributeValue {
+    fn to_json(&self) -> Value {
+        match self {
+            // 64-bit integers are strings in OTLP JSON
+            AttributeValue::String(s) => json!({ "stringValue": s }),
+            AttributeValue::Int(i) => json!({ "intValue": i.to_string() }),
+            AttributeValue::Bool(b) => json!({ "boolValue": b }),
+            AttributeValue::Double(d) => json!({ "doubleValue": d }),
+        }
+    }
+}
+
+/// A span's data while it is open, kept in its registry extensions.
+#[derive(Debug)]
+struct SpanData {
+    context: SpanContext,
+    parent_span_id: Option<u64>,
+    name: &'static str,
+    start: SystemTime,
+    attributes: Vec<(&'static str, AttributeValue)>,
+}
+
+/// A closed span waiting for export.
+#[derive(Debug)]
+struct FinishedSpan {
+    data: SpanData,
+    end: SystemTime,
+}
+
+impl FinishedSpan {
+    fn to_json(&self) -> Value {
+        let data = &self.data;
+        let mut span = json!({
+            "traceId": format!("{:032x}", data.context.trace_id),
+            "spanId": format!("{:016x}", data.context.span_id),
+            "name": data.name,
+            // SPAN_KIND_INTERNAL
+            "kind": 1,
+            "startTimeUnixNano": unix_nanos(data.start),
+            "endTimeUnixNano": unix_nanos(self.end),
+            "attributes": data.attributes.iter().map(|(key, value)| {
+                json!({ "key": key, "value": value.to_json() })
+            }).collect::<Vec<_>>(),
+        });
+        if let Some(parent) = data.parent_span_id {
+            span["parentSpanId"] = json!(format!("{:016x}", parent));
+        }
+        span
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+/// An OTLP `ExportTraceServiceRequest` carrying `spans`.
+fn export_request(spans: &[FinishedSpan]) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [{
+                    "key": "service.name",
+                    "value": { "stringValue": SERVICE_NAME },
+                }],
+            },
+            "scopeSpans": [{
+                "scope": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans.iter().map(FinishedSpan::to_json).collect::<Vec<_>>(),
+            }],
+        }],
+    })
+}
+
+/// Collects span fields as attributes, replacing earlier values of a field.
+struct AttributeVisitor<'a>(&'a mut Vec<(&'static str, AttributeValue)>);
+
+impl AttributeVisitor<'_> {
+    fn set(&mut self, field: &Field, value: AttributeValue) {
+        match self.0.iter_mut().find(|(key, _)| *key == field.name()) {
+            Some((_, existing)) => *existing = value,
+            None => self.0.push((field.name(), value)),
+        }
+    }
+}
+
+impl Visit for AttributeVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, AttributeValue::String(value.to_string()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, AttributeValue::Int(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(
+            field,
+            AttributeValue::Int(i64::try_from(value).unwrap_or(i64::MAX)),
+        );
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, AttributeValue::Bool(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, AttributeValue::Double(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.set(field, AttributeValue::String(format!("{:?}", value)));
+    }
+}
+
+/// Random non-zero ID bits. Each `RandomState` is freshly keyed, and the
+/// counter keeps IDs distinct even if two keys were to collide.
+fn random_u64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.finish().max(1)
+}
+
+enum Message {
+    Span(FinishedSpan),
+    /// Send everything queued so far, then acknowledge.
+    Flush(mpsc::Sender<()>),
+}
+
+/// Layer recording spans for export.
+pub struct OtlpLayer {
+    queue: SyncSender<Message>,
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let parent = span
+            .parent()
+            .and_then(|parent| parent.extensions().get::<SpanData>().map(|d| d.context));
+
+        let mut attributes = Vec::new();
+        attrs.record(&mut AttributeVisitor(&mut attributes));
+        let data = SpanData {
+            context: SpanContext {
+                trace_id: parent.map_or_else(
+                    || (u128::from(random_u64()) << 64) | u128::from(random_u64()),
+                    |p| p.trace_id,
+                ),
+                span_id: random_u64(),
+            },
+            parent_span_id: parent.map(|p| p.span_id),
+            name: span.name(),
+            start: SystemTime::now(),
+            attributes,
+        };
+        span.extensions_mut().insert(data);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        if let Some(data) = extensions.get_mut::<SpanData>() {
+            values.record(&mut AttributeVisitor(&mut data.attributes));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+        let finished = FinishedSpan {
+            data,
+            end: SystemTime::now(),
+        };
+        // Full means the collector cannot keep up; drop the span
+        let _ = self.queue.try_send(Message::Span(finished));
+    }
+}
+
+/// Flushes queued spans when dropped. Keep it alive until exit.
+pub struct OtlpGuard {
+    queue: SyncSender<Message>,
+}
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        let (ack, acked) = mpsc::channel();
+        if self.queue.send(Message::Flush(ack)).is_ok() {
+            let _ = acked.recv_timeout(EXPORT_TIMEOUT);
+        }
+    }
+}
+
+/// Create the layer and start the exporter thread for `endpoint`.
+pub fn init(endpoint: &str) -> Result<(OtlpLayer, OtlpGuard), OtlpError> {
+    let endpoint = Endpoint::parse(endpoint)?;
+    let (queue, spans) = mpsc::sync_channel(QUEUE_CAPACITY);
+    std::thread::Builder::new()
+        .name("otlp-export".to_string())
+        .spawn(move || export_loop(&endpoint, spans))?;
+
+    Ok((
+        OtlpLayer {
+            queue: queue.clone(),
+        },
+        OtlpGuard { queue },
+    ))
+}
+
+/// `traceparent` of the current span, if OTLP export is enabled.
+pub fn current_traceparent() -> Option<String> {
+    let span = tracing::Span::current();
+    let id = span.id()?;
+    span.with_subscriber(|(_, dispatch)| {
+        let registry = dispatch.downcast_ref::<Registry>()?;
+        let span = registry.span(&id)?;
+        let extensions = span.extensions();
+        extensions
+            .get::<SpanData>()
+            .map(|d| d.context.traceparent())
+    })
+    .flatten()
+}
+
+fn export_loop(endpoint: &Endpoint, queue: mpsc::Receiver<Message>) {
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut failing = false;
+    let mut send = |batch: &mut Vec<FinishedSpan>| {
+        if batch.is_empty() {
+            return;
+        }
+        match post(endpoint, &export_request(batch)) {
+            Ok(()) => failing = false,
+            // Warn once per outage rather than once per batch
+            Err(e) if !failing => {
+                failing = true;
+                tracing::warn!(
+                    endpoint = %endpoint.authority,
+                    error = %e,
+                    "OTLP export failed, dropping spans until the collector is reachable"
+                );
+            }
+            Err(_) => {}
+        }
+        batch.clear();
+    };
+
+    loop {
+        match queue.recv_timeout(FLUSH_INTERVAL) {
+            Ok(Message::Span(span)) => {
+                batch.push(span);
+                if batch.len() >= BATCH_SIZE {
+                    send(&mut batch);
+                }
+            }
+            Ok(Message::Flush(ack)) => {
+                // Take whatever else is already queued along
+                while let Ok(Message::Span(span)) = queue.try_recv() {
+                    batch.push(span);
+                }
+                send(&mut batch);
+                let _ = ack.send(());
+            }
+            Err(RecvTimeoutError::Timeout) => send(&mut batch),
+            Err(RecvTimeoutError::Disconnected) => {
+                send(&mut batch);
+                return;
+            }
+        }
+    }
+}
+
+/// POST one export request and check for a 2xx answer.
+fn post(endpoint: &Endpoint, request: &Value) -> io::Result<()> {
+    let body = serde_json::to_vec(request)?;
+    let addr = endpoint
+        .authority
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "collector address not found"))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, EXPORT_TIMEOUT)?;
+    stream.set_read_timeout(Some(EXPORT_TIMEOUT))?;
+    stream.set_write_timeout(Some(EXPORT_TIMEOUT))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        endpoint.path,
+        endpoint.authority,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status.starts_with('2') && status.len() == 3 {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "collector answered '{}'",
+            status_line.trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_endpoint_parse() {
+        let parse = |s| Endpoint::parse(s).unwrap();
+        assert_eq!(
+            parse("http://collector"),
+            Endpoint {
+                authority: "collector:4318".to_string(),
+                path: "/v1/traces".to_string(),
+            }
+        );
+        assert_eq!(parse("http://10.0.0.5:9999/").authority, "10.0.0.5:9999");
+        assert_eq!(parse("http://[::1]").authority, "[::1]:4318");
+        assert_eq!(parse("http://[::1]:4000").authority, "[::1]:4000");
+        assert_eq!(parse("http://c/otel").path, "/otel/v1/traces");
+        assert_eq!(parse("http://c/otel/v1/traces").path, "/otel/v1/traces");
+
+        for invalid in [
+            "https://collector",
+            "collector:4318",
+            "http://",
+            "http:///v1",
+        ] {
+            assert!(
+                matches!(Endpoint::parse(invalid), Err(OtlpError::InvalidEndpoint(_))),
+                "{} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_traceparent_format() {
+        let context = SpanContext {
+            trace_id: 0x4bf92f3577b34da6a3ce929d0e0e4736,
+            span_id: 0x00f067aa0ba902b7,
+        };
+        assert_eq!(
+            context.traceparent(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+    }
+
+    /// Accept one export request and answer 200. Returns the JSON body.
+    fn fake_collector() -> (String, std::thread::JoinHandle<Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let collector = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            assert_eq!(request_line, "POST /v1/traces HTTP/1.1\r\n");
+
+            let mut length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header == "\r\n" {
+                    break;
+                }
+                if let Some(value) = header.strip_prefix("Content-Length: ") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}")
+                .unwrap();
+            serde_json::from_slice(&body).unwrap()
+        });
+        (endpoint, collector)
+    }
+
+    #[test]
+    fn test_spans_exported() {
+        let (endpoint, collector) = fake_collector();
+        let (layer, guard) = init(&endpoint).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        let traceparent = tracing::subscriber::with_default(subscriber, || {
+            let parent = tracing::info_span!("spawn_handler", function_id = "traced");
+            let _entered = parent.enter();
+            let child = tracing::info_span!("criu_restore", elapsed_ms = tracing::field::Empty);
+            child.record("elapsed_ms", 7u64);
+            let traceparent = child.in_scope(current_traceparent);
+            drop(child);
+            traceparent
+        });
+        drop(guard);
+
+        let request = collector.join().unwrap();
+        let scope = &request["resourceSpans"][0]["scopeSpans"][0];
+        let spans = scope["spans"].as_array().unwrap();
+        assert_eq!(spans.len(), 2);
+        let (child, parent) = (&spans[0], &spans[1]);
+        assert_eq!(child["name"], "criu_restore");
+        assert_eq!(parent["name"], "spawn_handler");
+        assert_eq!(child["traceId"], parent["traceId"]);
+        assert_eq!(child["parentSpanId"], parent["spanId"]);
+        assert!(parent.get("parentSpanId").is_none());
+        assert_eq!(
+            parent["attributes"][0],
+            json!({ "key": "function_id", "value": { "stringValue": "traced" } })
+        );
+        assert_eq!(
+            child["attributes"][0],
+            json!({ "key": "elapsed_ms", "value": { "intValue": "7" } })
+        );
+
+        let traceparent = traceparent.unwrap();
+        assert_eq!(
+            traceparent,
+            format!(
+                "00-{}-{}-01",
+                child["traceId"].as_str().unwrap(),
+                child["spanId"].as_str().unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_no_traceparent_without_layer() {
+        let subscriber = tracing_subscriber::registry();
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("untraced");
+            assert_eq!(span.in_scope(current_traceparent), None);
+        });
+    }
+}
@@ -3,9 +3,15 @@
 
 //! TUI Dashboard using ratatui.
 //!
-//! Visualizes the warm pool of functions and real-time statistics.
+//! Visualizes the warm pool of functions and real-time statistics. A
+//! background task polls the orchestrator's control socket and hands each
+//! snapshot to the render loop, so a slow or missing orchestrator never
+//! blocks input handling. The selected function can be snapshotted or
+//! restored from the dashboard.
 
+use std::collections::VecDeque;
 use std::io::stdout;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crossterm::{
@@ -17,6 +23,34 @@ use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Cell, Gauge, List, ListItem, Paragraph, Row, Table},
 };
+use tokio::sync::{mpsc, watch, Notify};
+
+use crate::commands::control::{self, ControlError, ControlRequest, FunctionSummary};
+
+/// How often the orchestrator is polled
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of entries kept in the events panel
+const MAX_EVENTS: usize = 50;
+
+/// One row of the warm pool table.
+#[derive(Debug, Clone, PartialEq)]
+struct FunctionRow {
+    summary: FunctionSummary,
+    restart_count: u64,
+    /// Resident memory of the handler process, if one is running.
+    rss_bytes: Option<u64>,
+}
+
+/// Orchestrator state as of the last refresh.
+#[derive(Debug, Clone, PartialEq)]
+enum Snapshot {
+    /// No refresh has completed yet.
+    Connecting,
+    Connected(Vec<FunctionRow>),
+    /// The last refresh failed; no numbers are shown.
+    Disconnected(String),
+}
 
 /// Dashboard state.
 struct App {
@@ -24,6 +58,12 @@ struct App {
     should_quit: bool,
     /// Current tick for animations.
     tick: u64,
+    /// Latest orchestrator snapshot.
+    snapshot: Snapshot,
+    /// Most recent events, oldest first.
+    events: VecDeque<String>,
+    /// ID of the highlighted function; `None` when there are no functions.
+    selected: Option<String>,
 }
 
 impl App {
@@ -31,12 +71,100 @@ impl App {
         Self {
             should_quit: false,
             tick: 0,
+            snapshot: Snapshot::Connecting,
+            events: VecDeque::from(["Dashboard started".to_string()]),
+            selected: None,
         }
     }
 
+    fn rows(&self) -> &[FunctionRow] {
+        match &self.snapshot {
+            Snapshot::Connected(rows) => rows,
+            _ => &[],
+        }
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        let selected = self.selected.as_deref()?;
+        self.rows()
+            .iter()
+            .position(|row| row.summary.id == selected)
+    }
+
+    /// Move the selection by `offset` rows, stopping at either end.
+    fn move_selection(&mut self, offset: isize) {
+        let len = self.rows().len();
+        if len == 0 {
+            return;
+        }
+        let index = match self.selected_index() {
+            Some(index) => index.saturating_add_signed(offset).min(len - 1),
+            None => 0,
+        };
+        self.selected = Some(self.rows()[index].summary.id.clone());
+    }
+
     fn tick(&mut self) {
         self.tick = self.tick.wrapping_add(1);
     }
+
+    /// Replace the snapshot, logging what changed since the previous one.
+    fn apply(&mut self, snapshot: Snapshot) {
+        for event in changes(&self.snapshot, &snapshot) {
+            self.log(event);
+        }
+        self.snapshot = snapshot;
+
+        // Keep the selected function if it still exists, else the first row
+        if self.selected_index().is_none() {
+            self.selected = self.rows().first().map(|row| row.summary.id.clone());
+        }
+    }
+
+    fn log(&mut self, event: String) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
+
+/// Describe what changed between two snapshots.
+fn changes(old: &Snapshot, new: &Snapshot) -> Vec<String> {
+    match (old, new) {
+        (Snapshot::Connected(old), Snapshot::Connected(new)) => {
+            let mut events = Vec::new();
+            for row in new {
+                let id = &row.summary.id;
+                match old.iter().find(|o| o.summary.id == *id) {
+                    None => events.push(format!("{} added", id)),
+                    Some(o) if o.summary.state != row.summary.state => events.push(format!(
+                        "{}: {} → {}",
+                        id, o.summary.state, row.summary.state
+                    )),
+                    Some(o) if o.restart_count < row.restart_count => {
+                        let pid = row.summary.pid.map(|pid| pid.to_string());
+                        events.push(format!(
+                            "{} restarted (PID: {})",
+                            id,
+                            pid.as_deref().unwrap_or("-")
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+            for row in old {
+                if !new.iter().any(|n| n.summary.id == row.summary.id) {
+                    events.push(format!("{} removed", row.summary.id));
+                }
+            }
+            events
+        }
+        (_, Snapshot::Connected(rows)) => vec![format!("Connected ({} functions)", rows.len())],
+        (Snapshot::Disconnected(_), Snapshot::Disconnected(_)) => Vec::new(),
+        (_, Snapshot::Disconnected(reason)) => vec![format!("Disconnected: {}", reason)],
+        (_, Snapshot::Connecting) => Vec::new(),
+    }
 }
 
 /// Run the TUI dashboard.
@@ -47,9 +175,20 @@ pub async fn run_dashboard() -> Result<(), Box<dyn std::error::Error>> {
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
     let mut app = App::new();
+    let refresh = Arc::new(Notify::new());
+    let mut snapshots = spawn_refresher(Arc::clone(&refresh));
+    let (results_tx, mut results) = mpsc::unbounded_channel();
 
     // Main loop
     loop {
+        if snapshots.has_changed().unwrap_or(false) {
+            let snapshot = snapshots.borrow_and_update().clone();
+            app.apply(snapshot);
+        }
+        while let Ok(result) = results.try_recv() {
+            app.log(result);
+        }
+
         terminal.draw(|frame| render(frame, &app))?;
 
         if event::poll(Duration::from_millis(100))? {
@@ -57,6 +196,26 @@ pub async fn run_dashboard() -> Result<(), Box<dyn std::error::Error>> {
                 if key.kind == KeyEventKind::Press {
                     match key.code {
                         KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
+                        KeyCode::Up => app.move_selection(-1),
+                        KeyCode::Down => app.move_selection(1),
+                        KeyCode::Char('s') | KeyCode::Char('r') => {
+                            if let Some(id) = app.selected.clone() {
+                                let function_id = id.clone();
+                                let (verb, request) = if key.code == KeyCode::Char('s') {
+                                    ("Snapshot", ControlRequest::Snapshot { function_id })
+                                } else {
+                                    ("Restore", ControlRequest::Restore { function_id })
+                                };
+                                app.log(format!("{} of {} requested", verb, id));
+                                spawn_action(
+                                    format!("{} of {}", verb, id),
+                                    request,
+                                    results_tx.clone(),
+                                    &refresh,
+                                );
+                            }
+                        }
+                        KeyCode::F(5) => refresh.notify_one(),
                         _ => {}
                     }
                 }
@@ -77,7 +236,93 @@ pub async fn run_dashboard() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn render(frame: &mut Frame, _app: &App) {
+/// Poll the orchestrator every [`REFRESH_INTERVAL`], or immediately when
+/// `refresh` is notified. The task ends once the receiver is dropped.
+fn spawn_refresher(refresh: Arc<Notify>) -> watch::Receiver<Snapshot> {
+    let (tx, rx) = watch::channel(Snapshot::Connecting);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = refresh.notified() => {}
+            }
+            let snapshot = match fetch_rows().await {
+                Ok(rows) => Snapshot::Connected(rows),
+                Err(e) => Snapshot::Disconnected(e.to_string()),
+            };
+            if tx.send(snapshot).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Send a snapshot or restore request without blocking the input loop,
+/// reporting the outcome on `results` and refreshing afterwards.
+fn spawn_action(
+    action: String,
+    request: ControlRequest,
+    results: mpsc::UnboundedSender<String>,
+    refresh: &Arc<Notify>,
+) {
+    let refresh = Arc::clone(refresh);
+    tokio::spawn(async move {
+        let result = match control::request(&request).await {
+            Ok(_) => format!("{} succeeded", action),
+            Err(e) => format!("{} failed: {}", action, e),
+        };
+        let _ = results.send(result);
+        refresh.notify_one();
+    });
+}
+
+/// Query the function list and stats from the control socket.
+async fn fetch_rows() -> Result<Vec<FunctionRow>, ControlError> {
+    let summaries: Vec<FunctionSummary> =
+        serde_json::from_value(control::request(&ControlRequest::ListFunctions).await?)?;
+    let stats = control::request(&ControlRequest::GetStats).await?;
+    let empty = Vec::new();
+    let metrics = stats["functions"].as_array().unwrap_or(&empty);
+
+    Ok(summaries
+        .into_iter()
+        .map(|summary| {
+            let restart_count = metrics
+                .iter()
+                .find(|m| m["function_id"].as_str() == Some(summary.id.as_str()))
+                .and_then(|m| m["restart_count"].as_u64())
+                .unwrap_or(0);
+            let rss_bytes = summary.pid.and_then(resident_bytes);
+            FunctionRow {
+                summary,
+                restart_count,
+                rss_bytes,
+            }
+        })
+        .collect())
+}
+
+/// Resident set size of a local process, from `VmRSS` in `/proc/<pid>/status`.
+fn resident_bytes(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn state_color(state: &str) -> Color {
+    match state {
+        "Running" => Color::Green,
+        "WarmSnapshot" => Color::Cyan,
+        "Suspended" | "Draining" => Color::Yellow,
+        "Failed" => Color::Red,
+        _ => Color::DarkGray,
+    }
+}
+
+fn render(frame: &mut Frame, app: &App) {
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -88,18 +333,31 @@ fn render(frame: &mut Frame, _app: &App) {
         .split(frame.area());
 
     // Title
-    let title = Paragraph::new(" AETHERLESS DASHBOARD ")
-        .style(
+    let (status, status_color) = match &app.snapshot {
+        Snapshot::Connecting => ("connecting...".to_string(), Color::DarkGray),
+        Snapshot::Connected(rows) => (format!("connected, {} functions", rows.len()), Color::Green),
+        Snapshot::Disconnected(_) => ("DISCONNECTED".to_string(), Color::Red),
+    };
+    let title = Paragraph::new(Line::from(vec![
+        Span::styled(
+            " AETHERLESS DASHBOARD ",
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
-        )
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
+        ),
+        Span::styled(
+            format!("[{}]", status),
+            Style::default()
+                .fg(status_color)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]))
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
     frame.render_widget(title, main_layout[0]);
 
     // Main content - split into columns
@@ -127,13 +385,48 @@ fn render(frame: &mut Frame, _app: &App) {
             .fg(Color::Yellow),
     );
 
-    let warm_pool_rows = vec![Row::new(vec![
-        Cell::from("(no functions)"),
-        Cell::from("-"),
-        Cell::from("-"),
-        Cell::from("-"),
-    ])
-    .style(Style::default().fg(Color::DarkGray))];
+    let placeholder = |text: &str, color: Color| {
+        Row::new(vec![
+            Cell::from(text.to_string()),
+            Cell::from("-"),
+            Cell::from("-"),
+            Cell::from("-"),
+        ])
+        .style(Style::default().fg(color))
+    };
+    let warm_pool_rows = match &app.snapshot {
+        Snapshot::Connecting => vec![placeholder("(connecting...)", Color::DarkGray)],
+        Snapshot::Disconnected(_) => vec![placeholder("(disconnected)", Color::Red)],
+        Snapshot::Connected(rows) if rows.is_empty() => {
+            vec![placeholder("(no functions)", Color::DarkGray)]
+        }
+        Snapshot::Connected(rows) => rows
+            .iter()
+            .map(|row| {
+                let selected = app.selected.as_deref() == Some(row.summary.id.as_str());
+                let memory = match row.rss_bytes {
+                    Some(rss) => format!(
+                        "{} / {} MB",
+                        rss / (1024 * 1024),
+                        row.summary.memory_limit_mb
+                    ),
+                    None => format!("- / {} MB", row.summary.memory_limit_mb),
+                };
+                Row::new(vec![
+                    Cell::from(row.summary.id.clone()),
+                    Cell::from(row.summary.state.clone())
+                        .style(Style::default().fg(state_color(&row.summary.state))),
+                    Cell::from(memory),
+                    Cell::from(row.summary.trigger_port.to_string()),
+                ])
+                .style(if selected {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                })
+            })
+            .collect(),
+    };
 
     let warm_pool = Table::new(
         warm_pool_rows,
@@ -187,7 +480,7 @@ fn render(frame: &mut Frame, _app: &App) {
         ])
         .split(content_layout[1]);
 
-    // SHM Latency gauge
+    // The orchestrator does not measure IPC or restore latency yet
     let shm_gauge = Gauge::default()
         .block(
             Block::default()
@@ -196,7 +489,7 @@ fn render(frame: &mut Frame, _app: &App) {
         )
         .gauge_style(Style::default().fg(Color::Cyan))
         .percent(0)
-        .label("-- μs");
+        .label("not reported");
     frame.render_widget(shm_gauge, right_layout[0]);
 
     // CRIU Restore gauge
@@ -208,10 +501,27 @@ fn render(frame: &mut Frame, _app: &App) {
         )
         .gauge_style(Style::default().fg(Color::Yellow))
         .percent(0)
-        .label("-- ms (limit: 15ms)");
+        .label("not reported (limit: 15ms)");
     frame.render_widget(criu_gauge, right_layout[1]);
 
-    // Memory usage
+    // Memory usage: handler RSS against the sum of their limits
+    let (mem_percent, mem_label) = match &app.snapshot {
+        Snapshot::Connected(rows) => {
+            let used: u64 = rows.iter().filter_map(|row| row.rss_bytes).sum();
+            let limit: u64 = rows
+                .iter()
+                .filter(|row| row.rss_bytes.is_some())
+                .map(|row| row.summary.memory_limit_mb * 1024 * 1024)
+                .sum();
+            let percent = (used * 100).checked_div(limit).unwrap_or(0).min(100) as u16;
+            (
+                percent,
+                format!("{} / {} MB", used / (1024 * 1024), limit / (1024 * 1024)),
+            )
+        }
+        Snapshot::Connecting => (0, "--".to_string()),
+        Snapshot::Disconnected(_) => (0, "disconnected".to_string()),
+    };
     let mem_gauge = Gauge::default()
         .block(
             Block::default()
@@ -219,24 +529,121 @@ fn render(frame: &mut Frame, _app: &App) {
                 .borders(Borders::ALL),
         )
         .gauge_style(Style::default().fg(Color::Green))
-        .percent(0)
-        .label("0 / 0 MB");
+        .percent(mem_percent)
+        .label(mem_label);
     frame.render_widget(mem_gauge, right_layout[2]);
 
-    // Events log
-    let events: Vec<ListItem> = vec![
-        ListItem::new("Dashboard started"),
-        ListItem::new("Waiting for orchestrator..."),
-    ];
+    // Events log, newest first
+    let events: Vec<ListItem> = app
+        .events
+        .iter()
+        .rev()
+        .map(|event| ListItem::new(event.as_str()))
+        .collect();
     let events_list = List::new(events)
         .block(Block::default().title(" Events ").borders(Borders::ALL))
         .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(events_list, right_layout[3]);
 
     // Footer
-    let footer = Paragraph::new(" Press 'q' to quit | 'r' to refresh ")
-        .style(Style::default().fg(Color::DarkGray))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+    let footer =
+        Paragraph::new(" 'q' quit | ↑/↓ select | 's' snapshot | 'r' restore | F5 refresh ")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, main_layout[2]);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: &str, state: &str, restart_count: u64) -> FunctionRow {
+        FunctionRow {
+            summary: FunctionSummary {
+                id: id.to_string(),
+                trigger_port: 8080,
+                memory_limit_mb: 128,
+                state: state.to_string(),
+                pid: Some(42),
+                last_exit: None,
+            },
+            restart_count,
+            rss_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_selection() {
+        let mut app = App::new();
+        app.move_selection(1);
+        assert_eq!(app.selected, None);
+
+        // Empty list: nothing to select
+        app.apply(Snapshot::Connected(Vec::new()));
+        app.move_selection(1);
+        assert_eq!(app.selected, None);
+
+        // The first row is selected by default; movement stops at the ends
+        let rows = vec![
+            row("a", "Running", 0),
+            row("b", "Running", 0),
+            row("c", "Running", 0),
+        ];
+        app.apply(Snapshot::Connected(rows));
+        assert_eq!(app.selected.as_deref(), Some("a"));
+        app.move_selection(-1);
+        assert_eq!(app.selected.as_deref(), Some("a"));
+        app.move_selection(1);
+        app.move_selection(1);
+        app.move_selection(1);
+        assert_eq!(app.selected.as_deref(), Some("c"));
+
+        // Selection follows the function across refreshes
+        app.apply(Snapshot::Connected(vec![
+            row("c", "Running", 0),
+            row("d", "Running", 0),
+        ]));
+        assert_eq!(app.selected_index(), Some(0));
+        app.apply(Snapshot::Connected(vec![row("d", "Running", 0)]));
+        assert_eq!(app.selected.as_deref(), Some("d"));
+
+        app.apply(Snapshot::Disconnected(
+            "orchestrator not running".to_string(),
+        ));
+        assert_eq!(app.selected, None);
+    }
+
+    #[test]
+    fn test_apply_logs_changes() {
+        let mut app = App::new();
+        app.apply(Snapshot::Disconnected(
+            "orchestrator not running".to_string(),
+        ));
+        app.apply(Snapshot::Connected(vec![row("a", "Running", 0)]));
+        app.apply(Snapshot::Connected(vec![
+            row("a", "Running", 1),
+            row("b", "Running", 0),
+        ]));
+        app.apply(Snapshot::Connected(vec![row("a", "Suspended", 1)]));
+        app.apply(Snapshot::Disconnected(
+            "orchestrator not running".to_string(),
+        ));
+
+        let events: Vec<&str> = app.events.iter().map(String::as_str).collect();
+        assert_eq!(
+            events,
+            [
+                "Dashboard started",
+                "Disconnected: orchestrator not running",
+                "Connected (1 functions)",
+                "a restarted (PID: 42)",
+                "b added",
+                "a: Running → Suspended",
+                "b removed",
+                "Disconnected: orchestrator not running",
+            ]
+        );
+        assert!(matches!(app.snapshot, Snapshot::Disconnected(_)));
+    }
+}
@@ -0,0 +1,255 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Ankit Kumar Pandey
+
+//! cgroup v2 resource limits for handler processes.
+//!
+//! The orchestrator owns a parent group `<root>/aetherless` with one child
+//! group per function. Limits are written to the child's interface files and
+//! handler PIDs are moved in through `cgroup.procs`.
+//!
+//! Memory limits set both `memory.max`, where the kernel OOM-kills the group,
+//! and a lower `memory.high`, where it throttles and reclaims first so a
+//! handler under pressure slows down before it is killed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::CgroupError;
+use crate::types::{CpuLimit, FunctionId, MemoryLimit};
+
+/// Default cgroup v2 mount point.
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Controllers the orchestrator sets limits with.
+pub const CONTROLLERS: &[&str] = &["cpu", "memory"];
+
+/// Name of the orchestrator's parent group under the root.
+const PARENT_GROUP: &str = "aetherless";
+
+/// The orchestrator's parent group, holding one group per function.
+#[derive(Debug, Clone)]
+pub struct CgroupParent {
+    path: PathBuf,
+}
+
+impl CgroupParent {
+    /// Create (or reuse) `<root>/aetherless` with `controllers` enabled for
+    /// its children.
+    ///
+    /// # Errors
+    /// NotMounted if `root` is not a cgroup v2 hierarchy, ControllerUnavailable
+    /// if a controller is missing, Write if the groups cannot be set up
+    /// (typically missing permissions).
+    pub fn create(
+        root: impl AsRef<Path>,
+        controllers: &[&'static str],
+    ) -> Result<Self, CgroupError> {
+        let root = root.as_ref();
+        let available = fs::read_to_string(root.join("cgroup.controllers")).map_err(|_| {
+            CgroupError::NotMounted {
+                root: root.to_path_buf(),
+            }
+        })?;
+        for &controller in controllers {
+            if !available.split_whitespace().any(|c| c == controller) {
+                return Err(CgroupError::ControllerUnavailable {
+                    controller,
+                    root: root.to_path_buf(),
+                });
+            }
+        }
+
+        let enable: Vec<String> = controllers.iter().map(|c| format!("+{}", c)).collect();
+        let enable = enable.join(" ");
+
+        // Controllers must be enabled at every level down to the handler groups
+        write(root.join("cgroup.subtree_control"), &enable)?;
+        let path = root.join(PARENT_GROUP);
+        fs::create_dir_all(&path).map_err(|source| CgroupError::Write {
+            path: path.clone(),
+            source,
+        })?;
+        write(path.join("cgroup.subtree_control"), &enable)?;
+
+        Ok(Self { path })
+    }
+
+    /// Path of the parent group.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Create (or reuse) the group for a function.
+    pub fn group(&self, function_id: &FunctionId) -> Result<HandlerCgroup, CgroupError> {
+        let path = self.path.join(function_id.as_str());
+        fs::create_dir_all(&path).map_err(|source| CgroupError::Write {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(HandlerCgroup { path })
+    }
+}
+
+/// A function's cgroup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerCgroup {
+    path: PathBuf,
+}
+
+impl HandlerCgroup {
+    /// Path of the group.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write `cpu.max`; `None` removes the quota.
+    pub fn set_cpu_limit(&self, limit: Option<CpuLimit>) -> Result<(), CgroupError> {
+        let value = limit.map_or_else(|| "max".to_string(), |limit| limit.cpu_max());
+        write(self.path.join("cpu.max"), &value)
+    }
+
+    /// Write `memory.max` and `memory.high`, the latter at 90% of the limit.
+    pub fn set_memory_limit(&self, limit: MemoryLimit) -> Result<(), CgroupError> {
+        let max = limit.bytes();
+        write(self.path.join("memory.high"), &(max / 10 * 9).to_string())?;
+        write(self.path.join("memory.max"), &max.to_string())
+    }
+
+    /// Number of processes in the group the OOM killer has killed so far.
+    ///
+    /// The counter is cumulative over the group's lifetime, so callers compare
+    /// it against an earlier reading.
+    pub fn oom_kills(&self) -> Result<u64, CgroupError> {
+        let path = self.path.join("memory.events");
+        let events = fs::read_to_string(&path).map_err(|source| CgroupError::Read {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(events
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .find(|(key, _)| *key == "oom_kill")
+            .and_then(|(_, count)| count.trim().parse().ok())
+            .unwrap_or(0))
+    }
+
+    /// Move a process into the group.
+    pub fn add_process(&self, pid: u32) -> Result<(), CgroupError> {
+        write(self.path.join("cgroup.procs"), &pid.to_string())
+    }
+
+    /// Remove the group. The kernel refuses while processes remain in it.
+    pub fn remove(&self) -> Result<(), CgroupError> {
+        fs::remove_dir(&self.path).map_err(|source| CgroupError::Write {
+            path: self.path.clone(),
+            source,
+        })
+    }
+}
+
+fn write(path: PathBuf, contents: &str) -> Result<(), CgroupError> {
+    fs::write(&path, contents).map_err(|source| CgroupError::Write { path, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A directory laid out like a cgroup v2 root. Interface files are plain
+    /// files, so writes can be read back.
+    fn fake_root(controllers: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("cgroup.controllers"), controllers).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_cpu_limit_written() {
+        let root = fake_root("cpuset cpu io memory pids");
+        let parent = CgroupParent::create(root.path(), &["cpu"]).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.path().join("cgroup.subtree_control")).unwrap(),
+            "+cpu"
+        );
+
+        let group = parent.group(&FunctionId::new("limited").unwrap()).unwrap();
+        assert_eq!(group.path(), root.path().join("aetherless/limited"));
+
+        group
+            .set_cpu_limit(Some(CpuLimit::with_cpus(250, 1).unwrap()))
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(group.path().join("cpu.max")).unwrap(),
+            "25000 100000"
+        );
+        group.set_cpu_limit(None).unwrap();
+        assert_eq!(
+            fs::read_to_string(group.path().join("cpu.max")).unwrap(),
+            "max"
+        );
+
+        group.add_process(4242).unwrap();
+        assert_eq!(
+            fs::read_to_string(group.path().join("cgroup.procs")).unwrap(),
+            "4242"
+        );
+    }
+
+    #[test]
+    fn test_memory_limit_written() {
+        let root = fake_root("cpu memory");
+        let parent = CgroupParent::create(root.path(), CONTROLLERS).unwrap();
+        assert_eq!(
+            fs::read_to_string(parent.path().join("cgroup.subtree_control")).unwrap(),
+            "+cpu +memory"
+        );
+
+        let group = parent.group(&FunctionId::new("bounded").unwrap()).unwrap();
+        group
+            .set_memory_limit(MemoryLimit::from_mb(100).unwrap())
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(group.path().join("memory.max")).unwrap(),
+            "104857600"
+        );
+        assert_eq!(
+            fs::read_to_string(group.path().join("memory.high")).unwrap(),
+            "94371840"
+        );
+    }
+
+    #[test]
+    fn test_oom_kills() {
+        let root = fake_root("memory");
+        let parent = CgroupParent::create(root.path(), &["memory"]).unwrap();
+        let group = parent.group(&FunctionId::new("hungry").unwrap()).unwrap();
+
+        // Not readable before the group exists in a real hierarchy
+        assert!(group.oom_kills().is_err());
+
+        fs::write(
+            group.path().join("memory.events"),
+            "low 0\nhigh 12\nmax 3\noom 1\noom_kill 1\noom_group_kill 0\n",
+        )
+        .unwrap();
+        assert_eq!(group.oom_kills().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_unavailable_hierarchy() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            CgroupParent::create(dir.path(), &["cpu"]),
+            Err(CgroupError::NotMounted { .. })
+        ));
+
+        let root = fake_root("memory pids");
+        assert!(matches!(
+            CgroupParent::create(root.path(), &["cpu"]),
+            Err(CgroupError::ControllerUnavailable {
+                controller: "cpu",
+                ..
+            })
+        ));
+    }
+}
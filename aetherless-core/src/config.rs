@@ -1,36 +1,173 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright 2025 Ankit Kumar Pandey
 
-//! YAML configuration parser with strict schema validation.
+//! YAML/JSON configuration parser with strict schema validation.
 //!
 //! Validates function configurations at boot-up time.
 //! Any invalid field results in a HardValidationError that prevents startup.
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::criu::CriuOptions;
 use crate::error::{AetherError, AetherResult, HardValidationError};
-use crate::types::{FunctionId, HandlerPath, MemoryLimit, Port};
+use crate::registry::RegistrySnapshot;
+use crate::types::{CpuLimit, FunctionId, HandlerPath, MemoryLimit, Port};
 
 /// Raw configuration as parsed from YAML (before validation).
 #[derive(Debug, Deserialize)]
 struct RawFunctionConfig {
     id: String,
-    memory_limit_mb: u64,
+    #[serde(default)]
+    memory_limit_mb: Option<u64>,
+    /// Human-readable alternative to `memory_limit_mb`, e.g. `"256MiB"`.
+    #[serde(default)]
+    memory: Option<String>,
+    /// CPU quota in millicores; unlimited if absent.
+    #[serde(default)]
+    cpu_limit_millicores: Option<u32>,
     trigger_port: u16,
     handler_path: String,
     #[serde(default)]
     environment: HashMap<String, String>,
     #[serde(default = "default_timeout")]
     timeout_ms: u64,
+    #[serde(default)]
+    restart_policy: RestartPolicy,
+    #[serde(default = "default_max_restarts")]
+    max_restarts: u32,
+    #[serde(default = "default_restart_backoff_max_ms")]
+    restart_backoff_max_ms: u64,
+    #[serde(default)]
+    max_concurrency: Option<usize>,
+    #[serde(default = "default_ready_timeout_ms")]
+    ready_timeout_ms: u64,
+    #[serde(default)]
+    handshake: HandshakeMode,
+    #[serde(default)]
+    health_check_interval_ms: Option<u64>,
+    #[serde(default)]
+    restore_mode: RestoreMode,
+    /// Directory of the file this function was declared in, used to resolve
+    /// a relative `handler_path`. `None` for configs loaded from a string.
+    #[serde(skip)]
+    base_dir: Option<PathBuf>,
 }
 
 fn default_timeout() -> u64 {
     30000 // 30 seconds
 }
 
+fn default_max_restarts() -> u32 {
+    5
+}
+
+fn default_restart_backoff_max_ms() -> u64 {
+    30000 // 30 seconds
+}
+
+fn default_ready_timeout_ms() -> u64 {
+    30000 // 30 seconds
+}
+
+/// When the orchestrator restarts a handler process that has exited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartPolicy {
+    /// Never restart; the function stays down once its handler exits.
+    #[default]
+    Never,
+    /// Restart only if the handler exited unsuccessfully.
+    OnFailure,
+    /// Restart whenever the handler exits.
+    Always,
+}
+
+/// How a handler tells the orchestrator it is ready.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandshakeMode {
+    /// Connect to `AETHER_SOCKET` and send `READY`.
+    #[default]
+    Socket,
+    /// Print a line equal to `READY` on stdout.
+    Stdout,
+}
+
+/// How CRIU restores a function from its snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    /// Load every page before the process resumes.
+    #[default]
+    Eager,
+    /// Resume first and fault pages in on demand through userfaultfd.
+    /// Falls back to eager when the kernel or CRIU lacks support.
+    Lazy,
+}
+
+/// How the XDP program is attached to the network interface.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum XdpAttachMode {
+    /// Let the kernel choose: native if the driver supports it, else generic.
+    #[default]
+    Auto,
+    /// Generic (SKB) mode; works on any interface, slowest.
+    #[serde(alias = "skb")]
+    Generic,
+    /// Native (driver) mode; requires driver support.
+    #[serde(alias = "driver")]
+    Native,
+    /// Offload (hardware) mode; runs on the NIC itself.
+    #[serde(alias = "hardware")]
+    Offload,
+}
+
+impl std::fmt::Display for XdpAttachMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Auto => "auto",
+            Self::Generic => "generic",
+            Self::Native => "native",
+            Self::Offload => "offload",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for XdpAttachMode {
+    type Err = HardValidationError;
+
+    /// Parse a mode name; `skb`, `driver` and `hardware` are accepted as aliases.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "generic" | "skb" => Ok(Self::Generic),
+            "native" | "driver" => Ok(Self::Native),
+            "offload" | "hardware" => Ok(Self::Offload),
+            _ => Err(HardValidationError::InvalidFieldValue {
+                field: "xdp_attach_mode",
+                value: s.to_string(),
+                reason: "Expected auto, generic, native or offload".to_string(),
+            }),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Whether a handler that exited with the given success flag should be restarted.
+    pub fn should_restart(&self, exited_successfully: bool) -> bool {
+        match self {
+            Self::Never => false,
+            Self::OnFailure => !exited_successfully,
+            Self::Always => true,
+        }
+    }
+}
+
 /// Raw orchestrator configuration.
 #[derive(Debug, Deserialize)]
 struct RawOrchestratorConfig {
@@ -42,6 +179,20 @@ struct RawOrchestratorConfig {
     restore_timeout_ms: u64,
     #[serde(default = "default_snapshot_dir")]
     snapshot_dir: String,
+    #[serde(default)]
+    require_memory_snapshot_dir: bool,
+    #[serde(default)]
+    max_snapshot_mb: Option<u64>,
+    #[serde(default)]
+    criu: RawCriuConfig,
+    #[serde(default)]
+    xdp_attach_mode: XdpAttachMode,
+    #[serde(default)]
+    allow_privileged_ports: bool,
+    #[serde(default)]
+    admin_listen: Option<String>,
+    #[serde(default)]
+    admin_token: Option<String>,
 }
 
 fn default_shm_size() -> usize {
@@ -67,27 +218,69 @@ impl Default for RawOrchestratorConfig {
             warm_pool_size: default_warm_pool_size(),
             restore_timeout_ms: default_restore_timeout_ms(),
             snapshot_dir: default_snapshot_dir(),
+            require_memory_snapshot_dir: false,
+            max_snapshot_mb: None,
+            criu: RawCriuConfig::default(),
+            xdp_attach_mode: XdpAttachMode::default(),
+            allow_privileged_ports: false,
+            admin_listen: None,
+            admin_token: None,
         }
     }
 }
 
+/// Raw CRIU options.
+#[derive(Debug, Default, Deserialize)]
+struct RawCriuConfig {
+    #[serde(default)]
+    extra_dump_args: Vec<String>,
+    #[serde(default)]
+    extra_restore_args: Vec<String>,
+    #[serde(default)]
+    verbosity: Option<u8>,
+}
+
 /// Raw root configuration file.
 #[derive(Debug, Deserialize)]
 struct RawConfig {
+    /// Additional config files whose functions are merged into this one.
+    #[serde(default)]
+    include: Vec<String>,
+    /// Only allowed in the root file.
+    orchestrator: Option<RawOrchestratorConfig>,
     #[serde(default)]
-    orchestrator: RawOrchestratorConfig,
     functions: Vec<RawFunctionConfig>,
 }
 
 /// Validated function configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionConfig {
     pub id: FunctionId,
     pub memory_limit: MemoryLimit,
+    /// CPU quota; `None` means unlimited.
+    pub cpu_limit: Option<CpuLimit>,
     pub trigger_port: Port,
     pub handler_path: HandlerPath,
     pub environment: HashMap<String, String>,
     pub timeout_ms: u64,
+    pub restart_policy: RestartPolicy,
+    /// Restarts allowed before the function is marked Failed.
+    pub max_restarts: u32,
+    /// Upper bound for the exponential restart backoff.
+    pub restart_backoff_max_ms: u64,
+    /// Maximum in-flight requests; `None` means unlimited. Validated but not
+    /// enforced yet: requests reach handlers directly, with no gateway to
+    /// count them.
+    pub max_concurrency: Option<usize>,
+    /// How long a freshly spawned handler has to send READY.
+    pub ready_timeout_ms: u64,
+    /// How the handler signals READY.
+    pub handshake: HandshakeMode,
+    /// How often to ping the handler over its control socket; `None`
+    /// disables health checks.
+    pub health_check_interval_ms: Option<u64>,
+    /// How the function is restored from its CRIU snapshot.
+    pub restore_mode: RestoreMode,
 }
 
 /// Validated orchestrator configuration.
@@ -97,6 +290,21 @@ pub struct OrchestratorConfig {
     pub warm_pool_size: usize,
     pub restore_timeout_ms: u64,
     pub snapshot_dir: std::path::PathBuf,
+    /// Refuse to start unless `snapshot_dir` is on tmpfs or ramfs, instead
+    /// of only warning.
+    pub require_memory_snapshot_dir: bool,
+    /// Budget for all snapshots combined; the least recently restored are
+    /// evicted past it. `None` means unlimited.
+    pub max_snapshot_bytes: Option<u64>,
+    /// Extra arguments and log verbosity for CRIU.
+    pub criu: CriuOptions,
+    pub xdp_attach_mode: XdpAttachMode,
+    /// Opt out of [`LoadOptions::deny_privileged_ports`] for this config.
+    pub allow_privileged_ports: bool,
+    /// Address of the HTTP admin API; `None` leaves it off.
+    pub admin_listen: Option<std::net::SocketAddr>,
+    /// Bearer token required by the admin API's mutating routes.
+    pub admin_token: Option<String>,
 }
 
 /// Complete validated configuration.
@@ -106,15 +314,221 @@ pub struct Config {
     pub functions: Vec<FunctionConfig>,
 }
 
+/// Ports below this need root to bind and are often used by system services.
+pub const PRIVILEGED_PORT_LIMIT: u16 = 1024;
+
+/// Default cap on environment variables per function.
+pub const DEFAULT_MAX_ENV_VARS: usize = 256;
+
+/// Default cap on a function's environment size in bytes.
+pub const DEFAULT_MAX_ENV_BYTES: usize = 1024 * 1024;
+
+/// Optional checks applied while loading a configuration.
+///
+/// The defaults are lenient so tests and templated configs load on machines
+/// where their handlers are not installed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Require every `handler_path` to exist and be executable, failing with
+    /// `HandlerPathNotFound` or `HandlerNotExecutable` at load time.
+    pub strict_paths: bool,
+    /// Most environment variables a function may set.
+    pub max_env_vars: usize,
+    /// Most bytes a function's environment may take, counted as in `environ`:
+    /// `KEY=value` plus a terminating NUL per variable, after expansion.
+    pub max_env_bytes: usize,
+    /// Reject trigger ports below [`PRIVILEGED_PORT_LIMIT`] unless the
+    /// config sets `orchestrator.allow_privileged_ports`.
+    pub deny_privileged_ports: bool,
+    /// Trigger ports no function may use, e.g. ones taken by local services.
+    pub denied_ports: Vec<u16>,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            strict_paths: false,
+            max_env_vars: DEFAULT_MAX_ENV_VARS,
+            max_env_bytes: DEFAULT_MAX_ENV_BYTES,
+            deny_privileged_ports: true,
+            denied_ports: Vec::new(),
+        }
+    }
+}
+
+/// One field that differs between a running function and its config file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    /// Field name as written in a serialized [`FunctionConfig`].
+    pub field: String,
+    /// Value the running function has, as JSON.
+    pub running: String,
+    /// Value in the file, as JSON.
+    pub on_disk: String,
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.running, self.on_disk)
+    }
+}
+
+/// A function whose config file no longer matches what is running.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FunctionChange {
+    pub id: FunctionId,
+    pub fields: Vec<FieldChange>,
+}
+
+/// Differences between a running registry and a config file, from
+/// [`ConfigLoader::diff`]. Each list is sorted by function ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConfigDiff {
+    /// Functions in the file that are not running.
+    pub added: Vec<FunctionId>,
+    /// Running functions the file no longer declares.
+    pub removed: Vec<FunctionId>,
+    /// Functions in both whose configs differ.
+    pub changed: Vec<FunctionChange>,
+}
+
+impl ConfigDiff {
+    /// Compare the functions of a running registry with `functions`.
+    pub fn between(running: &RegistrySnapshot, functions: &[FunctionConfig]) -> Self {
+        let running: HashMap<&FunctionId, &FunctionConfig> = running
+            .functions
+            .iter()
+            .map(|entry| (&entry.config.id, &entry.config))
+            .collect();
+        let on_disk: HashMap<&FunctionId, &FunctionConfig> = functions
+            .iter()
+            .map(|config| (&config.id, config))
+            .collect();
+
+        let mut diff = Self::default();
+        for (&id, &config) in &on_disk {
+            match running.get(id) {
+                None => diff.added.push(id.clone()),
+                Some(&current) if current != config => diff.changed.push(FunctionChange {
+                    id: id.clone(),
+                    fields: field_changes(current, config),
+                }),
+                Some(_) => {}
+            }
+        }
+        diff.removed = running
+            .keys()
+            .filter(|id| !on_disk.contains_key(*id))
+            .map(|&id| id.clone())
+            .collect();
+
+        diff.added.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        diff.removed.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        diff.changed
+            .sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+        diff
+    }
+
+    /// Whether the file matches the running registry.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Fields that differ between two configs, sorted by name.
+///
+/// Compares the serialized forms, so fields added to `FunctionConfig` are
+/// covered without listing them here.
+fn field_changes(running: &FunctionConfig, on_disk: &FunctionConfig) -> Vec<FieldChange> {
+    let to_fields = |config: &FunctionConfig| match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    };
+    let (running, on_disk) = (to_fields(running), to_fields(on_disk));
+
+    let mut changes: Vec<FieldChange> = running
+        .iter()
+        .filter_map(|(field, value)| {
+            let other = on_disk.get(field).unwrap_or(&serde_json::Value::Null);
+            (value != other).then(|| FieldChange {
+                field: field.clone(),
+                running: value.to_string(),
+                on_disk: other.to_string(),
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    changes
+}
+
 /// Configuration loader with strict validation.
 pub struct ConfigLoader;
 
 impl ConfigLoader {
-    /// Load and validate configuration from a YAML file.
+    /// Load and validate configuration from a YAML or JSON file.
+    ///
+    /// The parser is chosen by extension (`.json`, `.yaml`/`.yml`). Unknown
+    /// extensions are tried as YAML first, then JSON.
     /// Returns HardValidationError for any invalid fields.
+    ///
+    /// Files listed under `include:` are resolved relative to the including
+    /// file's directory and their functions are merged before validation.
+    /// Relative `handler_path` values are resolved against the directory of
+    /// the file that declares the function.
     pub fn load_file(path: impl AsRef<Path>) -> AetherResult<Config> {
+        Self::load_file_with(path, &LoadOptions::default())
+    }
+
+    /// Load and validate configuration from a file, as [`ConfigLoader::load_file`],
+    /// with extra checks enabled by `options`.
+    pub fn load_file_with(path: impl AsRef<Path>, options: &LoadOptions) -> AetherResult<Config> {
         let path = path.as_ref();
+        let mut raw = Self::parse_file(path)?;
+
+        let canonical = path.canonicalize().map_err(|e| AetherError::Io {
+            context: "resolving config file path",
+            source: e,
+        })?;
+        let base_dir = canonical.parent().unwrap_or(Path::new(".")).to_path_buf();
+        Self::set_base_dir(&mut raw, &base_dir);
+        Self::resolve_includes(&mut raw, &base_dir, &mut vec![canonical])?;
+
+        Self::validate(raw, options)
+    }
 
+    /// Load and validate configuration from a YAML string.
+    ///
+    /// Relative `include:` paths are resolved against the current directory.
+    /// Relative `handler_path` values are left as-is (i.e. relative to the
+    /// working directory the handler is spawned from).
+    pub fn load_string(content: &str) -> AetherResult<Config> {
+        let mut raw = Self::parse_yaml(content)?;
+        Self::resolve_includes(&mut raw, Path::new("."), &mut Vec::new())?;
+        Self::validate(raw, &LoadOptions::default())
+    }
+
+    /// Load and validate configuration from a JSON string.
+    ///
+    /// Relative `include:` paths are resolved against the current directory,
+    /// as with [`ConfigLoader::load_string`].
+    pub fn load_json_string(content: &str) -> AetherResult<Config> {
+        let mut raw = Self::parse_json(content)?;
+        Self::resolve_includes(&mut raw, Path::new("."), &mut Vec::new())?;
+        Self::validate(raw, &LoadOptions::default())
+    }
+
+    /// Load a config file and report how it differs from `running`, e.g. a
+    /// [`FunctionRegistry::snapshot`](crate::FunctionRegistry::snapshot) of
+    /// an orchestrator that has not been reloaded since the file was edited.
+    ///
+    /// Fails as [`ConfigLoader::load_file`] does if the file is invalid.
+    pub fn diff(running: &RegistrySnapshot, path: impl AsRef<Path>) -> AetherResult<ConfigDiff> {
+        let config = Self::load_file(path)?;
+        Ok(ConfigDiff::between(running, &config.functions))
+    }
+
+    /// Read and parse a raw config file, choosing the parser by extension.
+    fn parse_file(path: &Path) -> AetherResult<RawConfig> {
         if !path.exists() {
             return Err(AetherError::ConfigNotFound {
                 path: path.to_path_buf(),
@@ -126,23 +540,97 @@ impl ConfigLoader {
             source: e,
         })?;
 
-        Self::load_string(&content)
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("json") => Self::parse_json(&content),
+            Some("yaml") | Some("yml") => Self::parse_yaml(&content),
+            _ => Self::parse_yaml(&content).or_else(|_| Self::parse_json(&content)),
+        }
     }
 
-    /// Load and validate configuration from a YAML string.
-    pub fn load_string(content: &str) -> AetherResult<Config> {
-        let raw: RawConfig =
-            serde_yaml::from_str(content).map_err(|e| AetherError::ConfigParse {
-                message: format!("YAML parse error: {}", e),
+    /// Recursively merge the functions of included files into `raw`.
+    ///
+    /// `chain` holds the canonical paths of the files currently being loaded
+    /// and is used to detect include cycles.
+    fn resolve_includes(
+        raw: &mut RawConfig,
+        base_dir: &Path,
+        chain: &mut Vec<PathBuf>,
+    ) -> AetherResult<()> {
+        for include in std::mem::take(&mut raw.include) {
+            let path = base_dir.join(&include);
+            if !path.exists() {
+                return Err(AetherError::ConfigNotFound { path });
+            }
+
+            let canonical = path.canonicalize().map_err(|e| AetherError::Io {
+                context: "resolving included config path",
+                source: e,
             })?;
 
-        Self::validate(raw)
+            if chain.contains(&canonical) {
+                let cycle: Vec<String> = chain
+                    .iter()
+                    .chain(std::iter::once(&canonical))
+                    .map(|p| p.display().to_string())
+                    .collect();
+                return Err(AetherError::ConfigParse {
+                    message: format!("Cyclic include detected: {}", cycle.join(" -> ")),
+                });
+            }
+
+            let mut included = Self::parse_file(&canonical)?;
+            if included.orchestrator.is_some() {
+                return Err(HardValidationError::SchemaValidation {
+                    message: format!(
+                        "orchestrator block is only allowed in the root config (found in {})",
+                        canonical.display()
+                    ),
+                }
+                .into());
+            }
+
+            let included_dir = canonical.parent().unwrap_or(Path::new(".")).to_path_buf();
+            Self::set_base_dir(&mut included, &included_dir);
+            chain.push(canonical);
+            Self::resolve_includes(&mut included, &included_dir, chain)?;
+            chain.pop();
+
+            raw.functions.append(&mut included.functions);
+        }
+
+        Ok(())
+    }
+
+    /// Record the directory a file's functions were declared in.
+    fn set_base_dir(raw: &mut RawConfig, dir: &Path) {
+        for func in &mut raw.functions {
+            func.base_dir = Some(dir.to_path_buf());
+        }
+    }
+
+    /// Parse raw configuration from YAML.
+    fn parse_yaml(content: &str) -> AetherResult<RawConfig> {
+        serde_yaml::from_str(content).map_err(|e| AetherError::ConfigParse {
+            message: format!("YAML parse error: {}", e),
+        })
+    }
+
+    /// Parse raw configuration from JSON.
+    fn parse_json(content: &str) -> AetherResult<RawConfig> {
+        serde_json::from_str(content).map_err(|e| AetherError::ConfigParse {
+            message: format!("JSON parse error: {}", e),
+        })
     }
 
     /// Validate raw configuration and convert to validated types.
-    fn validate(raw: RawConfig) -> AetherResult<Config> {
+    fn validate(raw: RawConfig, options: &LoadOptions) -> AetherResult<Config> {
         // Validate orchestrator config
-        let orchestrator = Self::validate_orchestrator(raw.orchestrator)?;
+        let orchestrator = Self::validate_orchestrator(raw.orchestrator.unwrap_or_default())?;
 
         // Validate all functions
         let mut functions = Vec::with_capacity(raw.functions.len());
@@ -150,7 +638,8 @@ impl ConfigLoader {
         let mut seen_ports = std::collections::HashSet::new();
 
         for (index, raw_func) in raw.functions.into_iter().enumerate() {
-            let func = Self::validate_function(raw_func, index)?;
+            let func = Self::validate_function(raw_func, index, options)?;
+            Self::check_port_policy(func.trigger_port, &orchestrator, options)?;
 
             // Check for duplicate IDs
             if !seen_ids.insert(func.id.as_str().to_string()) {
@@ -227,18 +716,129 @@ impl ConfigLoader {
             .into());
         }
 
-        let snapshot_dir = std::path::PathBuf::from(&raw.snapshot_dir);
+        let snapshot_dir = std::path::PathBuf::from(expand_env("snapshot_dir", &raw.snapshot_dir)?);
+
+        let max_snapshot_bytes = match raw.max_snapshot_mb {
+            Some(mb) if mb == 0 || mb.checked_mul(1024 * 1024).is_none() => {
+                return Err(HardValidationError::InvalidFieldValue {
+                    field: "max_snapshot_mb",
+                    value: mb.to_string(),
+                    reason: "Must be positive and fit in bytes as a u64".to_string(),
+                }
+                .into());
+            }
+            mb => mb.map(|mb| mb * 1024 * 1024),
+        };
+
+        let mut criu = CriuOptions {
+            extra_dump_args: raw.criu.extra_dump_args,
+            extra_restore_args: raw.criu.extra_restore_args,
+            ..CriuOptions::default()
+        };
+        if let Some(verbosity) = raw.criu.verbosity {
+            if verbosity > 4 {
+                return Err(HardValidationError::InvalidFieldValue {
+                    field: "criu.verbosity",
+                    value: verbosity.to_string(),
+                    reason: "Must be between 0 and 4".to_string(),
+                }
+                .into());
+            }
+            criu.verbosity = verbosity;
+        }
+
+        let admin_listen = raw
+            .admin_listen
+            .map(|listen| {
+                listen
+                    .parse()
+                    .map_err(|_| HardValidationError::InvalidFieldValue {
+                        field: "admin_listen",
+                        value: listen.clone(),
+                        reason: "Must be an address and port, e.g. 127.0.0.1:9090".to_string(),
+                    })
+            })
+            .transpose()?;
+
+        // Usually `${VAR}`, so the secret stays out of the file. The value is
+        // never echoed in errors.
+        let admin_token = match raw.admin_token {
+            Some(token) => {
+                let token = expand_env("admin_token", &token).map_err(|e| match e {
+                    HardValidationError::InvalidFieldValue { field, reason, .. } => {
+                        HardValidationError::InvalidFieldValue {
+                            field,
+                            value: String::new(),
+                            reason,
+                        }
+                    }
+                    other => other,
+                })?;
+                if token.is_empty() {
+                    return Err(HardValidationError::InvalidFieldValue {
+                        field: "admin_token",
+                        value: String::new(),
+                        reason: "Must not be empty".to_string(),
+                    }
+                    .into());
+                }
+                Some(token)
+            }
+            None => None,
+        };
 
         Ok(OrchestratorConfig {
             shm_buffer_size: raw.shm_buffer_size,
             warm_pool_size: raw.warm_pool_size,
             restore_timeout_ms: raw.restore_timeout_ms,
             snapshot_dir,
+            require_memory_snapshot_dir: raw.require_memory_snapshot_dir,
+            max_snapshot_bytes,
+            criu,
+            xdp_attach_mode: raw.xdp_attach_mode,
+            allow_privileged_ports: raw.allow_privileged_ports,
+            admin_listen,
+            admin_token,
         })
     }
 
+    /// Apply the port policy: the `Port` type accepts any non-zero port.
+    fn check_port_policy(
+        port: Port,
+        orchestrator: &OrchestratorConfig,
+        options: &LoadOptions,
+    ) -> AetherResult<()> {
+        if options.denied_ports.contains(&port.value()) {
+            return Err(HardValidationError::InvalidPort {
+                port: port.value(),
+                reason: format!("Port {} is on the deny list", port),
+            }
+            .into());
+        }
+
+        if port.value() < PRIVILEGED_PORT_LIMIT
+            && options.deny_privileged_ports
+            && !orchestrator.allow_privileged_ports
+        {
+            return Err(HardValidationError::InvalidPort {
+                port: port.value(),
+                reason: format!(
+                    "Port {} is privileged (below {}); set orchestrator.allow_privileged_ports to use it",
+                    port, PRIVILEGED_PORT_LIMIT
+                ),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
     /// Validate a single function configuration.
-    fn validate_function(raw: RawFunctionConfig, index: usize) -> AetherResult<FunctionConfig> {
+    fn validate_function(
+        raw: RawFunctionConfig,
+        index: usize,
+        options: &LoadOptions,
+    ) -> AetherResult<FunctionConfig> {
         let context = format!("function at index {}", index);
 
         // Validate function ID
@@ -249,21 +849,61 @@ impl ConfigLoader {
             e
         })?;
 
-        // Validate memory limit
-        let memory_limit = MemoryLimit::from_mb(raw.memory_limit_mb).map_err(|e| {
-            HardValidationError::InvalidFieldValue {
-                field: "memory_limit_mb",
-                value: raw.memory_limit_mb.to_string(),
-                reason: e.to_string(),
+        // Validate memory limit (exactly one of memory_limit_mb / memory)
+        let memory_limit = match (raw.memory_limit_mb, raw.memory.as_deref()) {
+            (Some(mb), None) => {
+                MemoryLimit::from_mb(mb).map_err(|e| HardValidationError::InvalidFieldValue {
+                    field: "memory_limit_mb",
+                    value: mb.to_string(),
+                    reason: e.to_string(),
+                })?
             }
-        })?;
+            (None, Some(memory)) => memory.parse::<MemoryLimit>().map_err(|e| match e {
+                e @ HardValidationError::InvalidFieldValue { .. } => e,
+                other => HardValidationError::InvalidFieldValue {
+                    field: "memory",
+                    value: memory.to_string(),
+                    reason: other.to_string(),
+                },
+            })?,
+            (Some(_), Some(memory)) => {
+                return Err(HardValidationError::InvalidFieldValue {
+                    field: "memory",
+                    value: memory.to_string(),
+                    reason: "Specify either memory or memory_limit_mb, not both".to_string(),
+                }
+                .into());
+            }
+            (None, None) => {
+                return Err(HardValidationError::MissingRequiredField {
+                    field: "memory_limit_mb",
+                    context: context.clone(),
+                }
+                .into());
+            }
+        };
+
+        let cpu_limit = raw.cpu_limit_millicores.map(CpuLimit::new).transpose()?;
 
         // Validate trigger port
         let trigger_port = Port::new(raw.trigger_port)?;
 
-        // Validate handler path (existence check is optional at config load time)
-        // In production, we'd validate the path exists
-        let handler_path = HandlerPath::new_unchecked(&raw.handler_path);
+        // Validate handler path. Existence is only checked with strict_paths;
+        // by default handlers may not be installed yet at load time.
+        let handler_path = PathBuf::from(expand_env("handler_path", &raw.handler_path)?);
+        let handler_path = match raw.base_dir {
+            Some(ref base_dir) if handler_path.is_relative() => {
+                let joined = base_dir.join(&handler_path);
+                // Keep the joined path for handlers that do not exist yet
+                joined.canonicalize().unwrap_or(joined)
+            }
+            _ => handler_path,
+        };
+        let handler_path = if options.strict_paths {
+            HandlerPath::new(handler_path)?
+        } else {
+            HandlerPath::new_unchecked(handler_path)
+        };
 
         // Validate timeout
         if raw.timeout_ms == 0 {
@@ -285,8 +925,81 @@ impl ConfigLoader {
             .into());
         }
 
-        // Validate environment variables
-        for key in raw.environment.keys() {
+        // Validate restart settings
+        if raw.max_restarts > 1000 {
+            return Err(HardValidationError::InvalidFieldValue {
+                field: "max_restarts",
+                value: raw.max_restarts.to_string(),
+                reason: "Must not exceed 1000".to_string(),
+            }
+            .into());
+        }
+
+        if raw.restart_backoff_max_ms == 0 || raw.restart_backoff_max_ms > 900_000 {
+            return Err(HardValidationError::InvalidFieldValue {
+                field: "restart_backoff_max_ms",
+                value: raw.restart_backoff_max_ms.to_string(),
+                reason: "Must be between 1 and 900000ms".to_string(),
+            }
+            .into());
+        }
+
+        if raw.max_concurrency == Some(0) {
+            return Err(HardValidationError::InvalidFieldValue {
+                field: "max_concurrency",
+                value: "0".to_string(),
+                reason: "Must be at least 1 (omit the field for unlimited)".to_string(),
+            }
+            .into());
+        }
+
+        if raw.ready_timeout_ms == 0 || raw.ready_timeout_ms > 900_000 {
+            return Err(HardValidationError::InvalidFieldValue {
+                field: "ready_timeout_ms",
+                value: raw.ready_timeout_ms.to_string(),
+                reason: "Must be between 1 and 900000ms".to_string(),
+            }
+            .into());
+        }
+
+        if let Some(interval) = raw.health_check_interval_ms {
+            if interval == 0 {
+                return Err(HardValidationError::InvalidFieldValue {
+                    field: "health_check_interval_ms",
+                    value: "0".to_string(),
+                    reason: "Must be at least 1 (omit the field to disable)".to_string(),
+                }
+                .into());
+            }
+
+            if raw.handshake != HandshakeMode::Socket {
+                return Err(HardValidationError::InvalidFieldValue {
+                    field: "health_check_interval_ms",
+                    value: interval.to_string(),
+                    reason: "Health checks need the socket handshake".to_string(),
+                }
+                .into());
+            }
+        }
+
+        // Validate environment variables. Oversized environments would only
+        // fail at exec time, so they are capped here.
+        if raw.environment.len() > options.max_env_vars {
+            return Err(HardValidationError::InvalidFieldValue {
+                field: "environment",
+                value: format!("{} variables in {}", raw.environment.len(), context),
+                reason: format!("At most {} variables are allowed", options.max_env_vars),
+            }
+            .into());
+        }
+
+        // Sorted so a limit is always reported against the same key
+        let mut raw_environment: Vec<_> = raw.environment.into_iter().collect();
+        raw_environment.sort();
+
+        let mut environment = HashMap::with_capacity(raw_environment.len());
+        let mut environment_bytes = 0;
+        for (key, value) in raw_environment {
             if key.is_empty() {
                 return Err(HardValidationError::InvalidFieldValue {
                     field: "environment",
@@ -295,19 +1008,179 @@ impl ConfigLoader {
                 }
                 .into());
             }
+            if key.contains(['=', '\0']) {
+                return Err(HardValidationError::InvalidFieldValue {
+                    field: "environment",
+                    value: key.escape_debug().to_string(),
+                    reason: "Environment variable names cannot contain '=' or NUL".to_string(),
+                }
+                .into());
+            }
+
+            // Only values are expanded - keys are taken literally
+            let value = expand_env("environment", &value).map_err(|e| match e {
+                HardValidationError::InvalidFieldValue {
+                    field,
+                    value,
+                    reason,
+                } => HardValidationError::InvalidFieldValue {
+                    field,
+                    value: format!("{}={}", key, value),
+                    reason,
+                },
+                other => other,
+            })?;
+
+            if value.contains('\0') {
+                return Err(HardValidationError::InvalidFieldValue {
+                    field: "environment",
+                    value: key,
+                    reason: "Environment variable values cannot contain NUL".to_string(),
+                }
+                .into());
+            }
+            environment_bytes += key.len() + value.len() + 2;
+            if environment_bytes > options.max_env_bytes {
+                return Err(HardValidationError::InvalidFieldValue {
+                    field: "environment",
+                    value: key,
+                    reason: format!(
+                        "Environment exceeds {} bytes in {}",
+                        options.max_env_bytes, context
+                    ),
+                }
+                .into());
+            }
+            environment.insert(key, value);
         }
 
         Ok(FunctionConfig {
             id,
             memory_limit,
+            cpu_limit,
             trigger_port,
             handler_path,
-            environment: raw.environment,
+            environment,
             timeout_ms: raw.timeout_ms,
+            restart_policy: raw.restart_policy,
+            max_restarts: raw.max_restarts,
+            restart_backoff_max_ms: raw.restart_backoff_max_ms,
+            max_concurrency: raw.max_concurrency,
+            ready_timeout_ms: raw.ready_timeout_ms,
+            handshake: raw.handshake,
+            health_check_interval_ms: raw.health_check_interval_ms,
+            restore_mode: raw.restore_mode,
         })
     }
 }
 
+/// Expand `${VAR}`, `$VAR` and `${VAR:-default}` references against the process environment.
+///
+/// Defaults are themselves expanded, so `${A:-${B:-fallback}}` resolves left to right.
+/// Like the shell, `:-` applies when the variable is unset or empty.
+/// A `$` not followed by `{` or a variable name is kept literally.
+fn expand_env(field: &'static str, input: &str) -> Result<String, HardValidationError> {
+    let invalid = |reason: String| HardValidationError::InvalidFieldValue {
+        field,
+        value: input.to_string(),
+        reason,
+    };
+
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(braced) = after.strip_prefix('{') {
+            // Find the matching closing brace, allowing nested ${...} in defaults
+            let mut depth = 1;
+            let mut end = None;
+            let bytes = braced.as_bytes();
+            let mut i = 0;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                        depth += 1;
+                        i += 1;
+                    }
+                    b'}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            end = Some(i);
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+
+            let end = end.ok_or_else(|| invalid("Unterminated '${' in value".to_string()))?;
+            let inner = &braced[..end];
+            let (name, default) = match inner.find(":-") {
+                Some(sep) => (&inner[..sep], Some(&inner[sep + 2..])),
+                None => (inner, None),
+            };
+
+            if !is_env_name(name) {
+                return Err(invalid(format!(
+                    "Invalid environment variable name '{}'",
+                    name
+                )));
+            }
+
+            match (std::env::var(name), default) {
+                (Ok(value), Some(_)) if !value.is_empty() => output.push_str(&value),
+                (Ok(value), None) => output.push_str(&value),
+                (_, Some(default)) => output.push_str(&expand_env(field, default)?),
+                (Err(_), None) => {
+                    return Err(invalid(format!(
+                        "Environment variable '{}' is not set and has no default",
+                        name
+                    )))
+                }
+            }
+
+            rest = &braced[end + 1..];
+        } else {
+            let len = after
+                .char_indices()
+                .take_while(|&(i, c)| {
+                    c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())
+                })
+                .count();
+
+            if len == 0 {
+                output.push('$');
+                rest = after;
+                continue;
+            }
+
+            let name = &after[..len];
+            let value = std::env::var(name).map_err(|_| {
+                invalid(format!(
+                    "Environment variable '{}' is not set and has no default",
+                    name
+                ))
+            })?;
+            output.push_str(&value);
+            rest = &after[len..];
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Check whether a string is a valid environment variable name.
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -421,6 +1294,33 @@ functions:
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_cpu_limit() {
+        let yaml = |limit: &str| {
+            format!(
+                r#"
+functions:
+  - id: test-func
+    memory_limit_mb: 128
+    trigger_port: 8080
+    handler_path: /bin/echo
+    {}
+"#,
+                limit
+            )
+        };
+
+        let config = ConfigLoader::load_string(&yaml("")).unwrap();
+        assert_eq!(config.functions[0].cpu_limit, None);
+
+        let config = ConfigLoader::load_string(&yaml("cpu_limit_millicores: 500")).unwrap();
+        assert_eq!(config.functions[0].cpu_limit.unwrap().millicores(), 500);
+
+        assert!(ConfigLoader::load_string(&yaml("cpu_limit_millicores: 0")).is_err());
+        // More cores than any host has
+        assert!(ConfigLoader::load_string(&yaml("cpu_limit_millicores: 4000000000")).is_err());
+    }
+
     #[test]
     fn test_restore_timeout_too_high() {
         let yaml = r#"
@@ -447,6 +1347,780 @@ functions:
 "#;
         let config = ConfigLoader::load_string(yaml).unwrap();
         assert_eq!(config.functions[0].timeout_ms, 30000);
+        assert_eq!(config.functions[0].restart_policy, RestartPolicy::Never);
+        assert_eq!(config.functions[0].max_concurrency, None);
         assert_eq!(config.orchestrator.restore_timeout_ms, 15);
+        assert_eq!(config.orchestrator.xdp_attach_mode, XdpAttachMode::Auto);
+        assert_eq!(config.orchestrator.max_snapshot_bytes, None);
+        assert_eq!(config.orchestrator.criu, CriuOptions::default());
+    }
+
+    #[test]
+    fn test_criu_options() {
+        let config_with = |criu: &str| {
+            ConfigLoader::load_string(&format!(
+                "orchestrator:\n  criu:\n{}\nfunctions:\n  - id: f\n    memory_limit_mb: 64\n    trigger_port: 8080\n    handler_path: /bin/echo\n",
+                criu
+            ))
+        };
+
+        let config = config_with(
+            "    extra_dump_args: [--ext-unix-sk, --tcp-established]\n    extra_restore_args: [--tcp-established]\n    verbosity: 4",
+        )
+        .unwrap();
+        assert_eq!(
+            config.orchestrator.criu,
+            CriuOptions {
+                extra_dump_args: vec!["--ext-unix-sk".into(), "--tcp-established".into()],
+                extra_restore_args: vec!["--tcp-established".into()],
+                verbosity: 4,
+            }
+        );
+
+        // Verbosity keeps its default when only arguments are given
+        let config = config_with("    extra_dump_args: [--ext-unix-sk]").unwrap();
+        assert_eq!(
+            config.orchestrator.criu.verbosity,
+            CriuOptions::default().verbosity
+        );
+
+        assert!(config_with("    verbosity: 5").is_err());
+    }
+
+    #[test]
+    fn test_max_snapshot_mb() {
+        let config_with = |mb: &str| {
+            ConfigLoader::load_string(&format!(
+                "orchestrator:\n  max_snapshot_mb: {}\nfunctions:\n  - id: f\n    memory_limit_mb: 64\n    trigger_port: 8080\n    handler_path: /bin/echo\n",
+                mb
+            ))
+        };
+
+        let config = config_with("512").unwrap();
+        assert_eq!(
+            config.orchestrator.max_snapshot_bytes,
+            Some(512 * 1024 * 1024)
+        );
+        assert!(config_with("0").is_err());
+    }
+
+    #[test]
+    fn test_xdp_attach_mode() {
+        let config_with = |mode: &str| {
+            ConfigLoader::load_string(&format!(
+                "orchestrator:\n  xdp_attach_mode: {}\nfunctions:\n  - id: f\n    memory_limit_mb: 64\n    trigger_port: 8080\n    handler_path: /bin/echo\n",
+                mode
+            ))
+        };
+
+        let config = config_with("native").unwrap();
+        assert_eq!(config.orchestrator.xdp_attach_mode, XdpAttachMode::Native);
+        let config = config_with("skb").unwrap();
+        assert_eq!(config.orchestrator.xdp_attach_mode, XdpAttachMode::Generic);
+        assert!(config_with("turbo").is_err());
+
+        assert_eq!("hardware".parse().ok(), Some(XdpAttachMode::Offload));
+        assert_eq!("Driver".parse().ok(), Some(XdpAttachMode::Native));
+        assert!("turbo".parse::<XdpAttachMode>().is_err());
+        assert_eq!(XdpAttachMode::Generic.to_string(), "generic");
+    }
+
+    #[test]
+    fn test_restart_policy() {
+        let yaml = r#"
+functions:
+  - id: test-func
+    memory_limit_mb: 128
+    trigger_port: 8080
+    handler_path: /bin/echo
+    restart_policy: on_failure
+    max_restarts: 3
+    restart_backoff_max_ms: 5000
+"#;
+        let config = ConfigLoader::load_string(yaml).unwrap();
+        let func = &config.functions[0];
+        assert_eq!(func.restart_policy, RestartPolicy::OnFailure);
+        assert_eq!(func.max_restarts, 3);
+        assert_eq!(func.restart_backoff_max_ms, 5000);
+
+        assert!(!RestartPolicy::Never.should_restart(false));
+        assert!(RestartPolicy::OnFailure.should_restart(false));
+        assert!(!RestartPolicy::OnFailure.should_restart(true));
+        assert!(RestartPolicy::Always.should_restart(true));
+
+        let invalid = yaml.replace("on_failure", "sometimes");
+        assert!(ConfigLoader::load_string(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_human_readable_memory() {
+        let yaml = r#"
+functions:
+  - id: test-func
+    memory: "1GiB"
+    trigger_port: 8080
+    handler_path: /bin/echo
+"#;
+        let config = ConfigLoader::load_string(yaml).unwrap();
+        assert_eq!(config.functions[0].memory_limit.megabytes(), 1024);
+
+        let unitless = yaml.replace("\"1GiB\"", "\"1024\"");
+        assert!(matches!(
+            ConfigLoader::load_string(&unitless),
+            Err(AetherError::HardValidation(
+                HardValidationError::InvalidFieldValue {
+                    field: "memory",
+                    ..
+                }
+            ))
+        ));
+
+        let both = yaml.replace("memory:", "memory_limit_mb: 128\n    memory:");
+        assert!(ConfigLoader::load_string(&both).is_err());
+
+        let neither = yaml.replace("    memory: \"1GiB\"\n", "");
+        assert!(matches!(
+            ConfigLoader::load_string(&neither),
+            Err(AetherError::HardValidation(
+                HardValidationError::MissingRequiredField { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_max_concurrency() {
+        let yaml = r#"
+functions:
+  - id: test-func
+    memory_limit_mb: 128
+    trigger_port: 8080
+    handler_path: /bin/echo
+    max_concurrency: 8
+"#;
+        let config = ConfigLoader::load_string(yaml).unwrap();
+        assert_eq!(config.functions[0].max_concurrency, Some(8));
+
+        let zero = yaml.replace("max_concurrency: 8", "max_concurrency: 0");
+        assert!(matches!(
+            ConfigLoader::load_string(&zero),
+            Err(AetherError::HardValidation(
+                HardValidationError::InvalidFieldValue {
+                    field: "max_concurrency",
+                    ..
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_ready_timeout() {
+        let yaml = r#"
+functions:
+  - id: test-func
+    memory_limit_mb: 128
+    trigger_port: 8080
+    handler_path: /bin/echo
+"#;
+        let config = ConfigLoader::load_string(yaml).unwrap();
+        assert_eq!(config.functions[0].ready_timeout_ms, 30000);
+
+        let custom = format!("{}    ready_timeout_ms: 250\n", yaml);
+        let config = ConfigLoader::load_string(&custom).unwrap();
+        assert_eq!(config.functions[0].ready_timeout_ms, 250);
+
+        assert_eq!(config.functions[0].handshake, HandshakeMode::Socket);
+
+        let stdout = format!("{}    handshake: stdout\n", yaml);
+        let config = ConfigLoader::load_string(&stdout).unwrap();
+        assert_eq!(config.functions[0].handshake, HandshakeMode::Stdout);
+
+        assert_eq!(config.functions[0].restore_mode, RestoreMode::Eager);
+        let lazy = format!("{}    restore_mode: lazy\n", yaml);
+        let config = ConfigLoader::load_string(&lazy).unwrap();
+        assert_eq!(config.functions[0].restore_mode, RestoreMode::Lazy);
+
+        let health = format!("{}    health_check_interval_ms: 5000\n", yaml);
+        let config = ConfigLoader::load_string(&health).unwrap();
+        assert_eq!(config.functions[0].health_check_interval_ms, Some(5000));
+
+        let health_stdout = format!("{}    handshake: stdout\n", health);
+        assert!(matches!(
+            ConfigLoader::load_string(&health_stdout),
+            Err(AetherError::HardValidation(
+                HardValidationError::InvalidFieldValue {
+                    field: "health_check_interval_ms",
+                    ..
+                }
+            ))
+        ));
+
+        let zero = format!("{}    ready_timeout_ms: 0\n", yaml);
+        assert!(matches!(
+            ConfigLoader::load_string(&zero),
+            Err(AetherError::HardValidation(
+                HardValidationError::InvalidFieldValue {
+                    field: "ready_timeout_ms",
+                    ..
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_json_config() {
+        let json = r#"{
+            "orchestrator": { "warm_pool_size": 4 },
+            "functions": [
+                { "id": "json-func", "memory_limit_mb": 64, "trigger_port": 9000, "handler_path": "/bin/echo" }
+            ]
+        }"#;
+        let config = ConfigLoader::load_json_string(json).unwrap();
+        assert_eq!(config.functions[0].id.as_str(), "json-func");
+        assert_eq!(config.orchestrator.warm_pool_size, 4);
+        assert_eq!(config.functions[0].timeout_ms, 30000);
+    }
+
+    #[test]
+    fn test_json_duplicate_ports() {
+        let json = r#"{
+            "functions": [
+                { "id": "func1", "memory_limit_mb": 128, "trigger_port": 8080, "handler_path": "/bin/echo" },
+                { "id": "func2", "memory_limit_mb": 128, "trigger_port": 8080, "handler_path": "/bin/echo" }
+            ]
+        }"#;
+        let result = ConfigLoader::load_json_string(json);
+        assert!(matches!(
+            result,
+            Err(AetherError::HardValidation(
+                HardValidationError::InvalidPort { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_load_file_by_extension() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let json = r#"{"functions": [{"id": "f", "memory_limit_mb": 128, "trigger_port": 8080, "handler_path": "/bin/echo"}]}"#;
+
+        let json_path = dir.path().join("config.json");
+        std::fs::write(&json_path, json).unwrap();
+        assert!(ConfigLoader::load_file(&json_path).is_ok());
+
+        // Unknown extension falls back from YAML to JSON
+        let conf_path = dir.path().join("config.conf");
+        std::fs::write(&conf_path, VALID_CONFIG).unwrap();
+        assert!(ConfigLoader::load_file(&conf_path).is_ok());
+
+        std::fs::write(&conf_path, "{ not valid").unwrap();
+        match ConfigLoader::load_file(&conf_path) {
+            Err(AetherError::ConfigParse { message }) => assert!(message.starts_with("JSON")),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_diff_against_running() {
+        use crate::registry::SnapshotEntry;
+        use crate::state::FunctionState;
+
+        let running = ConfigLoader::load_string(
+            r#"
+functions:
+  - id: kept
+    memory_limit_mb: 128
+    trigger_port: 8080
+    handler_path: /bin/echo
+  - id: edited
+    memory_limit_mb: 128
+    trigger_port: 8081
+    handler_path: /bin/echo
+    timeout_ms: 30000
+  - id: dropped
+    memory_limit_mb: 128
+    trigger_port: 8082
+    handler_path: /bin/echo
+"#,
+        )
+        .unwrap();
+        let snapshot = RegistrySnapshot {
+            functions: running
+                .functions
+                .into_iter()
+                .map(|config| SnapshotEntry {
+                    config,
+                    state: FunctionState::Running,
+                })
+                .collect(),
+        };
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            r#"
+functions:
+  - id: kept
+    memory_limit_mb: 128
+    trigger_port: 8080
+    handler_path: /bin/echo
+  - id: edited
+    memory_limit_mb: 256
+    trigger_port: 8081
+    handler_path: /bin/echo
+    timeout_ms: 60000
+  - id: fresh
+    memory_limit_mb: 128
+    trigger_port: 8083
+    handler_path: /bin/echo
+"#,
+        )
+        .unwrap();
+
+        let diff = ConfigLoader::diff(&snapshot, &path).unwrap();
+        assert!(!diff.is_empty());
+        assert_eq!(diff.added, vec![FunctionId::new("fresh").unwrap()]);
+        assert_eq!(diff.removed, vec![FunctionId::new("dropped").unwrap()]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].id.as_str(), "edited");
+        let fields: Vec<String> = diff.changed[0]
+            .fields
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                "memory_limit: 134217728 -> 268435456",
+                "timeout_ms: 30000 -> 60000"
+            ]
+        );
+
+        // A file matching the registry reports no drift
+        let unchanged = ConfigDiff::between(
+            &snapshot,
+            &snapshot
+                .functions
+                .iter()
+                .map(|e| e.config.clone())
+                .collect::<Vec<_>>(),
+        );
+        assert!(unchanged.is_empty());
+
+        assert!(ConfigLoader::diff(&snapshot, dir.path().join("missing.yaml")).is_err());
+    }
+
+    #[test]
+    fn test_relative_handler_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("bin")).unwrap();
+        std::fs::create_dir_all(dir.path().join("teams/bin")).unwrap();
+        std::fs::write(dir.path().join("bin/handler"), "").unwrap();
+
+        let relative = VALID_CONFIG.replace("/bin/echo", "./bin/handler");
+        std::fs::write(
+            dir.path().join("teams/api.yaml"),
+            INCLUDED_FUNC.replace("/bin/echo", "bin/missing"),
+        )
+        .unwrap();
+        let root = dir.path().join("app.yaml");
+        std::fs::write(&root, format!("include: [teams/api.yaml]\n{}", relative)).unwrap();
+
+        let config = ConfigLoader::load_file(&root).unwrap();
+        let base = dir.path().canonicalize().unwrap();
+        assert_eq!(
+            config.functions[0].handler_path.as_path(),
+            base.join("bin/handler")
+        );
+        // Included functions resolve against their own file; missing files are kept joined
+        assert_eq!(
+            config.functions[1].handler_path.as_path(),
+            base.join("teams/bin/missing")
+        );
+
+        // Absolute paths are untouched and strings keep paths relative to CWD
+        let config = ConfigLoader::load_string(VALID_CONFIG).unwrap();
+        assert_eq!(
+            config.functions[0].handler_path.as_path(),
+            Path::new("/bin/echo")
+        );
+        let config = ConfigLoader::load_string(&relative).unwrap();
+        assert_eq!(
+            config.functions[0].handler_path.as_path(),
+            Path::new("./bin/handler")
+        );
+    }
+
+    #[test]
+    fn test_strict_paths() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let handler = dir.path().join("handler");
+        std::fs::write(&handler, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&handler, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let config_path = dir.path().join("app.yaml");
+        let strict = LoadOptions {
+            strict_paths: true,
+            ..Default::default()
+        };
+
+        // Lenient by default, even for a missing handler
+        std::fs::write(&config_path, VALID_CONFIG.replace("/bin/echo", "./missing")).unwrap();
+        assert!(ConfigLoader::load_file(&config_path).is_ok());
+        assert!(matches!(
+            ConfigLoader::load_file_with(&config_path, &strict),
+            Err(AetherError::HardValidation(
+                HardValidationError::HandlerPathNotFound { .. }
+            ))
+        ));
+
+        std::fs::write(&config_path, VALID_CONFIG.replace("/bin/echo", "./handler")).unwrap();
+        assert!(matches!(
+            ConfigLoader::load_file_with(&config_path, &strict),
+            Err(AetherError::HardValidation(
+                HardValidationError::HandlerNotExecutable { .. }
+            ))
+        ));
+
+        std::fs::set_permissions(&handler, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let config = ConfigLoader::load_file_with(&config_path, &strict).unwrap();
+        assert_eq!(
+            config.functions[0].handler_path.as_path(),
+            handler.canonicalize().unwrap()
+        );
+    }
+
+    const INCLUDED_FUNC: &str = r#"
+functions:
+  - id: included-func
+    memory_limit_mb: 128
+    trigger_port: 9090
+    handler_path: /bin/echo
+"#;
+
+    #[test]
+    fn test_include_merges_functions() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("teams")).unwrap();
+        std::fs::write(
+            dir.path().join("teams/a.yaml"),
+            format!("include: [b.yaml]\n{}", INCLUDED_FUNC),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("teams/b.yaml"),
+            INCLUDED_FUNC
+                .replace("included-func", "nested-func")
+                .replace("9090", "9091"),
+        )
+        .unwrap();
+
+        let root = dir.path().join("root.yaml");
+        std::fs::write(&root, format!("include: [teams/a.yaml]\n{}", VALID_CONFIG)).unwrap();
+
+        let config = ConfigLoader::load_file(&root).unwrap();
+        let ids: Vec<&str> = config.functions.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["hello-world", "included-func", "nested-func"]);
+    }
+
+    #[test]
+    fn test_include_cross_file_duplicate() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("dup.yaml"),
+            INCLUDED_FUNC.replace("included-func", "hello-world"),
+        )
+        .unwrap();
+        let root = dir.path().join("root.yaml");
+        std::fs::write(&root, format!("include: [dup.yaml]\n{}", VALID_CONFIG)).unwrap();
+
+        assert!(matches!(
+            ConfigLoader::load_file(&root),
+            Err(AetherError::HardValidation(
+                HardValidationError::DuplicateFunctionId { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_include_cycle_detected() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.yaml"), "include: [b.yaml]\n").unwrap();
+        std::fs::write(dir.path().join("b.yaml"), "include: [a.yaml]\n").unwrap();
+
+        match ConfigLoader::load_file(dir.path().join("a.yaml")) {
+            Err(AetherError::ConfigParse { message }) => assert!(message.contains("Cyclic")),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_include_rejects_orchestrator_block() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("inc.yaml"),
+            format!("orchestrator:\n  warm_pool_size: 2\n{}", INCLUDED_FUNC),
+        )
+        .unwrap();
+        let root = dir.path().join("root.yaml");
+        std::fs::write(&root, format!("include: [inc.yaml]\n{}", VALID_CONFIG)).unwrap();
+
+        assert!(matches!(
+            ConfigLoader::load_file(&root),
+            Err(AetherError::HardValidation(
+                HardValidationError::SchemaValidation { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_env_interpolation() {
+        std::env::set_var("AETHER_TEST_INTERP_BIN", "/opt/handlers");
+        std::env::set_var("AETHER_TEST_INTERP_TOKEN", "s3cret");
+        std::env::remove_var("AETHER_TEST_INTERP_UNSET");
+        std::env::remove_var("AETHER_TEST_INTERP_UNSET2");
+
+        let yaml = r#"
+orchestrator:
+  snapshot_dir: ${AETHER_TEST_INTERP_UNSET:-/dev/shm}/snapshots
+functions:
+  - id: test-func
+    memory_limit_mb: 128
+    trigger_port: 8080
+    handler_path: $AETHER_TEST_INTERP_BIN/hello
+    environment:
+      TOKEN: ${AETHER_TEST_INTERP_TOKEN}
+      NESTED: ${AETHER_TEST_INTERP_UNSET:-${AETHER_TEST_INTERP_UNSET2:-fallback}}
+      $LITERAL_KEY: cost is $5
+"#;
+        let config = ConfigLoader::load_string(yaml).unwrap();
+        let func = &config.functions[0];
+        assert_eq!(
+            config.orchestrator.snapshot_dir,
+            std::path::PathBuf::from("/dev/shm/snapshots")
+        );
+        assert_eq!(
+            func.handler_path.as_path(),
+            Path::new("/opt/handlers/hello")
+        );
+        assert_eq!(func.environment["TOKEN"], "s3cret");
+        assert_eq!(func.environment["NESTED"], "fallback");
+        assert_eq!(func.environment["$LITERAL_KEY"], "cost is $5");
+    }
+
+    #[test]
+    fn test_env_interpolation_missing_variable() {
+        std::env::remove_var("AETHER_TEST_INTERP_MISSING");
+
+        let yaml = r#"
+functions:
+  - id: test-func
+    memory_limit_mb: 128
+    trigger_port: 8080
+    handler_path: /bin/echo
+    environment:
+      API_KEY: ${AETHER_TEST_INTERP_MISSING}
+"#;
+        let err = ConfigLoader::load_string(yaml).unwrap_err();
+        match err {
+            AetherError::HardValidation(HardValidationError::InvalidFieldValue {
+                field,
+                value,
+                reason,
+            }) => {
+                assert_eq!(field, "environment");
+                assert!(value.starts_with("API_KEY="));
+                assert!(reason.contains("AETHER_TEST_INTERP_MISSING"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_privileged_ports() {
+        let config = |port: u16, allow: bool| {
+            format!(
+                "orchestrator:\n  allow_privileged_ports: {}\nfunctions:\n  - id: web\n    memory_limit_mb: 128\n    trigger_port: {}\n    handler_path: /bin/echo\n",
+                allow, port
+            )
+        };
+
+        // 80 is rejected by default
+        match ConfigLoader::load_string(&config(80, false)) {
+            Err(AetherError::HardValidation(HardValidationError::InvalidPort { port, reason })) => {
+                assert_eq!(port, 80);
+                assert!(reason.contains("allow_privileged_ports"));
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+
+        // 8080 is allowed
+        let loaded = ConfigLoader::load_string(&config(8080, false)).unwrap();
+        assert!(!loaded.orchestrator.allow_privileged_ports);
+
+        // 80 with the override
+        let loaded = ConfigLoader::load_string(&config(80, true)).unwrap();
+        assert_eq!(loaded.functions[0].trigger_port.value(), 80);
+        assert!(loaded.orchestrator.allow_privileged_ports);
+
+        // The loader can turn the check off
+        let options = LoadOptions {
+            deny_privileged_ports: false,
+            ..Default::default()
+        };
+        let raw = ConfigLoader::parse_yaml(&config(80, false)).unwrap();
+        assert!(ConfigLoader::validate(raw, &options).is_ok());
+    }
+
+    #[test]
+    fn test_denied_ports() {
+        let options = LoadOptions {
+            denied_ports: vec![8080],
+            ..Default::default()
+        };
+        let raw = ConfigLoader::parse_yaml(VALID_CONFIG).unwrap();
+        assert!(matches!(
+            ConfigLoader::validate(raw, &options),
+            Err(AetherError::HardValidation(
+                HardValidationError::InvalidPort { port: 8080, .. }
+            ))
+        ));
+
+        // The privileged-port override does not lift the deny list
+        let yaml = VALID_CONFIG.replace(
+            "orchestrator:",
+            "orchestrator:\n  allow_privileged_ports: true",
+        );
+        let raw = ConfigLoader::parse_yaml(&yaml).unwrap();
+        assert!(ConfigLoader::validate(raw, &options).is_err());
+    }
+
+    #[test]
+    fn test_admin_settings() {
+        let config = |orchestrator: &str| {
+            format!(
+                "orchestrator:\n{}\nfunctions:\n  - id: web\n    memory_limit_mb: 128\n    trigger_port: 8080\n    handler_path: /bin/echo\n",
+                orchestrator
+            )
+        };
+
+        let loaded = ConfigLoader::load_string(&config("  warm_pool_size: 1")).unwrap();
+        assert_eq!(loaded.orchestrator.admin_listen, None);
+        assert_eq!(loaded.orchestrator.admin_token, None);
+
+        std::env::set_var("AETHER_TEST_ADMIN_TOKEN", "s3cret");
+        let loaded = ConfigLoader::load_string(&config(
+            "  admin_listen: 127.0.0.1:9090\n  admin_token: ${AETHER_TEST_ADMIN_TOKEN}",
+        ))
+        .unwrap();
+        assert_eq!(
+            loaded.orchestrator.admin_listen,
+            Some("127.0.0.1:9090".parse().unwrap())
+        );
+        assert_eq!(loaded.orchestrator.admin_token.as_deref(), Some("s3cret"));
+
+        for (orchestrator, field) in [
+            ("  admin_listen: localhost", "admin_listen"),
+            ("  admin_token: \"\"", "admin_token"),
+            (
+                "  admin_token: ${AETHER_TEST_UNSET_ADMIN_TOKEN}",
+                "admin_token",
+            ),
+        ] {
+            match ConfigLoader::load_string(&config(orchestrator)) {
+                Err(AetherError::HardValidation(HardValidationError::InvalidFieldValue {
+                    field: f,
+                    value,
+                    ..
+                })) => {
+                    assert_eq!(f, field);
+                    if field == "admin_token" {
+                        assert!(value.is_empty());
+                    }
+                }
+                other => panic!("unexpected result: {:?}", other.map(|_| ())),
+            }
+        }
+    }
+
+    /// Validate `yaml` with `options`, returning the environment error's
+    /// value and reason.
+    fn environment_error(yaml: &str, options: &LoadOptions) -> (String, String) {
+        let raw = ConfigLoader::parse_yaml(yaml).unwrap();
+        match ConfigLoader::validate(raw, options) {
+            Err(AetherError::HardValidation(HardValidationError::InvalidFieldValue {
+                field: "environment",
+                value,
+                reason,
+            })) => (value, reason),
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+
+    fn config_with_environment(entries: &str) -> String {
+        format!(
+            "functions:\n  - id: test-func\n    memory_limit_mb: 128\n    trigger_port: 8080\n    handler_path: /bin/echo\n    environment:\n{}",
+            entries
+        )
+    }
+
+    #[test]
+    fn test_environment_count_limit() {
+        let entries: String = (0..=DEFAULT_MAX_ENV_VARS)
+            .map(|i| format!("      VAR_{}: x\n", i))
+            .collect();
+        let (value, reason) =
+            environment_error(&config_with_environment(&entries), &LoadOptions::default());
+        assert!(value.starts_with("257 variables"));
+        assert!(reason.contains("256"));
+
+        let options = LoadOptions {
+            max_env_vars: 2,
+            ..Default::default()
+        };
+        let yaml = config_with_environment("      A: x\n      B: y\n");
+        assert!(ConfigLoader::validate(ConfigLoader::parse_yaml(&yaml).unwrap(), &options).is_ok());
+        let yaml = config_with_environment("      A: x\n      B: y\n      C: z\n");
+        environment_error(&yaml, &options);
+    }
+
+    #[test]
+    fn test_environment_size_limit() {
+        let huge = "x".repeat(DEFAULT_MAX_ENV_BYTES);
+        let yaml = config_with_environment(&format!("      SMALL: x\n      HUGE: {}\n", huge));
+        let (value, reason) = environment_error(&yaml, &LoadOptions::default());
+        assert_eq!(value, "HUGE");
+        assert!(reason.contains("1048576 bytes"));
+
+        // Each variable counts as KEY=value plus a NUL: 10 bytes for A and B
+        let options = LoadOptions {
+            max_env_bytes: 10,
+            ..Default::default()
+        };
+        let yaml = config_with_environment("      A: xyz\n      B: x\n");
+        assert!(ConfigLoader::validate(ConfigLoader::parse_yaml(&yaml).unwrap(), &options).is_ok());
+        let yaml = config_with_environment("      A: xyz\n      B: xy\n");
+        let (value, _) = environment_error(&yaml, &options);
+        assert_eq!(value, "B");
+    }
+
+    #[test]
+    fn test_environment_invalid_characters() {
+        let options = LoadOptions::default();
+
+        let (value, reason) =
+            environment_error(&config_with_environment("      \"A=B\": x\n"), &options);
+        assert_eq!(value, "A=B");
+        assert!(reason.contains("'='"));
+
+        let (value, reason) =
+            environment_error(&config_with_environment("      \"A\\0B\": x\n"), &options);
+        assert_eq!(value, "A\\0B");
+        assert!(reason.contains("NUL"));
+
+        let (value, reason) =
+            environment_error(&config_with_environment("      A: \"x\\0y\"\n"), &options);
+        assert_eq!(value, "A");
+        assert!(reason.contains("NUL"));
     }
 }
@@ -9,5 +9,7 @@
 mod process;
 mod snapshot;
 
-pub use process::FunctionProcess;
-pub use snapshot::SnapshotManager;
+pub use process::{
+    encode_control_frame, health_check_stream, FunctionProcess, CONTROL_FRAME_HEADER_SIZE,
+};
+pub use snapshot::{check_snapshot_dir, CriuOptions, RestoreJob, SnapshotManager};
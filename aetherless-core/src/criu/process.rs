@@ -3,23 +3,113 @@
 
 //! Function process management.
 //!
-//! Spawns function processes and waits for READY signal on Unix socket.
+//! Spawns function processes and waits for READY signal on a Unix socket
+//! or, for simple scripts, as a line on stdout.
+//!
+//! # Control socket framing
+//!
+//! After READY, messages on the control socket are framed as:
+//!
+//! ```text
+//! ┌──────────────────┬──────────────────┬─────────────────────┐
+//! │ length: u32 (LE) │ type: u32 (LE)   │ body (length bytes) │
+//! └──────────────────┴──────────────────┴─────────────────────┘
+//! ```
+//!
+//! `length` counts only the body. `type` is a [`PayloadType`]. A handler
+//! answers each `HealthPing` (3, empty body) with a `HealthPong` (4, empty
+//! body).
 
-use std::io::{Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
+use crate::config::HandshakeMode;
 use crate::error::CriuError;
-use crate::types::{FunctionId, HandlerPath};
-
-/// Timeout for waiting for READY signal.
-const READY_TIMEOUT: Duration = Duration::from_secs(30);
+use crate::shm::PayloadType;
+use crate::types::{ExitReason, FunctionId, HandlerPath};
 
 /// Ready signal message.
 const READY_SIGNAL: &[u8] = b"READY";
 
+/// Size of a control frame header (length + type).
+pub const CONTROL_FRAME_HEADER_SIZE: usize = 8;
+
+/// Largest control frame body accepted from a handler.
+const MAX_CONTROL_BODY: usize = 64 * 1024;
+
+/// Encode a control socket frame.
+pub fn encode_control_frame(payload_type: PayloadType, body: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(CONTROL_FRAME_HEADER_SIZE + body.len());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&(payload_type as u32).to_le_bytes());
+    frame.extend_from_slice(body);
+    frame
+}
+
+/// Send a `HealthPing` on a control socket and wait for the `HealthPong`.
+///
+/// A closed stream fails immediately rather than waiting out the timeout.
+pub fn health_check_stream(stream: &mut UnixStream, timeout: Duration) -> Result<(), CriuError> {
+    let failed = |reason: String| CriuError::HealthCheckFailed { reason };
+    let io_failed = |what: &str, e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+            failed(format!("No {} within {}ms", what, timeout.as_millis()))
+        }
+        std::io::ErrorKind::UnexpectedEof
+        | std::io::ErrorKind::BrokenPipe
+        | std::io::ErrorKind::ConnectionReset => failed("Control socket closed".to_string()),
+        _ => failed(format!("{} failed: {}", what, e)),
+    };
+
+    let start = Instant::now();
+    stream
+        .set_nonblocking(false)
+        .and_then(|()| stream.set_write_timeout(Some(timeout)))
+        .map_err(|e| failed(format!("Failed to configure socket: {}", e)))?;
+
+    stream
+        .write_all(&encode_control_frame(PayloadType::HealthPing, &[]))
+        .map_err(|e| io_failed("ping", e))?;
+
+    loop {
+        let remaining = timeout.saturating_sub(start.elapsed());
+        if remaining.is_zero() {
+            return Err(failed(format!("No pong within {}ms", timeout.as_millis())));
+        }
+        stream
+            .set_read_timeout(Some(remaining))
+            .map_err(|e| failed(format!("Failed to configure socket: {}", e)))?;
+
+        let mut header = [0u8; CONTROL_FRAME_HEADER_SIZE];
+        stream
+            .read_exact(&mut header)
+            .map_err(|e| io_failed("pong", e))?;
+
+        let length = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let raw_type = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+        if length > MAX_CONTROL_BODY {
+            return Err(failed(format!("Oversized control frame: {} bytes", length)));
+        }
+
+        let mut body = vec![0u8; length];
+        stream
+            .read_exact(&mut body)
+            .map_err(|e| io_failed("pong", e))?;
+
+        match PayloadType::try_from(raw_type) {
+            Ok(PayloadType::HealthPong) => return Ok(()),
+            // Anything else is unrelated traffic; keep waiting for the pong
+            Ok(_) => continue,
+            Err(_) => return Err(failed(format!("Unknown frame type: {}", raw_type))),
+        }
+    }
+}
+
 /// Function process wrapper.
 ///
 /// Manages the lifecycle of a function process including spawning
@@ -40,50 +130,54 @@ pub struct FunctionProcess {
 impl FunctionProcess {
     /// Spawn a new function process.
     ///
-    /// Creates a Unix socket for control communication, spawns the handler
-    /// process, and waits for the READY signal.
+    /// Spawns the handler process and waits for the READY signal, either on
+    /// a Unix control socket or as a line on the handler's stdout depending
+    /// on `handshake`.
     ///
     /// # Arguments
     /// * `function_id` - ID of the function
     /// * `handler_path` - Path to the handler executable
     /// * `socket_dir` - Directory for the control socket
+    /// * `ready_timeout` - How long to wait for READY (exported to the
+    ///   handler as `AETHER_READY_TIMEOUT_MS`)
+    /// * `handshake` - How the handler signals READY
     ///
     /// # Errors
-    /// Returns CriuError if spawn fails or READY timeout is reached.
+    /// Returns CriuError if spawn fails or READY timeout is reached. The
+    /// process is killed in either handshake mode.
     pub fn spawn(
         function_id: &FunctionId,
         handler_path: &HandlerPath,
         socket_dir: &Path,
+        ready_timeout: Duration,
+        handshake: HandshakeMode,
     ) -> Result<Self, CriuError> {
         // Create socket path
         let socket_path = socket_dir.join(format!("{}.sock", function_id));
 
-        // Remove old socket if exists
-        let _ = std::fs::remove_file(&socket_path);
-
-        // Create Unix listener
-        let listener = UnixListener::bind(&socket_path).map_err(|e| CriuError::UnixSocket {
-            reason: format!("Failed to bind socket {}: {}", socket_path.display(), e),
-        })?;
-
-        // Set socket to non-blocking for timeout handling
-        listener
-            .set_nonblocking(true)
-            .map_err(|e| CriuError::UnixSocket {
-                reason: format!("Failed to set non-blocking: {}", e),
-            })?;
+        let listener = match handshake {
+            HandshakeMode::Socket => Some(Self::bind_listener(&socket_path)?),
+            HandshakeMode::Stdout => None,
+        };
 
         // Spawn the handler process
-        let child = Command::new(handler_path.as_path())
-            .env("AETHER_SOCKET", &socket_path)
+        let mut command = Command::new(handler_path.as_path());
+        command
             .env("AETHER_FUNCTION_ID", function_id.as_str())
+            .env(
+                "AETHER_READY_TIMEOUT_MS",
+                ready_timeout.as_millis().to_string(),
+            )
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| CriuError::SpawnFailed {
-                reason: format!("Failed to spawn {}: {}", handler_path, e),
-            })?;
+            .stderr(Stdio::piped());
+        if listener.is_some() {
+            command.env("AETHER_SOCKET", &socket_path);
+        }
+
+        let mut child = command.spawn().map_err(|e| CriuError::SpawnFailed {
+            reason: format!("Failed to spawn {}: {}", handler_path, e),
+        })?;
 
         let pid = child.id();
 
@@ -91,32 +185,88 @@ impl FunctionProcess {
             function_id = %function_id,
             pid = pid,
             handler = %handler_path,
+            handshake = ?handshake,
             "Spawned function process"
         );
 
         // Wait for READY signal with timeout
         let start = Instant::now();
-        let mut stream = None;
+        let ready = match &listener {
+            Some(listener) => Self::wait_socket_ready(listener, start, ready_timeout).map(Some),
+            None => Self::wait_stdout_ready(&mut child, start, ready_timeout).map(|()| None),
+        };
+
+        let stream = match ready {
+            Ok(stream) => stream,
+            Err(e) => {
+                // Kill the process since it didn't respond
+                let _ = Command::new("kill").arg("-9").arg(pid.to_string()).status();
+                return Err(e);
+            }
+        };
+
+        tracing::info!(
+            function_id = %function_id,
+            pid = pid,
+            elapsed_ms = start.elapsed().as_millis(),
+            "Function sent READY signal"
+        );
+
+        Ok(Self {
+            function_id: function_id.clone(),
+            child,
+            socket_path,
+            pid,
+            stream,
+        })
+    }
+
+    /// Bind the non-blocking control socket listener.
+    fn bind_listener(socket_path: &Path) -> Result<UnixListener, CriuError> {
+        // Remove old socket if exists
+        let _ = std::fs::remove_file(socket_path);
+
+        // Create Unix listener
+        let listener = UnixListener::bind(socket_path).map_err(|e| CriuError::UnixSocket {
+            reason: format!("Failed to bind socket {}: {}", socket_path.display(), e),
+        })?;
 
-        while start.elapsed() < READY_TIMEOUT {
+        // Set socket to non-blocking for timeout handling
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| CriuError::UnixSocket {
+                reason: format!("Failed to set non-blocking: {}", e),
+            })?;
+
+        Ok(listener)
+    }
+
+    /// Wait for a connection on the control socket that sends READY.
+    fn wait_socket_ready(
+        listener: &UnixListener,
+        start: Instant,
+        ready_timeout: Duration,
+    ) -> Result<UnixStream, CriuError> {
+        let mut connected = false;
+
+        while start.elapsed() < ready_timeout {
             match listener.accept() {
                 Ok((mut s, _)) => {
+                    connected = true;
+                    // Don't let a silent connection outlast the READY deadline
+                    let remaining = ready_timeout
+                        .saturating_sub(start.elapsed())
+                        .clamp(Duration::from_millis(1), Duration::from_secs(5));
                     s.set_nonblocking(false).ok();
-                    s.set_read_timeout(Some(Duration::from_secs(5))).ok();
+                    s.set_read_timeout(Some(remaining)).ok();
 
                     let mut buf = [0u8; 16];
                     match s.read(&mut buf) {
-                        Ok(n) if n >= READY_SIGNAL.len() => {
-                            if &buf[..READY_SIGNAL.len()] == READY_SIGNAL {
-                                tracing::info!(
-                                    function_id = %function_id,
-                                    pid = pid,
-                                    elapsed_ms = start.elapsed().as_millis(),
-                                    "Function sent READY signal"
-                                );
-                                stream = Some(s);
-                                break;
-                            }
+                        Ok(n)
+                            if n >= READY_SIGNAL.len()
+                                && &buf[..READY_SIGNAL.len()] == READY_SIGNAL =>
+                        {
+                            return Ok(s);
                         }
                         _ => {}
                     }
@@ -132,20 +282,52 @@ impl FunctionProcess {
             }
         }
 
-        if stream.is_none() {
-            // Kill the process since it didn't respond
-            let _ = Command::new("kill").arg("-9").arg(pid.to_string()).status();
+        let waited_ms = start.elapsed().as_millis() as u64;
+        Err(if connected {
+            CriuError::ReadyNotSent { waited_ms }
+        } else {
+            CriuError::ReadyTimeout { waited_ms }
+        })
+    }
 
-            return Err(CriuError::ReadyTimeout);
-        }
+    /// Wait for a line equal to READY on the child's stdout.
+    ///
+    /// Stdout keeps being drained after READY so the handler never blocks
+    /// on a full pipe.
+    fn wait_stdout_ready(
+        child: &mut Child,
+        start: Instant,
+        ready_timeout: Duration,
+    ) -> Result<(), CriuError> {
+        let stdout = child.stdout.take().ok_or_else(|| CriuError::SpawnFailed {
+            reason: "Handler stdout was not captured".to_string(),
+        })?;
 
-        Ok(Self {
-            function_id: function_id.clone(),
-            child,
-            socket_path,
-            pid,
-            stream,
-        })
+        let (ready_tx, ready_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut ready_tx = Some(ready_tx);
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if line.as_bytes() == READY_SIGNAL {
+                    if let Some(tx) = ready_tx.take() {
+                        let _ = tx.send(());
+                    }
+                }
+            }
+        });
+
+        match ready_rx.recv_timeout(ready_timeout.saturating_sub(start.elapsed())) {
+            Ok(()) => Ok(()),
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(CriuError::ReadyTimeout {
+                waited_ms: start.elapsed().as_millis() as u64,
+            }),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(CriuError::SpawnFailed {
+                reason: format!(
+                    "Handler closed stdout without sending READY after {}ms",
+                    start.elapsed().as_millis()
+                ),
+            }),
+        }
     }
 
     /// Get the process ID.
@@ -163,6 +345,20 @@ impl FunctionProcess {
         &self.socket_path
     }
 
+    /// Check the process is responsive over its control socket.
+    ///
+    /// Sends a `HealthPing` frame and expects a `HealthPong` within
+    /// `timeout`. Fails immediately if the socket is closed or the process
+    /// uses the stdout handshake and has no control socket.
+    pub fn health_check(&mut self, timeout: Duration) -> Result<(), CriuError> {
+        match self.stream {
+            Some(ref mut stream) => health_check_stream(stream, timeout),
+            None => Err(CriuError::HealthCheckFailed {
+                reason: "No connection to process".to_string(),
+            }),
+        }
+    }
+
     /// Send a message to the process.
     pub fn send(&mut self, message: &[u8]) -> Result<(), CriuError> {
         if let Some(ref mut stream) = self.stream {
@@ -184,10 +380,16 @@ impl FunctionProcess {
 
     /// Check if the process is still running.
     pub fn is_running(&mut self) -> bool {
+        self.exit_reason() == Some(ExitReason::Running)
+    }
+
+    /// How the process ended, or [`ExitReason::Running`] if it has not.
+    /// `None` if its status could not be read.
+    pub fn exit_reason(&mut self) -> Option<ExitReason> {
         match self.child.try_wait() {
-            Ok(Some(_)) => false,
-            Ok(None) => true,
-            Err(_) => false,
+            Ok(Some(status)) => Some(ExitReason::from_status(status)),
+            Ok(None) => Some(ExitReason::Running),
+            Err(_) => None,
         }
     }
 
@@ -220,4 +422,212 @@ mod tests {
     fn test_ready_signal_constant() {
         assert_eq!(READY_SIGNAL, b"READY");
     }
+
+    /// Write an executable handler script into `dir`.
+    fn handler(dir: &Path, name: &str, script: &str) -> HandlerPath {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join(name);
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        HandlerPath::new(path).unwrap()
+    }
+
+    #[test]
+    fn test_ready_handshake() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = FunctionId::new("ready-func").unwrap();
+        // Only signals READY if the timeout was exported
+        let handler = handler(
+            dir.path(),
+            "ready.py",
+            "#!/usr/bin/env python3\n\
+             import os, socket, time\n\
+             assert os.environ['AETHER_READY_TIMEOUT_MS'] == '5000'\n\
+             s = socket.socket(socket.AF_UNIX)\n\
+             s.connect(os.environ['AETHER_SOCKET'])\n\
+             s.sendall(b'READY')\n\
+             time.sleep(30)\n",
+        );
+
+        let mut process = FunctionProcess::spawn(
+            &id,
+            &handler,
+            dir.path(),
+            Duration::from_secs(5),
+            HandshakeMode::Socket,
+        )
+        .unwrap();
+        assert!(process.is_running());
+        process.kill().unwrap();
+    }
+
+    #[test]
+    fn test_ready_timeout_never_connects() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = FunctionId::new("silent-func").unwrap();
+        let handler = handler(dir.path(), "silent.sh", "#!/bin/sh\nsleep 30\n");
+
+        let result = FunctionProcess::spawn(
+            &id,
+            &handler,
+            dir.path(),
+            Duration::from_millis(200),
+            HandshakeMode::Socket,
+        );
+        match result {
+            Err(CriuError::ReadyTimeout { waited_ms }) => assert!(waited_ms >= 200),
+            other => panic!("expected ReadyTimeout, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_ready_timeout_connected_without_ready() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = FunctionId::new("mute-func").unwrap();
+        let handler = handler(
+            dir.path(),
+            "mute.py",
+            "#!/usr/bin/env python3\n\
+             import os, socket, time\n\
+             s = socket.socket(socket.AF_UNIX)\n\
+             s.connect(os.environ['AETHER_SOCKET'])\n\
+             time.sleep(30)\n",
+        );
+
+        let start = Instant::now();
+        let result = FunctionProcess::spawn(
+            &id,
+            &handler,
+            dir.path(),
+            Duration::from_millis(500),
+            HandshakeMode::Socket,
+        );
+        match result {
+            Err(CriuError::ReadyNotSent { waited_ms }) => assert!(waited_ms >= 500),
+            other => panic!("expected ReadyNotSent, got {:?}", other.err()),
+        }
+        // The read timeout is capped by the READY deadline, not the 5s default
+        assert!(start.elapsed() < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_stdout_handshake() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = FunctionId::new("stdout-func").unwrap();
+        let handler = handler(dir.path(), "ready.sh", "#!/bin/sh\necho READY; sleep 30\n");
+
+        let mut process = FunctionProcess::spawn(
+            &id,
+            &handler,
+            dir.path(),
+            Duration::from_secs(5),
+            HandshakeMode::Stdout,
+        )
+        .unwrap();
+        assert!(process.is_running());
+        // No control socket in stdout mode
+        assert!(!process.socket_path().exists());
+        process.kill().unwrap();
+    }
+
+    #[test]
+    fn test_stdout_handshake_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = FunctionId::new("chatty-func").unwrap();
+        // Only a line exactly equal to READY counts
+        let handler = handler(
+            dir.path(),
+            "chatty.sh",
+            "#!/bin/sh\necho 'NOT READY'; echo READY.; sleep 30\n",
+        );
+
+        let result = FunctionProcess::spawn(
+            &id,
+            &handler,
+            dir.path(),
+            Duration::from_millis(300),
+            HandshakeMode::Stdout,
+        );
+        match result {
+            Err(CriuError::ReadyTimeout { waited_ms }) => assert!(waited_ms >= 300),
+            other => panic!("expected ReadyTimeout, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_control_frame_encoding() {
+        let frame = encode_control_frame(PayloadType::HealthPing, &[]);
+        assert_eq!(frame, [0, 0, 0, 0, 3, 0, 0, 0]);
+
+        let frame = encode_control_frame(PayloadType::InvokeRequest, b"hi");
+        assert_eq!(frame, [2, 0, 0, 0, 1, 0, 0, 0, b'h', b'i']);
+    }
+
+    #[test]
+    fn test_health_check_stream() {
+        let (mut ours, mut theirs) = UnixStream::pair().unwrap();
+
+        // Answer with an unrelated frame, then the pong
+        let responder = std::thread::spawn(move || {
+            let mut ping = [0u8; CONTROL_FRAME_HEADER_SIZE];
+            theirs.read_exact(&mut ping).unwrap();
+            assert_eq!(ping, encode_control_frame(PayloadType::HealthPing, &[])[..]);
+            theirs
+                .write_all(&encode_control_frame(PayloadType::InvokeResponse, b"late"))
+                .unwrap();
+            theirs
+                .write_all(&encode_control_frame(PayloadType::HealthPong, &[]))
+                .unwrap();
+            theirs
+        });
+
+        health_check_stream(&mut ours, Duration::from_secs(5)).unwrap();
+        let theirs = responder.join().unwrap();
+
+        // No reply: times out
+        let start = Instant::now();
+        let result = health_check_stream(&mut ours, Duration::from_millis(100));
+        assert!(matches!(result, Err(CriuError::HealthCheckFailed { .. })));
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // Closed: fails without waiting for the timeout
+        drop(theirs);
+        let start = Instant::now();
+        let result = health_check_stream(&mut ours, Duration::from_secs(5));
+        assert!(matches!(result, Err(CriuError::HealthCheckFailed { .. })));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_exit_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = FunctionId::new("crashing-func").unwrap();
+        let handler = handler(
+            dir.path(),
+            "crash.sh",
+            "#!/bin/sh\necho READY; read line; kill -SEGV $$\n",
+        );
+
+        let mut process = FunctionProcess::spawn(
+            &id,
+            &handler,
+            dir.path(),
+            Duration::from_secs(5),
+            HandshakeMode::Stdout,
+        )
+        .unwrap();
+        assert_eq!(process.exit_reason(), Some(ExitReason::Running));
+
+        // Closing stdin lets the handler reach its crash
+        drop(process.child.stdin.take());
+        let start = Instant::now();
+        while process.is_running() && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(
+            process.exit_reason(),
+            Some(ExitReason::Signaled { signal, .. }) if signal == libc::SIGSEGV
+        ));
+    }
 }
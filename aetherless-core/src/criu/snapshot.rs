@@ -5,13 +5,27 @@
 //!
 //! Manages process checkpointing and restoration using CRIU.
 //! Enforces strict 15ms latency constraint on restore operations.
+//!
+//! In [`RestoreMode::Lazy`] a `criu lazy-pages` daemon is started on the
+//! dump before `criu restore --lazy-pages`. The restored process resumes
+//! as soon as its tasks are rebuilt and faults its memory in afterwards
+//! through userfaultfd, so the latency limit covers time-to-resumable
+//! rather than time-to-fully-paged. The daemon exits on its own once every
+//! page has been served.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::process::Command;
-use std::time::Instant;
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
 
+use crate::config::RestoreMode;
 use crate::error::CriuError;
+use crate::shm::xxhash::{xxh64, Xxh64};
 use crate::types::FunctionId;
 
 /// Default restore timeout in milliseconds.
@@ -21,8 +35,29 @@ pub const DEFAULT_RESTORE_TIMEOUT_MS: u64 = 15;
 /// CRIU dump directory prefix.
 const DUMP_DIR_PREFIX: &str = "criu_dump";
 
+/// Metadata file written into each dump directory.
+const METADATA_FILE: &str = "metadata.json";
+
+/// Image CRIU writes last; its presence marks a complete dump.
+const INVENTORY_IMAGE: &str = "inventory.img";
+
+/// Socket `criu lazy-pages` creates in the dump directory once it is ready.
+const LAZY_PAGES_SOCKET: &str = "lazy-pages.socket";
+
+/// Log file `criu lazy-pages` writes in the dump directory.
+const LAZY_PAGES_LOG: &str = "lazy-pages.log";
+
+/// How often to check whether the lazy-pages daemon is ready.
+const LAZY_PAGES_POLL: Duration = Duration::from_millis(1);
+
+/// Mount table used to classify the snapshot filesystem.
+const PROC_MOUNTS: &str = "/proc/mounts";
+
+/// Filesystems that keep snapshots in memory.
+const MEMORY_FILESYSTEMS: &[&str] = &["tmpfs", "ramfs"];
+
 /// Snapshot metadata.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnapshotMetadata {
     /// Function ID this snapshot belongs to.
     pub function_id: FunctionId,
@@ -32,6 +67,49 @@ pub struct SnapshotMetadata {
     pub original_pid: u32,
     /// Timestamp when snapshot was created.
     pub created_at: std::time::SystemTime,
+    /// Total size of the dump directory in bytes.
+    #[serde(default)]
+    pub size_bytes: u64,
+    /// Timestamp of the most recent restore, if any.
+    #[serde(default)]
+    pub last_restored_at: Option<std::time::SystemTime>,
+    /// XXH64 over the dump's image files, taken right after the dump.
+    #[serde(default)]
+    pub content_hash: Option<u64>,
+}
+
+impl SnapshotMetadata {
+    /// Recency used for eviction: last restore, or creation if never restored.
+    fn last_used(&self) -> std::time::SystemTime {
+        self.last_restored_at.unwrap_or(self.created_at)
+    }
+}
+
+/// Default CRIU log verbosity (errors and warnings).
+pub const DEFAULT_CRIU_VERBOSITY: u8 = 2;
+
+/// Extra command-line options passed to CRIU.
+///
+/// Extra arguments are appended verbatim after the built-in ones; CRIU
+/// itself is the only validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriuOptions {
+    /// Arguments appended to every `criu dump` (e.g. `--ext-unix-sk`).
+    pub extra_dump_args: Vec<String>,
+    /// Arguments appended to every `criu restore`.
+    pub extra_restore_args: Vec<String>,
+    /// Log verbosity passed as `-v<N>` (0-4).
+    pub verbosity: u8,
+}
+
+impl Default for CriuOptions {
+    fn default() -> Self {
+        Self {
+            extra_dump_args: Vec::new(),
+            extra_restore_args: Vec::new(),
+            verbosity: DEFAULT_CRIU_VERBOSITY,
+        }
+    }
 }
 
 /// Manager for CRIU snapshots.
@@ -44,8 +122,25 @@ pub struct SnapshotManager {
     restore_timeout_ms: u64,
     /// Path to CRIU binary.
     criu_path: PathBuf,
+    /// Extra CRIU command-line options.
+    options: CriuOptions,
     /// Cached snapshot metadata.
     snapshots: HashMap<FunctionId, SnapshotMetadata>,
+    /// Budget for all dump directories combined (None = unlimited).
+    max_snapshot_bytes: Option<u64>,
+    /// Functions whose snapshots were evicted since the last
+    /// [`take_evicted`](Self::take_evicted).
+    evicted: Vec<FunctionId>,
+    /// Re-hash dump images before each restore.
+    verify_integrity: bool,
+    /// Most restores [`restore_many`](Self::restore_many) runs at once.
+    restore_parallelism: usize,
+    /// Restore mode for functions without their own.
+    restore_mode: RestoreMode,
+    /// Per-function restore modes.
+    restore_modes: HashMap<FunctionId, RestoreMode>,
+    /// Whether CRIU can restore lazily here; probed on first use.
+    lazy_pages_supported: Option<bool>,
 }
 
 impl SnapshotManager {
@@ -54,12 +149,14 @@ impl SnapshotManager {
     /// # Arguments
     /// * `snapshot_dir` - Base directory for snapshots (use /dev/shm for memory-backed)
     /// * `restore_timeout_ms` - Maximum allowed restore time
+    /// * `options` - Extra CRIU arguments and log verbosity
     ///
     /// # Errors
     /// Returns CriuError if CRIU binary is not found.
     pub fn new(
         snapshot_dir: impl Into<PathBuf>,
         restore_timeout_ms: u64,
+        options: CriuOptions,
     ) -> Result<Self, CriuError> {
         let snapshot_dir = snapshot_dir.into();
 
@@ -71,10 +168,17 @@ impl SnapshotManager {
             reason: format!("Failed to create snapshot dir: {}", e),
         })?;
 
+        // Callers that need tmpfs check first with `check_snapshot_dir`; here
+        // a disk-backed directory still works, just slowly
+        if let Err(e) = check_snapshot_dir(&snapshot_dir) {
+            tracing::warn!(error = %e, "Snapshot directory is not memory-backed");
+        }
+
         tracing::info!(
             criu_path = %criu_path.display(),
             snapshot_dir = %snapshot_dir.display(),
             restore_timeout_ms = restore_timeout_ms,
+            verbosity = options.verbosity,
             "SnapshotManager initialized"
         );
 
@@ -82,10 +186,113 @@ impl SnapshotManager {
             snapshot_dir,
             restore_timeout_ms,
             criu_path,
+            options,
             snapshots: HashMap::new(),
+            max_snapshot_bytes: None,
+            evicted: Vec::new(),
+            verify_integrity: false,
+            restore_parallelism: default_restore_parallelism(),
+            restore_mode: RestoreMode::default(),
+            restore_modes: HashMap::new(),
+            lazy_pages_supported: None,
+        })
+    }
+
+    /// Verify each snapshot's content hash before restoring it.
+    ///
+    /// Catches truncated or modified dumps with a clear SnapshotCorrupt error
+    /// instead of a CRIU failure mid-restore. Off by default: hashing the
+    /// images costs far more than the 15ms restore budget on large dumps.
+    pub fn with_integrity_check(mut self, verify_integrity: bool) -> Self {
+        self.verify_integrity = verify_integrity;
+        self
+    }
+
+    /// Cap the number of concurrent restores in
+    /// [`restore_many`](Self::restore_many). Defaults to the number of CPUs
+    /// available to this process; values below 1 mean 1.
+    pub fn with_restore_parallelism(mut self, parallelism: usize) -> Self {
+        self.restore_parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Restore mode for functions without one set through
+    /// [`set_restore_mode`](Self::set_restore_mode). Defaults to eager.
+    pub fn with_restore_mode(mut self, mode: RestoreMode) -> Self {
+        self.restore_mode = mode;
+        self
+    }
+
+    /// Choose how one function is restored.
+    pub fn set_restore_mode(&mut self, function_id: &FunctionId, mode: RestoreMode) {
+        self.restore_modes.insert(function_id.clone(), mode);
+    }
+
+    /// The mode a restore of `function_id` will use.
+    ///
+    /// A lazy function falls back to eager, with a warning, when
+    /// `criu check --feature uffd-noncoop` fails: the kernel lacks
+    /// userfaultfd or CRIU was built without it; see
+    /// [`probe_lazy_pages`](Self::probe_lazy_pages).
+    fn effective_restore_mode(&mut self, function_id: &FunctionId) -> RestoreMode {
+        let mode = self
+            .restore_modes
+            .get(function_id)
+            .copied()
+            .unwrap_or(self.restore_mode);
+        if mode == RestoreMode::Eager {
+            return mode;
+        }
+
+        if self.probe_lazy_pages() {
+            mode
+        } else {
+            tracing::warn!(
+                function_id = %function_id,
+                "Lazy restore needs userfaultfd support in the kernel and CRIU; restoring eagerly"
+            );
+            RestoreMode::Eager
+        }
+    }
+
+    /// Whether CRIU can restore lazily here.
+    ///
+    /// Runs `criu check` on the first call and caches the answer. The check
+    /// blocks, so call this at startup rather than on the first lazy restore.
+    pub fn probe_lazy_pages(&mut self) -> bool {
+        let criu_path = &self.criu_path;
+        *self.lazy_pages_supported.get_or_insert_with(|| {
+            Command::new(criu_path)
+                .args(["check", "--feature", "uffd-noncoop"])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
         })
     }
 
+    /// Limit the combined size of all snapshots.
+    ///
+    /// When a dump pushes the total over budget, the least-recently-restored
+    /// snapshots of other functions are deleted until it fits again. The size
+    /// of a dump is only known once it is written, so usage can exceed the
+    /// budget by up to one dump while it is in progress.
+    pub fn with_max_snapshot_bytes(mut self, max_snapshot_bytes: u64) -> Self {
+        self.max_snapshot_bytes = Some(max_snapshot_bytes);
+        self
+    }
+
+    /// Configured snapshot budget in bytes, if any.
+    pub fn max_snapshot_bytes(&self) -> Option<u64> {
+        self.max_snapshot_bytes
+    }
+
+    /// Functions whose snapshots were evicted to stay within the budget since
+    /// the last call, so the caller can move them out of WarmSnapshot.
+    pub fn take_evicted(&mut self) -> Vec<FunctionId> {
+        std::mem::take(&mut self.evicted)
+    }
+
     /// Find the CRIU binary.
     fn find_criu() -> Result<PathBuf, CriuError> {
         let candidates = [
@@ -136,6 +343,57 @@ impl SnapshotManager {
         function_id: &FunctionId,
         pid: u32,
     ) -> Result<SnapshotMetadata, CriuError> {
+        let dump_path = self.prepare_dump_dir(function_id, pid)?;
+
+        let start = Instant::now();
+
+        // Execute CRIU dump
+        let output = Command::new(&self.criu_path)
+            .args(self.options.dump_args(pid, &dump_path))
+            .output()
+            .map_err(|e| CriuError::DumpFailed {
+                reason: format!("Failed to execute CRIU: {}", e),
+            })?;
+
+        check_dump(function_id, pid, start, &output)?;
+        let content_hash = hash_dump(&dump_path).map_err(hash_failed)?;
+        self.record_dump(function_id, pid, dump_path, content_hash)
+    }
+
+    /// Dump a process without blocking the async runtime.
+    ///
+    /// Behaves like [`dump`](Self::dump) but runs CRIU via `tokio::process`
+    /// and hashes the images on the blocking thread pool.
+    pub async fn dump_async(
+        &mut self,
+        function_id: &FunctionId,
+        pid: u32,
+    ) -> Result<SnapshotMetadata, CriuError> {
+        let dump_path = self.prepare_dump_dir(function_id, pid)?;
+
+        let start = Instant::now();
+
+        let output = tokio::process::Command::new(&self.criu_path)
+            .args(self.options.dump_args(pid, &dump_path))
+            .output()
+            .await
+            .map_err(|e| CriuError::DumpFailed {
+                reason: format!("Failed to execute CRIU: {}", e),
+            })?;
+
+        check_dump(function_id, pid, start, &output)?;
+        let images = dump_path.clone();
+        let content_hash = tokio::task::spawn_blocking(move || hash_dump(&images))
+            .await
+            .map_err(|e| CriuError::DumpFailed {
+                reason: format!("Hashing dump images panicked: {}", e),
+            })?
+            .map_err(hash_failed)?;
+        self.record_dump(function_id, pid, dump_path, content_hash)
+    }
+
+    /// Create a clean dump directory for a function.
+    fn prepare_dump_dir(&self, function_id: &FunctionId, pid: u32) -> Result<PathBuf, CriuError> {
         let dump_path = self.snapshot_path(function_id);
 
         // Remove old dump if exists
@@ -157,48 +415,155 @@ impl SnapshotManager {
             "Starting CRIU dump"
         );
 
-        let start = Instant::now();
+        Ok(dump_path)
+    }
 
-        // Execute CRIU dump
-        let output = Command::new(&self.criu_path)
-            .arg("dump")
-            .arg("-t")
-            .arg(pid.to_string())
-            .arg("-D")
-            .arg(&dump_path)
-            .arg("-j") // Leave shell job
-            .arg("--shell-job")
-            .arg("-v4") // Verbose for debugging
-            .arg("--tcp-established") // Handle TCP connections
-            .output()
-            .map_err(|e| CriuError::DumpFailed {
-                reason: format!("Failed to execute CRIU: {}", e),
+    /// Record a completed dump whose images hash to `content_hash`.
+    fn record_dump(
+        &mut self,
+        function_id: &FunctionId,
+        pid: u32,
+        dump_path: PathBuf,
+        content_hash: u64,
+    ) -> Result<SnapshotMetadata, CriuError> {
+        let metadata = SnapshotMetadata {
+            function_id: function_id.clone(),
+            size_bytes: dir_size(&dump_path),
+            path: dump_path,
+            original_pid: pid,
+            created_at: std::time::SystemTime::now(),
+            last_restored_at: None,
+            content_hash: Some(content_hash),
+        };
+
+        write_metadata(&metadata).map_err(|reason| CriuError::DumpFailed { reason })?;
+
+        self.snapshots.insert(function_id.clone(), metadata.clone());
+        self.enforce_budget(function_id)?;
+
+        Ok(metadata)
+    }
+
+    /// Evict least-recently-restored snapshots until the total fits the
+    /// budget. The snapshot of `keep` is never evicted.
+    fn enforce_budget(&mut self, keep: &FunctionId) -> Result<(), CriuError> {
+        let Some(budget) = self.max_snapshot_bytes else {
+            return Ok(());
+        };
+
+        while self.total_snapshot_bytes() > budget {
+            let victim = self
+                .snapshots
+                .values()
+                .filter(|m| &m.function_id != keep)
+                .min_by_key(|m| m.last_used())
+                .map(|m| m.function_id.clone());
+
+            let Some(victim) = victim else {
+                tracing::warn!(
+                    function_id = %keep,
+                    total_bytes = self.total_snapshot_bytes(),
+                    budget_bytes = budget,
+                    "Snapshot alone exceeds budget"
+                );
+                break;
+            };
+
+            tracing::info!(
+                function_id = %victim,
+                budget_bytes = budget,
+                "Evicting snapshot to stay within budget"
+            );
+            self.delete_snapshot(&victim)?;
+            self.evicted.push(victim);
+        }
+
+        Ok(())
+    }
+
+    /// Reload snapshot metadata from dumps left in `snapshot_dir`.
+    ///
+    /// Lets a restarted orchestrator reuse warm snapshots. Directories with
+    /// missing or corrupt metadata, or an incomplete dump, are skipped with a
+    /// warning. Returns the number of snapshots loaded.
+    ///
+    /// # Errors
+    /// Returns CriuError only if `snapshot_dir` itself cannot be read.
+    pub fn rehydrate(&mut self) -> Result<usize, CriuError> {
+        let entries =
+            std::fs::read_dir(&self.snapshot_dir).map_err(|e| CriuError::RestoreFailed {
+                reason: format!(
+                    "Failed to read snapshot dir {}: {}",
+                    self.snapshot_dir.display(),
+                    e
+                ),
             })?;
 
-        let elapsed = start.elapsed();
+        let prefix = format!("{}_", DUMP_DIR_PREFIX);
+        let mut loaded = 0;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(CriuError::DumpFailed {
-                reason: format!("CRIU dump failed: {}", stderr),
-            });
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dump = path.is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name.starts_with(&prefix));
+            if !is_dump {
+                continue;
+            }
+
+            match self.load_dump_metadata(&path) {
+                Ok(metadata) => {
+                    tracing::debug!(
+                        function_id = %metadata.function_id,
+                        path = %path.display(),
+                        "Rehydrated snapshot"
+                    );
+                    self.snapshots
+                        .insert(metadata.function_id.clone(), metadata);
+                    loaded += 1;
+                }
+                Err(reason) => {
+                    tracing::warn!(
+                        path = %path.display(),
+                        reason = %reason,
+                        "Skipping unusable snapshot"
+                    );
+                }
+            }
         }
 
         tracing::info!(
-            function_id = %function_id,
-            pid = pid,
-            elapsed_ms = elapsed.as_millis(),
-            "CRIU dump completed"
+            snapshot_dir = %self.snapshot_dir.display(),
+            loaded = loaded,
+            "Snapshot metadata rehydrated"
         );
 
-        let metadata = SnapshotMetadata {
-            function_id: function_id.clone(),
-            path: dump_path,
-            original_pid: pid,
-            created_at: std::time::SystemTime::now(),
-        };
+        Ok(loaded)
+    }
 
-        self.snapshots.insert(function_id.clone(), metadata.clone());
+    /// Read and validate the metadata of one dump directory.
+    fn load_dump_metadata(&self, path: &Path) -> Result<SnapshotMetadata, String> {
+        let json = std::fs::read(path.join(METADATA_FILE))
+            .map_err(|e| format!("Failed to read {}: {}", METADATA_FILE, e))?;
+        let mut metadata: SnapshotMetadata = serde_json::from_slice(&json)
+            .map_err(|e| format!("Corrupt {}: {}", METADATA_FILE, e))?;
+
+        if self.snapshot_path(&metadata.function_id) != path {
+            return Err(format!(
+                "Metadata belongs to function '{}'",
+                metadata.function_id
+            ));
+        }
+
+        if !path.join(INVENTORY_IMAGE).exists() {
+            return Err(format!("Incomplete dump: {} missing", INVENTORY_IMAGE));
+        }
+
+        // The snapshot dir may have been moved since the dump was taken
+        metadata.path = path.to_path_buf();
+        metadata.size_bytes = dir_size(path);
 
         Ok(metadata)
     }
@@ -207,13 +572,106 @@ impl SnapshotManager {
     ///
     /// Returns the new process ID.
     ///
+    /// Transient CRIU failures (see `classify_restore_failure`) are retried
+    /// up to `retries` times.
+    ///
     /// # Constraint
-    /// If restore takes longer than restore_timeout_ms, kills the process
-    /// and returns LatencyViolationError.
-    pub fn restore(&self, function_id: &FunctionId) -> Result<u32, CriuError> {
+    /// If restore takes longer than restore_timeout_ms, including all
+    /// retries, kills the process and returns LatencyViolationError.
+    pub fn restore(&mut self, function_id: &FunctionId, retries: u32) -> Result<u32, CriuError> {
+        self.prepare_restore(function_id)?
+            .with_retries(retries)
+            .run_blocking()
+    }
+
+    /// Restore a process from snapshot without blocking the async runtime.
+    ///
+    /// Same latency constraint as [`restore`](Self::restore). Callers that
+    /// keep the manager behind a lock should use
+    /// [`prepare_restore`](Self::prepare_restore) instead, so the lock is not
+    /// held across the CRIU call.
+    pub async fn restore_async(
+        &mut self,
+        function_id: &FunctionId,
+        retries: u32,
+    ) -> Result<u32, CriuError> {
+        self.prepare_restore(function_id)?
+            .with_retries(retries)
+            .run()
+            .await
+    }
+
+    /// Restore several functions concurrently, at most
+    /// [`with_restore_parallelism`](Self::with_restore_parallelism) at a time.
+    ///
+    /// Jobs are prepared up front, so the manager is only borrowed while
+    /// that happens, not across the CRIU calls. Each restore enforces
+    /// `restore_timeout_ms` on its own. Results come back in the order of
+    /// `ids`; a function listed twice fails the second time rather than
+    /// running two restores of one dump.
+    pub async fn restore_many(
+        &mut self,
+        ids: &[FunctionId],
+    ) -> Vec<(FunctionId, Result<u32, CriuError>)> {
+        let mut results: Vec<Option<Result<u32, CriuError>>> = Vec::with_capacity(ids.len());
+        let mut jobs = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for (index, function_id) in ids.iter().enumerate() {
+            if !seen.insert(function_id) {
+                results.push(Some(Err(CriuError::RestoreFailed {
+                    reason: format!("{} is listed more than once", function_id),
+                })));
+                continue;
+            }
+            match self.prepare_restore(function_id) {
+                Ok(job) => {
+                    jobs.push((index, job));
+                    results.push(None);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+
+        let permits = Arc::new(tokio::sync::Semaphore::new(self.restore_parallelism));
+        let mut running = tokio::task::JoinSet::new();
+        for (index, job) in jobs {
+            let permits = Arc::clone(&permits);
+            running.spawn(async move {
+                // The semaphore is never closed
+                let _permit = permits.acquire_owned().await;
+                (index, job.run().await)
+            });
+        }
+        while let Some(joined) = running.join_next().await {
+            match joined {
+                Ok((index, result)) => results[index] = Some(result),
+                // A panicked restore leaves its slot empty, reported below
+                Err(e) => tracing::error!(error = %e, "Restore task failed"),
+            }
+        }
+
+        ids.iter()
+            .cloned()
+            .zip(results)
+            .map(|(function_id, result)| {
+                let result = result.unwrap_or_else(|| {
+                    Err(CriuError::RestoreFailed {
+                        reason: "Restore task panicked".to_string(),
+                    })
+                });
+                (function_id, result)
+            })
+            .collect()
+    }
+
+    /// Look up a snapshot and build a restore job that no longer borrows the
+    /// manager.
+    ///
+    /// Marks the snapshot as recently restored for eviction purposes.
+    pub fn prepare_restore(&mut self, function_id: &FunctionId) -> Result<RestoreJob, CriuError> {
         let metadata =
             self.snapshots
-                .get(function_id)
+                .get_mut(function_id)
                 .ok_or_else(|| CriuError::SnapshotNotFound {
                     function_id: function_id.clone(),
                 })?;
@@ -224,52 +682,551 @@ impl SnapshotManager {
             });
         }
 
-        tracing::debug!(
-            function_id = %function_id,
-            path = %metadata.path.display(),
-            "Starting CRIU restore"
-        );
+        metadata.last_restored_at = Some(std::time::SystemTime::now());
+        // Persist the timestamp so eviction order survives a restart; a
+        // failure only makes this snapshot look older than it is
+        if let Err(e) = write_metadata(metadata) {
+            tracing::warn!(function_id = %function_id, error = %e, "Failed to record restore time");
+        }
+        let dump_path = metadata.path.clone();
+        let expected_hash = metadata.content_hash.filter(|_| self.verify_integrity);
 
-        let start = Instant::now();
+        Ok(RestoreJob {
+            mode: self.effective_restore_mode(function_id),
+            function_id: function_id.clone(),
+            criu_path: self.criu_path.clone(),
+            options: self.options.clone(),
+            dump_path,
+            restore_timeout_ms: self.restore_timeout_ms,
+            expected_hash,
+            retries: 0,
+        })
+    }
 
-        // Execute CRIU restore
-        let output = Command::new(&self.criu_path)
-            .arg("restore")
-            .arg("-D")
-            .arg(&metadata.path)
-            .arg("-j")
-            .arg("--shell-job")
-            .arg("-d") // Detach after restore
-            .arg("--pidfile")
-            .arg(metadata.path.join("restored.pid"))
-            .output()
+    /// Check if a snapshot exists for a function.
+    pub fn has_snapshot(&self, function_id: &FunctionId) -> bool {
+        if let Some(metadata) = self.snapshots.get(function_id) {
+            metadata.path.exists()
+        } else {
+            false
+        }
+    }
+
+    /// Delete a snapshot.
+    pub fn delete_snapshot(&mut self, function_id: &FunctionId) -> Result<(), CriuError> {
+        if let Some(metadata) = self.snapshots.remove(function_id) {
+            if metadata.path.exists() {
+                std::fs::remove_dir_all(&metadata.path).map_err(|e| CriuError::DumpFailed {
+                    reason: format!("Failed to delete snapshot: {}", e),
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get snapshot metadata.
+    pub fn get_metadata(&self, function_id: &FunctionId) -> Option<&SnapshotMetadata> {
+        self.snapshots.get(function_id)
+    }
+
+    /// List all snapshots.
+    pub fn list_snapshots(&self) -> Vec<&SnapshotMetadata> {
+        self.snapshots.values().collect()
+    }
+
+    /// Combined size of all snapshots in bytes.
+    pub fn total_snapshot_bytes(&self) -> u64 {
+        self.snapshots.values().map(|m| m.size_bytes).sum()
+    }
+
+    /// Size of each function's snapshot in bytes.
+    pub fn snapshot_sizes(&self) -> Vec<(FunctionId, u64)> {
+        self.snapshots
+            .values()
+            .map(|m| (m.function_id.clone(), m.size_bytes))
+            .collect()
+    }
+}
+
+/// Write a snapshot's metadata into its dump directory.
+fn write_metadata(metadata: &SnapshotMetadata) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(metadata)
+        .map_err(|e| format!("Failed to serialize snapshot metadata: {}", e))?;
+    std::fs::write(metadata.path.join(METADATA_FILE), json)
+        .map_err(|e| format!("Failed to write snapshot metadata: {}", e))
+}
+
+/// Check the CRIU dump result.
+fn check_dump(
+    function_id: &FunctionId,
+    pid: u32,
+    start: Instant,
+    output: &Output,
+) -> Result<(), CriuError> {
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(CriuError::DumpFailed {
+            reason: format!("CRIU dump failed: {}", stderr),
+        });
+    }
+
+    tracing::info!(
+        function_id = %function_id,
+        pid = pid,
+        elapsed_ms = start.elapsed().as_millis(),
+        "CRIU dump completed"
+    );
+    Ok(())
+}
+
+fn hash_failed(e: std::io::Error) -> CriuError {
+    CriuError::DumpFailed {
+        reason: format!("Failed to hash dump images: {}", e),
+    }
+}
+
+/// Bytes of an image read at a time while hashing.
+const HASH_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Hash the CRIU image files in a dump directory.
+///
+/// Files are hashed in name order, each chained into the next via the seed,
+/// so renamed, missing or truncated images all change the result.
+fn hash_dump(path: &Path) -> std::io::Result<u64> {
+    let mut images: Vec<PathBuf> = std::fs::read_dir(path)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "img"))
+        .collect();
+    images.sort();
+
+    // Images can be gigabytes, so stream each one through the hash
+    let mut chunk = vec![0u8; HASH_CHUNK_SIZE];
+    let mut hash = 0;
+    for image in images {
+        let name = image.file_name().unwrap_or_default().as_encoded_bytes();
+        hash = xxh64(name, hash);
+
+        let mut file = std::fs::File::open(&image)?;
+        let mut state = Xxh64::new(hash);
+        loop {
+            match file.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => state.update(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        hash = state.finish();
+    }
+
+    Ok(hash)
+}
+
+/// Total size of the regular files under `path`.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// A pending CRIU restore.
+///
+/// Owns everything needed to run the restore, so it can be awaited after the
+/// originating [`SnapshotManager`] (and any lock around it) is released.
+#[derive(Debug, Clone)]
+pub struct RestoreJob {
+    function_id: FunctionId,
+    criu_path: PathBuf,
+    options: CriuOptions,
+    dump_path: PathBuf,
+    restore_timeout_ms: u64,
+    /// Hash to verify before restoring, when integrity checks are enabled.
+    expected_hash: Option<u64>,
+    /// Extra attempts allowed on transient failures.
+    retries: u32,
+    /// Eager or lazy, after any fallback.
+    mode: RestoreMode,
+}
+
+/// A running `criu lazy-pages` daemon serving one restore.
+struct LazyPagesDaemon {
+    child: Child,
+    socket: PathBuf,
+    log: PathBuf,
+}
+
+impl LazyPagesDaemon {
+    /// Start the daemon on a dump, clearing any socket left by an earlier
+    /// run so readiness is not misread.
+    fn spawn(job: &RestoreJob) -> Result<Self, CriuError> {
+        let socket = job.dump_path.join(LAZY_PAGES_SOCKET);
+        let _ = std::fs::remove_file(&socket);
+
+        let child = Command::new(&job.criu_path)
+            .args(job.options.lazy_pages_args(&job.dump_path))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
             .map_err(|e| CriuError::RestoreFailed {
-                reason: format!("Failed to execute CRIU: {}", e),
+                reason: format!("Failed to start CRIU lazy-pages: {}", e),
             })?;
 
-        let elapsed_ms = start.elapsed().as_millis() as u64;
+        Ok(Self {
+            child,
+            socket,
+            log: job.dump_path.join(LAZY_PAGES_LOG),
+        })
+    }
+
+    /// Whether the daemon is accepting restores yet.
+    fn ready(&mut self) -> Result<bool, CriuError> {
+        if self.socket.exists() {
+            return Ok(true);
+        }
+        match self.child.try_wait() {
+            Ok(None) => Ok(false),
+            Ok(Some(status)) => Err(CriuError::RestoreFailed {
+                reason: format!(
+                    "CRIU lazy-pages exited with {} (see {})",
+                    status,
+                    self.log.display()
+                ),
+            }),
+            Err(e) => Err(CriuError::RestoreFailed {
+                reason: format!("Failed to check CRIU lazy-pages: {}", e),
+            }),
+        }
+    }
+
+    /// Leave the daemon serving pages to the restored process and reap it
+    /// when it is done.
+    fn detach(mut self, function_id: FunctionId) {
+        std::thread::spawn(move || match self.child.wait() {
+            Ok(status) if status.success() => {
+                tracing::debug!(function_id = %function_id, "All lazy pages served")
+            }
+            result => tracing::warn!(
+                function_id = %function_id,
+                status = ?result,
+                log = %self.log.display(),
+                "CRIU lazy-pages failed; the restored process may not survive"
+            ),
+        });
+    }
+
+    fn kill(mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Result of a single CRIU restore attempt.
+enum AttemptOutcome {
+    /// Stop and return this result.
+    Done(Result<u32, CriuError>),
+    /// A transient failure with retries left.
+    Retry,
+    /// The latency budget was exceeded.
+    Violation,
+}
+
+/// Whether a CRIU failure is worth retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailureKind {
+    /// Likely to succeed on another attempt (e.g. a socket still in use).
+    Transient,
+    /// Will fail again (e.g. missing or unreadable images).
+    Permanent,
+}
+
+/// Classify a CRIU restore failure from its stderr.
+///
+/// Only a small set of known races is treated as transient; anything else,
+/// including unrecognised errors, is permanent.
+fn classify_restore_failure(stderr: &str) -> FailureKind {
+    const TRANSIENT: &[&str] = &[
+        "Address already in use",
+        "EADDRINUSE",
+        "Resource temporarily unavailable",
+        "EAGAIN",
+        "Device or resource busy",
+        "EBUSY",
+    ];
+
+    if TRANSIENT.iter().any(|pattern| stderr.contains(pattern)) {
+        FailureKind::Transient
+    } else {
+        FailureKind::Permanent
+    }
+}
+
+impl RestoreJob {
+    /// Function being restored.
+    pub fn function_id(&self) -> &FunctionId {
+        &self.function_id
+    }
+
+    /// Mode the restore runs in.
+    pub fn mode(&self) -> RestoreMode {
+        self.mode
+    }
+
+    /// Allow up to `retries` extra attempts on transient CRIU failures.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Run the restore on the async runtime.
+    ///
+    /// Latency is measured around the awaited CRIU calls, cumulatively
+    /// across retries; on violation the restored process is killed and
+    /// LatencyViolation returned.
+    pub async fn run(self) -> Result<u32, CriuError> {
+        if self.expected_hash.is_some() {
+            let job = self.clone();
+            tokio::task::spawn_blocking(move || job.verify())
+                .await
+                .map_err(|e| CriuError::RestoreFailed {
+                    reason: format!("Integrity check panicked: {}", e),
+                })??;
+        }
+
+        self.log_start();
+        let start = Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            self.clear_pid_file();
+            let daemon = match self.start_lazy_pages()? {
+                Some(mut daemon) => loop {
+                    if daemon.ready()? {
+                        break Some(daemon);
+                    }
+                    if self.over_budget(start) {
+                        daemon.kill();
+                        return Err(self.latency_violation(start.elapsed().as_millis() as u64));
+                    }
+                    tokio::time::sleep(LAZY_PAGES_POLL).await;
+                },
+                None => None,
+            };
+
+            let output = tokio::process::Command::new(&self.criu_path)
+                .args(self.options.restore_args(&self.dump_path, self.mode))
+                .output()
+                .await
+                .map_err(|e| CriuError::RestoreFailed {
+                    reason: format!("Failed to execute CRIU: {}", e),
+                });
+            let output = self.settle_daemon(daemon, output)?;
+
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+
+            match self.check_attempt(output, elapsed_ms, attempt) {
+                AttemptOutcome::Done(result) => return result,
+                AttemptOutcome::Retry => attempt += 1,
+                AttemptOutcome::Violation => {
+                    if let Some(pid) = self.restored_pid() {
+                        let _ = tokio::process::Command::new("kill")
+                            .arg("-9")
+                            .arg(pid.to_string())
+                            .status()
+                            .await;
+                        self.log_violation(elapsed_ms);
+                    }
+
+                    return Err(self.latency_violation(elapsed_ms));
+                }
+            }
+        }
+    }
+
+    /// Run the restore, blocking the current thread.
+    pub fn run_blocking(self) -> Result<u32, CriuError> {
+        self.verify()?;
+        self.log_start();
+        let start = Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            self.clear_pid_file();
+            let daemon = match self.start_lazy_pages()? {
+                Some(mut daemon) => loop {
+                    if daemon.ready()? {
+                        break Some(daemon);
+                    }
+                    if self.over_budget(start) {
+                        daemon.kill();
+                        return Err(self.latency_violation(start.elapsed().as_millis() as u64));
+                    }
+                    std::thread::sleep(LAZY_PAGES_POLL);
+                },
+                None => None,
+            };
 
+            // Execute CRIU restore
+            let output = Command::new(&self.criu_path)
+                .args(self.options.restore_args(&self.dump_path, self.mode))
+                .output()
+                .map_err(|e| CriuError::RestoreFailed {
+                    reason: format!("Failed to execute CRIU: {}", e),
+                });
+            let output = self.settle_daemon(daemon, output)?;
+
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+
+            match self.check_attempt(output, elapsed_ms, attempt) {
+                AttemptOutcome::Done(result) => return result,
+                AttemptOutcome::Retry => attempt += 1,
+                AttemptOutcome::Violation => {
+                    // Try to read PID and kill the process
+                    if let Some(pid) = self.restored_pid() {
+                        let _ = Command::new("kill").arg("-9").arg(pid.to_string()).status();
+                        self.log_violation(elapsed_ms);
+                    }
+
+                    return Err(self.latency_violation(elapsed_ms));
+                }
+            }
+        }
+    }
+
+    /// Start the lazy-pages daemon for this attempt when restoring lazily.
+    ///
+    /// Its startup counts against the latency limit.
+    fn start_lazy_pages(&self) -> Result<Option<LazyPagesDaemon>, CriuError> {
+        if self.mode == RestoreMode::Eager {
+            return Ok(None);
+        }
+        tracing::debug!(function_id = %self.function_id, "Starting CRIU lazy-pages");
+        LazyPagesDaemon::spawn(self).map(Some)
+    }
+
+    fn over_budget(&self, start: Instant) -> bool {
+        start.elapsed().as_millis() as u64 > self.restore_timeout_ms
+    }
+
+    /// Keep the daemon running only behind a successful restore; it has
+    /// nobody to serve otherwise.
+    fn settle_daemon(
+        &self,
+        daemon: Option<LazyPagesDaemon>,
+        output: Result<Output, CriuError>,
+    ) -> Result<Output, CriuError> {
+        if let Some(daemon) = daemon {
+            match &output {
+                Ok(output) if output.status.success() => daemon.detach(self.function_id.clone()),
+                _ => daemon.kill(),
+            }
+        }
+        output
+    }
+
+    /// Decide what to do after a CRIU attempt.
+    ///
+    /// `elapsed_ms` is the time since the first attempt started, so the
+    /// latency limit covers all retries together.
+    fn check_attempt(&self, output: Output, elapsed_ms: u64, attempt: u32) -> AttemptOutcome {
         // Check latency constraint FIRST
         if elapsed_ms > self.restore_timeout_ms {
-            // Try to read PID and kill the process
-            if let Ok(pid_str) = std::fs::read_to_string(metadata.path.join("restored.pid")) {
-                if let Ok(pid) = pid_str.trim().parse::<u32>() {
-                    let _ = Command::new("kill").arg("-9").arg(pid.to_string()).status();
-                    tracing::error!(
-                        function_id = %function_id,
-                        elapsed_ms = elapsed_ms,
-                        limit_ms = self.restore_timeout_ms,
-                        "Latency violation - killed restored process"
-                    );
-                }
+            return AttemptOutcome::Violation;
+        }
+
+        if !output.status.success() && attempt < self.retries {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if classify_restore_failure(&stderr) == FailureKind::Transient {
+                tracing::warn!(
+                    function_id = %self.function_id,
+                    attempt = attempt + 1,
+                    elapsed_ms = elapsed_ms,
+                    stderr = %stderr.trim(),
+                    "Transient CRIU restore failure, retrying"
+                );
+                return AttemptOutcome::Retry;
             }
+        }
 
-            return Err(CriuError::LatencyViolation {
-                actual_ms: elapsed_ms,
-                limit_ms: self.restore_timeout_ms,
-            });
+        AttemptOutcome::Done(self.finish(output, elapsed_ms))
+    }
+
+    /// Remove a pid file left over from an earlier restore, so a failed
+    /// attempt can't be mistaken for a restored process.
+    fn clear_pid_file(&self) {
+        let _ = std::fs::remove_file(self.pid_file());
+    }
+
+    /// Compare the dump against its recorded hash, if verification is on.
+    ///
+    /// Runs before the restore latency is measured.
+    fn verify(&self) -> Result<(), CriuError> {
+        let Some(expected) = self.expected_hash else {
+            return Ok(());
+        };
+
+        match hash_dump(&self.dump_path) {
+            Ok(actual) if actual == expected => Ok(()),
+            result => {
+                tracing::error!(
+                    function_id = %self.function_id,
+                    path = %self.dump_path.display(),
+                    error = ?result.err(),
+                    "Snapshot failed integrity check"
+                );
+                Err(CriuError::SnapshotCorrupt {
+                    function_id: self.function_id.clone(),
+                })
+            }
+        }
+    }
+
+    fn pid_file(&self) -> PathBuf {
+        self.dump_path.join("restored.pid")
+    }
+
+    fn restored_pid(&self) -> Option<u32> {
+        std::fs::read_to_string(self.pid_file())
+            .ok()
+            .and_then(|pid_str| pid_str.trim().parse::<u32>().ok())
+    }
+
+    fn log_start(&self) {
+        tracing::debug!(
+            function_id = %self.function_id,
+            path = %self.dump_path.display(),
+            "Starting CRIU restore"
+        );
+    }
+
+    fn log_violation(&self, elapsed_ms: u64) {
+        tracing::error!(
+            function_id = %self.function_id,
+            elapsed_ms = elapsed_ms,
+            limit_ms = self.restore_timeout_ms,
+            "Latency violation - killed restored process"
+        );
+    }
+
+    fn latency_violation(&self, elapsed_ms: u64) -> CriuError {
+        CriuError::LatencyViolation {
+            actual_ms: elapsed_ms,
+            limit_ms: self.restore_timeout_ms,
         }
+    }
 
+    /// Check the CRIU result and read the new PID.
+    fn finish(&self, output: Output, elapsed_ms: u64) -> Result<u32, CriuError> {
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(CriuError::RestoreFailed {
@@ -278,11 +1235,10 @@ impl SnapshotManager {
         }
 
         // Read the new PID
-        let pid_str = std::fs::read_to_string(metadata.path.join("restored.pid")).map_err(|e| {
-            CriuError::RestoreFailed {
+        let pid_str =
+            std::fs::read_to_string(self.pid_file()).map_err(|e| CriuError::RestoreFailed {
                 reason: format!("Failed to read PID file: {}", e),
-            }
-        })?;
+            })?;
 
         let pid = pid_str
             .trim()
@@ -292,7 +1248,7 @@ impl SnapshotManager {
             })?;
 
         tracing::info!(
-            function_id = %function_id,
+            function_id = %self.function_id,
             new_pid = pid,
             elapsed_ms = elapsed_ms,
             "CRIU restore completed"
@@ -300,43 +1256,197 @@ impl SnapshotManager {
 
         Ok(pid)
     }
+}
 
-    /// Check if a snapshot exists for a function.
-    pub fn has_snapshot(&self, function_id: &FunctionId) -> bool {
-        if let Some(metadata) = self.snapshots.get(function_id) {
-            metadata.path.exists()
-        } else {
-            false
+impl CriuOptions {
+    /// CRIU arguments for dumping `pid` into `dump_path`.
+    fn dump_args(&self, pid: u32, dump_path: &Path) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![
+            "dump".into(),
+            "-t".into(),
+            pid.to_string().into(),
+            "-D".into(),
+            dump_path.into(),
+            "-j".into(), // Leave shell job
+            "--shell-job".into(),
+            self.verbosity_arg(),
+            "--tcp-established".into(), // Handle TCP connections
+        ];
+        args.extend(self.extra_dump_args.iter().map(OsString::from));
+        args
+    }
+
+    /// CRIU arguments for restoring from `dump_path`.
+    fn restore_args(&self, dump_path: &Path, mode: RestoreMode) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![
+            "restore".into(),
+            "-D".into(),
+            dump_path.into(),
+            "-j".into(),
+            "--shell-job".into(),
+            self.verbosity_arg(),
+            "-d".into(), // Detach after restore
+            "--pidfile".into(),
+            dump_path.join("restored.pid").into(),
+        ];
+        if mode == RestoreMode::Lazy {
+            args.push("--lazy-pages".into());
         }
+        args.extend(self.extra_restore_args.iter().map(OsString::from));
+        args
     }
 
-    /// Delete a snapshot.
-    pub fn delete_snapshot(&mut self, function_id: &FunctionId) -> Result<(), CriuError> {
-        if let Some(metadata) = self.snapshots.remove(function_id) {
-            if metadata.path.exists() {
-                std::fs::remove_dir_all(&metadata.path).map_err(|e| CriuError::DumpFailed {
-                    reason: format!("Failed to delete snapshot: {}", e),
-                })?;
-            }
+    /// CRIU arguments for a lazy-pages daemon serving pages from the images
+    /// in `dump_path`. Images are local, so no `--page-server` is needed.
+    fn lazy_pages_args(&self, dump_path: &Path) -> Vec<OsString> {
+        vec![
+            "lazy-pages".into(),
+            "-D".into(),
+            dump_path.into(),
+            self.verbosity_arg(),
+            "-o".into(),
+            LAZY_PAGES_LOG.into(),
+        ]
+    }
+
+    fn verbosity_arg(&self) -> OsString {
+        format!("-v{}", self.verbosity).into()
+    }
+}
+
+/// Default for [`SnapshotManager::with_restore_parallelism`].
+fn default_restore_parallelism() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Check that `snapshot_dir` is on tmpfs or ramfs, going by `/proc/mounts`.
+///
+/// The directory need not exist yet; its nearest existing ancestor decides.
+/// If the mount table cannot be read the check passes, since nothing is
+/// known to be wrong.
+///
+/// # Errors
+/// SnapshotDirNotInMemory naming the filesystem type otherwise.
+pub fn check_snapshot_dir(snapshot_dir: &Path) -> Result<(), CriuError> {
+    let Ok(mounts) = std::fs::read_to_string(PROC_MOUNTS) else {
+        tracing::debug!(path = PROC_MOUNTS, "Mount table unreadable, skipping check");
+        return Ok(());
+    };
+    let resolved = snapshot_dir
+        .ancestors()
+        .find_map(|dir| dir.canonicalize().ok())
+        .unwrap_or_else(|| snapshot_dir.to_path_buf());
+
+    match filesystem_type(&mounts, &resolved) {
+        Some(fs_type) if !MEMORY_FILESYSTEMS.contains(&fs_type) => {
+            Err(CriuError::SnapshotDirNotInMemory {
+                path: snapshot_dir.to_path_buf(),
+                fs_type: fs_type.to_string(),
+            })
         }
-        Ok(())
+        _ => Ok(()),
     }
+}
 
-    /// Get snapshot metadata.
-    pub fn get_metadata(&self, function_id: &FunctionId) -> Option<&SnapshotMetadata> {
-        self.snapshots.get(function_id)
+/// Filesystem type of the mount holding `path`, from `/proc/mounts` text.
+///
+/// The deepest mount point containing `path` wins; among mounts of the same
+/// point the last, which shadows the others, does.
+fn filesystem_type<'a>(mounts: &'a str, path: &Path) -> Option<&'a str> {
+    let mut best: Option<(usize, &str)> = None;
+    for line in mounts.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(_source), Some(mount_point), Some(fs_type)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let mount_point = unescape_mount_point(mount_point);
+        if !path.starts_with(&mount_point) {
+            continue;
+        }
+        let depth = Path::new(&mount_point).components().count();
+        if best.is_none_or(|(best_depth, _)| depth >= best_depth) {
+            best = Some((depth, fs_type));
+        }
     }
+    best.map(|(_, fs_type)| fs_type)
+}
 
-    /// List all snapshots.
-    pub fn list_snapshots(&self) -> Vec<&SnapshotMetadata> {
-        self.snapshots.values().collect()
+/// Undo the octal escapes (`\040` for a space, etc.) the kernel writes in
+/// mount table paths.
+fn unescape_mount_point(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)));
+        match escaped {
+            Some(digits) => {
+                let value = digits
+                    .iter()
+                    .fold(0u32, |acc, d| acc * 8 + u32::from(d - b'0'));
+                out.push(value as u8);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
     }
+    String::from_utf8_lossy(&out).into_owned()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_filesystem_type() {
+        let mounts = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+tmpfs /dev/shm tmpfs rw,nosuid,nodev 0 0
+/dev/sdb1 /dev/shm/disk xfs rw 0 0
+none /mnt/my\\040snaps ramfs rw 0 0
+/dev/sdc1 /data ext4 rw 0 0
+tmpfs /data tmpfs rw 0 0
+";
+        let fs = |path: &str| filesystem_type(mounts, Path::new(path));
+        assert_eq!(fs("/dev/shm/aetherless"), Some("tmpfs"));
+        assert_eq!(fs("/dev/shm"), Some("tmpfs"));
+        assert_eq!(fs("/dev/shm/disk/snaps"), Some("xfs"));
+        // Component-wise, so /dev/shmem is not under /dev/shm
+        assert_eq!(fs("/dev/shmem"), Some("ext4"));
+        assert_eq!(fs("/mnt/my snaps/a"), Some("ramfs"));
+        // The later mount on /data shadows the earlier one
+        assert_eq!(fs("/data/snapshots"), Some("tmpfs"));
+        assert_eq!(fs("/var/lib/aetherless"), Some("ext4"));
+        assert_eq!(filesystem_type("", Path::new("/tmp")), None);
+    }
+
+    #[test]
+    fn test_check_snapshot_dir() {
+        let mounts = std::fs::read_to_string(PROC_MOUNTS).unwrap_or_default();
+        let shm_type = filesystem_type(&mounts, Path::new("/dev/shm"));
+
+        // Missing directories are judged by their nearest existing ancestor
+        let result = check_snapshot_dir(Path::new("/dev/shm/aetherless-check/not/created"));
+        match shm_type {
+            Some("tmpfs") | Some("ramfs") | None => assert!(result.is_ok()),
+            Some(other) => match result {
+                Err(CriuError::SnapshotDirNotInMemory { fs_type, .. }) => {
+                    assert_eq!(fs_type, other)
+                }
+                unexpected => panic!("unexpected result: {:?}", unexpected),
+            },
+        }
+        assert_eq!(unescape_mount_point("/a\\040b\\134c"), "/a b\\c");
+        assert_eq!(unescape_mount_point("/trailing\\04"), "/trailing\\04");
+    }
+
     #[test]
     fn test_snapshot_path() {
         // This test doesn't actually use CRIU, just tests path generation
@@ -344,4 +1454,481 @@ mod tests {
         let expected_suffix = format!("{}_{}", DUMP_DIR_PREFIX, function_id);
         assert!(expected_suffix.contains("test-func"));
     }
+
+    #[test]
+    fn test_criu_options_args() {
+        let path = Path::new("/dev/shm/criu_dump_f");
+        let default_args = CriuOptions::default().dump_args(42, path);
+        assert!(default_args.contains(&OsString::from("-v2")));
+        assert!(!default_args.contains(&OsString::from("-v4")));
+
+        let options = CriuOptions {
+            extra_dump_args: vec!["--ext-unix-sk".into(), "--file-locks".into()],
+            extra_restore_args: vec!["--some-future-flag=1".into()],
+            verbosity: 4,
+        };
+
+        let dump = options.dump_args(42, path);
+        assert_eq!(dump[0], "dump");
+        assert!(dump.contains(&OsString::from("-v4")));
+        assert!(dump.contains(&OsString::from("--tcp-established")));
+        assert_eq!(&dump[dump.len() - 2..], ["--ext-unix-sk", "--file-locks"]);
+
+        let restore = options.restore_args(path, RestoreMode::Eager);
+        assert_eq!(restore[0], "restore");
+        assert!(restore.contains(&OsString::from("-v4")));
+        assert_eq!(restore.last().unwrap(), "--some-future-flag=1");
+        assert!(!restore.contains(&OsString::from("--ext-unix-sk")));
+        assert!(!restore.contains(&OsString::from("--lazy-pages")));
+
+        let lazy = options.restore_args(path, RestoreMode::Lazy);
+        assert!(lazy.contains(&OsString::from("--lazy-pages")));
+        assert_eq!(lazy.last().unwrap(), "--some-future-flag=1");
+
+        let daemon = options.lazy_pages_args(path);
+        assert_eq!(daemon[..3], ["lazy-pages", "-D", "/dev/shm/criu_dump_f"]);
+        assert!(daemon.contains(&OsString::from("-v4")));
+    }
+
+    /// Manager over `dir` that doesn't require a CRIU binary.
+    fn test_manager(dir: &Path) -> SnapshotManager {
+        SnapshotManager {
+            snapshot_dir: dir.to_path_buf(),
+            restore_timeout_ms: DEFAULT_RESTORE_TIMEOUT_MS,
+            criu_path: PathBuf::from("/bin/false"),
+            options: CriuOptions::default(),
+            snapshots: HashMap::new(),
+            max_snapshot_bytes: None,
+            evicted: Vec::new(),
+            verify_integrity: false,
+            restore_parallelism: 1,
+            restore_mode: RestoreMode::Eager,
+            restore_modes: HashMap::new(),
+            lazy_pages_supported: None,
+        }
+    }
+
+    /// Lay out a dump directory as `dump` would leave it.
+    fn write_fake_dump(manager: &SnapshotManager, name: &str, complete: bool) -> PathBuf {
+        let function_id = FunctionId::new(name).unwrap();
+        let path = manager.snapshot_path(&function_id);
+        std::fs::create_dir_all(&path).unwrap();
+        if complete {
+            std::fs::write(path.join(INVENTORY_IMAGE), b"").unwrap();
+        }
+
+        let metadata = SnapshotMetadata {
+            function_id,
+            path: path.clone(),
+            original_pid: 1234,
+            created_at: std::time::SystemTime::now(),
+            size_bytes: 0,
+            last_restored_at: None,
+            content_hash: None,
+        };
+        std::fs::write(
+            path.join(METADATA_FILE),
+            serde_json::to_vec(&metadata).unwrap(),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_rehydrate() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = test_manager(dir.path());
+
+        write_fake_dump(&manager, "good-func", true);
+        write_fake_dump(&manager, "partial-func", false);
+        let corrupt = write_fake_dump(&manager, "corrupt-func", true);
+        std::fs::write(corrupt.join(METADATA_FILE), b"{not json").unwrap();
+        std::fs::create_dir(dir.path().join("unrelated")).unwrap();
+
+        assert_eq!(manager.rehydrate().unwrap(), 1);
+
+        let good = FunctionId::new("good-func").unwrap();
+        assert!(manager.has_snapshot(&good));
+        assert_eq!(manager.get_metadata(&good).unwrap().original_pid, 1234);
+        assert!(!manager.has_snapshot(&FunctionId::new("partial-func").unwrap()));
+        assert!(!manager.has_snapshot(&FunctionId::new("corrupt-func").unwrap()));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = test_manager(dir.path()).with_max_snapshot_bytes(2500);
+
+        for name in ["func-a", "func-b", "func-c"] {
+            let path = write_fake_dump(&manager, name, true);
+            std::fs::write(path.join("pages-1.img"), vec![0u8; 1000]).unwrap();
+        }
+        assert_eq!(manager.rehydrate().unwrap(), 3);
+
+        let [a, b, c] = ["func-a", "func-b", "func-c"].map(|n| FunctionId::new(n).unwrap());
+        assert!(manager.total_snapshot_bytes() > 3000);
+        assert_eq!(manager.snapshot_sizes().len(), 3);
+
+        // func-a was restored most recently, func-b before it; func-c never
+        let now = std::time::SystemTime::now();
+        manager.snapshots.get_mut(&b).unwrap().last_restored_at = Some(now);
+        manager.prepare_restore(&a).unwrap();
+
+        // func-c is the one being dumped, so func-b goes first despite func-c
+        // never having been restored
+        manager.enforce_budget(&c).unwrap();
+        assert!(manager.has_snapshot(&a));
+        assert!(!manager.has_snapshot(&b));
+        assert!(manager.has_snapshot(&c));
+        assert!(!dir
+            .path()
+            .join(format!("{}_{}", DUMP_DIR_PREFIX, b))
+            .exists());
+        assert!(manager.total_snapshot_bytes() <= 2500);
+        assert_eq!(manager.take_evicted(), vec![b.clone()]);
+        assert!(manager.take_evicted().is_empty());
+
+        // The restore time was persisted, so a restarted manager agrees
+        let mut reloaded = test_manager(dir.path());
+        reloaded.rehydrate().unwrap();
+        assert!(reloaded
+            .get_metadata(&a)
+            .unwrap()
+            .last_restored_at
+            .is_some());
+        assert!(reloaded
+            .get_metadata(&c)
+            .unwrap()
+            .last_restored_at
+            .is_none());
+
+        // A single snapshot over budget is kept
+        let mut tiny = test_manager(dir.path()).with_max_snapshot_bytes(10);
+        tiny.rehydrate().unwrap();
+        tiny.enforce_budget(&c).unwrap();
+        assert!(tiny.has_snapshot(&c));
+        assert_eq!(tiny.snapshot_sizes().len(), 1);
+        assert_eq!(tiny.take_evicted(), vec![a]);
+    }
+
+    /// Build a restore job around a fake CRIU shell script.
+    fn fake_criu_job(dir: &Path, body: &str, timeout_ms: u64) -> RestoreJob {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join("criu");
+        std::fs::write(&script, format!("#!/bin/sh\n{}", body)).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        RestoreJob {
+            function_id: FunctionId::new("test-func").unwrap(),
+            criu_path: script,
+            options: CriuOptions::default(),
+            dump_path: dir.to_path_buf(),
+            restore_timeout_ms: timeout_ms,
+            expected_hash: None,
+            retries: 0,
+            mode: RestoreMode::Eager,
+        }
+    }
+
+    /// Build a restore job around a fake CRIU script that writes `pid` to
+    /// the pid file after `delay` seconds.
+    fn fake_restore_job(dir: &Path, pid: u32, delay: &str, timeout_ms: u64) -> RestoreJob {
+        fake_criu_job(
+            dir,
+            &format!(
+                "sleep {}\nwhile [ \"$1\" != --pidfile ]; do shift; done\necho {} > \"$2\"\n",
+                delay, pid
+            ),
+            timeout_ms,
+        )
+    }
+
+    /// Fake CRIU that fails with `error` on its first `failures` runs, then
+    /// restores pid 4242. Each run is counted in `$dir/attempts`.
+    fn flaky_restore_job(dir: &Path, failures: u32, error: &str, timeout_ms: u64) -> RestoreJob {
+        let body = format!(
+            "cd \"$(dirname \"$0\")\"\n\
+             echo x >> attempts\n\
+             if [ $(wc -l < attempts) -le {} ]; then echo '{}' >&2; exit 1; fi\n\
+             while [ \"$1\" != --pidfile ]; do shift; done\n\
+             echo 4242 > \"$2\"\n",
+            failures, error
+        );
+        fake_criu_job(dir, &body, timeout_ms)
+    }
+
+    fn attempts(dir: &Path) -> usize {
+        std::fs::read_to_string(dir.join("attempts"))
+            .unwrap_or_default()
+            .lines()
+            .count()
+    }
+
+    #[test]
+    fn test_classify_restore_failure() {
+        assert_eq!(
+            classify_restore_failure(
+                "Error (criu/sk-inet.c:123): Can't bind: Address already in use"
+            ),
+            FailureKind::Transient
+        );
+        assert_eq!(
+            classify_restore_failure(
+                "Error (criu/image.c:456): Can't open core-1.img: No such file or directory"
+            ),
+            FailureKind::Permanent
+        );
+        assert_eq!(classify_restore_failure(""), FailureKind::Permanent);
+    }
+
+    #[test]
+    fn test_restore_retries_transient_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = flaky_restore_job(dir.path(), 2, "Address already in use", 10_000);
+
+        assert_eq!(job.with_retries(2).run_blocking().unwrap(), 4242);
+        assert_eq!(attempts(dir.path()), 3);
+    }
+
+    #[tokio::test]
+    async fn test_restore_retries_exhausted() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = flaky_restore_job(dir.path(), 5, "Address already in use", 10_000);
+
+        let result = job.with_retries(1).run().await;
+        assert!(matches!(result, Err(CriuError::RestoreFailed { .. })));
+        assert_eq!(attempts(dir.path()), 2);
+    }
+
+    #[test]
+    fn test_restore_does_not_retry_permanent_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = flaky_restore_job(
+            dir.path(),
+            1,
+            "core-1.img: No such file or directory",
+            10_000,
+        );
+
+        let result = job.with_retries(3).run_blocking();
+        assert!(matches!(result, Err(CriuError::RestoreFailed { .. })));
+        assert_eq!(attempts(dir.path()), 1);
+    }
+
+    #[test]
+    fn test_restore_retries_share_latency_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = flaky_restore_job(dir.path(), 100, "Address already in use", 15);
+
+        // Each attempt is well under budget on its own, but they add up
+        let result = job.with_retries(100).run_blocking();
+        match result {
+            Err(CriuError::LatencyViolation {
+                actual_ms,
+                limit_ms,
+            }) => {
+                assert_eq!(limit_ms, 15);
+                assert!(actual_ms > 15);
+            }
+            other => panic!("expected LatencyViolation, got {:?}", other),
+        }
+        assert!(attempts(dir.path()) > 1);
+    }
+
+    #[tokio::test]
+    async fn test_restore_job_async() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = fake_restore_job(dir.path(), 4242, "0", 10_000);
+
+        assert_eq!(job.clone().run().await.unwrap(), 4242);
+        assert_eq!(job.run_blocking().unwrap(), 4242);
+    }
+
+    #[tokio::test]
+    async fn test_restore_job_async_latency_violation() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut victim = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let job = fake_restore_job(dir.path(), victim.id(), "0.1", 15);
+
+        let result = job.run().await;
+        assert!(matches!(
+            result,
+            Err(CriuError::LatencyViolation { limit_ms: 15, .. })
+        ));
+
+        // The restored process is killed on violation
+        let status = victim.wait().unwrap();
+        assert!(!status.success());
+    }
+
+    #[tokio::test]
+    async fn test_restore_many() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::time::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        // Each restore takes 0.3s and restores the PID embedded in the dump
+        // directory name (func-<pid>)
+        let script = dir.path().join("criu");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+             sleep 0.3\n\
+             while [ \"$1\" != --pidfile ]; do shift; done\n\
+             basename \"$(dirname \"$2\")\" | sed 's/.*-//' > \"$2\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut manager = test_manager(dir.path()).with_restore_parallelism(2);
+        manager.criu_path = script;
+        manager.restore_timeout_ms = 10_000;
+        let names = ["func-101", "func-102", "func-103", "func-104"];
+        for name in names {
+            write_fake_dump(&manager, name, true);
+        }
+        assert_eq!(manager.rehydrate().unwrap(), 4);
+
+        let mut ids: Vec<FunctionId> = names.iter().map(|n| FunctionId::new(*n).unwrap()).collect();
+        ids.push(FunctionId::new("func-missing").unwrap());
+        ids.push(ids[0].clone());
+
+        let start = Instant::now();
+        let results = manager.restore_many(&ids).await;
+        let elapsed = start.elapsed();
+
+        assert_eq!(results.len(), 6);
+        for (i, (function_id, result)) in results.iter().take(4).enumerate() {
+            assert_eq!(function_id, &ids[i]);
+            assert_eq!(*result.as_ref().unwrap(), 101 + i as u32);
+        }
+        assert!(matches!(
+            results[4].1,
+            Err(CriuError::SnapshotNotFound { .. })
+        ));
+        assert!(matches!(results[5].1, Err(CriuError::RestoreFailed { .. })));
+
+        // Two waves of two: slower than all at once, faster than one by one
+        assert!(elapsed >= Duration::from_millis(550), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1150), "{:?}", elapsed);
+    }
+
+    /// A fake CRIU whose `lazy-pages` creates the daemon socket (unless
+    /// `serve` is false) and whose `restore` only succeeds with
+    /// `--lazy-pages` and that socket present.
+    fn lazy_restore_job(dir: &Path, serve: bool) -> RestoreJob {
+        let serve = if serve {
+            "touch \"$3/lazy-pages.socket\""
+        } else {
+            "exit 1"
+        };
+        let body = format!(
+            "if [ \"$1\" = lazy-pages ]; then {}; exit 0; fi\n\
+             lazy=no\n\
+             while [ $# -gt 0 ]; do\n\
+             case \"$1\" in --lazy-pages) lazy=yes ;; --pidfile) shift; pidfile=$1 ;; esac\n\
+             shift\n\
+             done\n\
+             [ $lazy = yes ] && [ -e \"$(dirname \"$pidfile\")/lazy-pages.socket\" ] || exit 1\n\
+             echo 4242 > \"$pidfile\"\n",
+            serve
+        );
+        let mut job = fake_criu_job(dir, &body, 5000);
+        job.mode = RestoreMode::Lazy;
+        job
+    }
+
+    #[tokio::test]
+    async fn test_lazy_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = lazy_restore_job(dir.path(), true);
+        assert_eq!(job.clone().run().await.unwrap(), 4242);
+        assert_eq!(job.run_blocking().unwrap(), 4242);
+
+        // Without lazy pages the fake refuses to restore
+        let mut eager = lazy_restore_job(dir.path(), true);
+        eager.mode = RestoreMode::Eager;
+        assert!(eager.run_blocking().is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let err = lazy_restore_job(dir.path(), false).run().await.unwrap_err();
+        assert!(
+            matches!(&err, CriuError::RestoreFailed { reason } if reason.contains("lazy-pages exited")),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_restore_mode_fallback() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = test_manager(dir.path());
+        let id = FunctionId::new("func-lazy").unwrap();
+        write_fake_dump(&manager, "func-lazy", true);
+        manager.rehydrate().unwrap();
+
+        // Eager by default, without probing CRIU
+        assert_eq!(
+            manager.prepare_restore(&id).unwrap().mode(),
+            RestoreMode::Eager
+        );
+        assert_eq!(manager.lazy_pages_supported, None);
+
+        // /bin/false fails `criu check`, so lazy falls back
+        manager.set_restore_mode(&id, RestoreMode::Lazy);
+        assert_eq!(
+            manager.prepare_restore(&id).unwrap().mode(),
+            RestoreMode::Eager
+        );
+        assert_eq!(manager.lazy_pages_supported, Some(false));
+
+        let script = dir.path().join("criu");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n[ \"$1 $3\" = \"check uffd-noncoop\" ]\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        manager.criu_path = script;
+        manager.lazy_pages_supported = None;
+        assert_eq!(
+            manager.prepare_restore(&id).unwrap().mode(),
+            RestoreMode::Lazy
+        );
+        assert!(manager.probe_lazy_pages());
+    }
+
+    #[tokio::test]
+    async fn test_integrity_check() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("core-1.img"), b"core").unwrap();
+        std::fs::write(dir.path().join("pages-1.img"), vec![7u8; 4096]).unwrap();
+        let hash = hash_dump(dir.path()).unwrap();
+
+        let mut job = fake_restore_job(dir.path(), 4242, "0", 10_000);
+        job.expected_hash = Some(hash);
+        assert_eq!(job.clone().run_blocking().unwrap(), 4242);
+
+        // Truncate an image: the restore fails before CRIU runs
+        std::fs::write(dir.path().join("pages-1.img"), vec![7u8; 1024]).unwrap();
+        assert!(matches!(
+            job.clone().run_blocking(),
+            Err(CriuError::SnapshotCorrupt { .. })
+        ));
+        assert!(matches!(
+            job.run().await,
+            Err(CriuError::SnapshotCorrupt { .. })
+        ));
+
+        // Non-image files don't take part
+        std::fs::write(dir.path().join("pages-1.img"), vec![7u8; 4096]).unwrap();
+        std::fs::write(dir.path().join("restored.pid"), b"1").unwrap();
+        assert_eq!(hash_dump(dir.path()).unwrap(), hash);
+    }
 }
@@ -58,6 +58,12 @@ pub enum AetherError {
     #[error("eBPF error: {0}")]
     Ebpf(#[from] EbpfError),
 
+    // =========================================================================
+    // cgroup Errors - Resource Limits
+    // =========================================================================
+    #[error("cgroup error: {0}")]
+    Cgroup(#[from] CgroupError),
+
     // =========================================================================
     // System Errors
     // =========================================================================
@@ -144,6 +150,15 @@ pub enum SharedMemoryError {
     #[error("Failed to unmap shared memory: {reason}")]
     UnmapFailed { reason: String },
 
+    #[error(
+        "Failed to lock {size} bytes of shared memory in RAM (RLIMIT_MEMLOCK: {limit_bytes} bytes): {reason}"
+    )]
+    LockFailed {
+        size: usize,
+        limit_bytes: u64,
+        reason: String,
+    },
+
     #[error("Ring buffer full - cannot write {size} bytes")]
     RingBufferFull { size: usize },
 
@@ -169,8 +184,11 @@ pub enum CriuError {
     #[error("Failed to spawn function process: {reason}")]
     SpawnFailed { reason: String },
 
-    #[error("Process did not send READY signal within timeout")]
-    ReadyTimeout,
+    #[error("Process did not send READY signal within {waited_ms}ms")]
+    ReadyTimeout { waited_ms: u64 },
+
+    #[error("Process connected to the control socket but did not send READY within {waited_ms}ms")]
+    ReadyNotSent { waited_ms: u64 },
 
     #[error("CRIU dump failed: {reason}")]
     DumpFailed { reason: String },
@@ -184,8 +202,23 @@ pub enum CriuError {
     #[error("Snapshot not found for function: {function_id}")]
     SnapshotNotFound { function_id: FunctionId },
 
+    #[error("Snapshot corrupt for function: {function_id}")]
+    SnapshotCorrupt { function_id: FunctionId },
+
     #[error("Unix socket error: {reason}")]
     UnixSocket { reason: String },
+
+    #[error("Health check failed: {reason}")]
+    HealthCheckFailed { reason: String },
+
+    #[error(
+        "Snapshot directory {} is on {fs_type}, not a memory-backed filesystem (tmpfs or ramfs); restores will miss the latency limit. Use a directory under /dev/shm",
+        path.display()
+    )]
+    SnapshotDirNotInMemory {
+        path: std::path::PathBuf,
+        fs_type: String,
+    },
 }
 
 /// eBPF errors - no fallback to userspace routing.
@@ -219,6 +252,33 @@ pub enum EbpfError {
     VerificationFailed { reason: String },
 }
 
+/// cgroup v2 errors while applying handler resource limits.
+#[derive(Debug, Error)]
+pub enum CgroupError {
+    #[error("cgroup v2 is not mounted at {root}")]
+    NotMounted { root: PathBuf },
+
+    #[error("cgroup controller '{controller}' is not available at {root}")]
+    ControllerUnavailable {
+        controller: &'static str,
+        root: PathBuf,
+    },
+
+    #[error("Failed to write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
 /// Result type alias using AetherError.
 pub type AetherResult<T> = Result<T, AetherError>;
 
@@ -5,8 +5,9 @@
 //!
 //! Core orchestrator library for the Aetherless serverless platform.
 //! Provides function registry, state machine, configuration parsing,
-//! shared memory IPC, and CRIU lifecycle management.
+//! shared memory IPC, CRIU lifecycle management, and cgroup resource limits.
 
+pub mod cgroup;
 pub mod config;
 pub mod criu;
 pub mod error;
@@ -16,8 +17,14 @@ pub mod state;
 pub mod types;
 
 // Re-export commonly used types
-pub use config::{Config, ConfigLoader, FunctionConfig, OrchestratorConfig};
-pub use error::{AetherError, AetherResult, EbpfError, HardValidationError};
-pub use registry::FunctionRegistry;
+pub use config::{
+    Config, ConfigDiff, ConfigLoader, FieldChange, FunctionChange, FunctionConfig, HandshakeMode,
+    LoadOptions, OrchestratorConfig, RestartPolicy, RestoreMode, XdpAttachMode,
+    DEFAULT_MAX_ENV_BYTES, DEFAULT_MAX_ENV_VARS, PRIVILEGED_PORT_LIMIT,
+};
+pub use error::{AetherError, AetherResult, CgroupError, EbpfError, HardValidationError};
+pub use registry::{
+    Endpoint, FunctionRegistry, RegistryEvent, RegistrySnapshot, SnapshotChanges, SnapshotEntry,
+};
 pub use state::{FunctionState, FunctionStateMachine};
-pub use types::{FunctionId, HandlerPath, MemoryLimit, Port, ProcessId};
+pub use types::{CpuLimit, ExitReason, FunctionId, HandlerPath, MemoryLimit, Port, ProcessId};
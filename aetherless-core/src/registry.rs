@@ -4,15 +4,37 @@
 //! Thread-safe function registry using DashMap.
 //!
 //! Provides concurrent access to registered functions and their state machines.
+//! The registry can be persisted to disk and restored after a restart, and
+//! publishes change events to any number of subscribers. Each function also
+//! tracks the handler instances ([`Endpoint`]s) currently serving it, so
+//! traffic can be spread across replicas.
 
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
 
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 
 use crate::config::FunctionConfig;
-use crate::error::{AetherError, AetherResult};
+use crate::error::{AetherError, AetherResult, HardValidationError};
 use crate::state::{FunctionState, FunctionStateMachine, StateMachineMetrics};
-use crate::types::FunctionId;
+use crate::types::{ExitReason, FunctionId, Port, ProcessId};
+
+/// A running handler instance serving a function.
+///
+/// A function with a single handler has one endpoint on its `trigger_port`;
+/// each further replica is given its own port by the orchestrator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Endpoint {
+    /// Port the handler listens on.
+    pub port: Port,
+    /// Handler process.
+    pub pid: ProcessId,
+}
 
 /// Entry in the function registry.
 #[derive(Debug)]
@@ -21,32 +43,113 @@ pub struct FunctionEntry {
     pub config: FunctionConfig,
     /// State machine managing the function lifecycle.
     pub state_machine: FunctionStateMachine,
+    /// Handler instances currently serving the function.
+    /// Runtime-only: not persisted, since PIDs do not survive a restart.
+    pub endpoints: Vec<Endpoint>,
+    /// How the most recent handler ended, if one has. Runtime-only, like
+    /// `endpoints`.
+    pub last_exit: Option<ExitReason>,
+    /// Round-robin cursor for [`FunctionRegistry::next_endpoint`].
+    next_endpoint: AtomicUsize,
 }
 
 impl FunctionEntry {
     /// Create a new function entry.
     pub fn new(config: FunctionConfig) -> Self {
         let state_machine = FunctionStateMachine::new(config.id.clone());
+        Self::with_state_machine(config, state_machine)
+    }
+
+    fn with_state_machine(config: FunctionConfig, state_machine: FunctionStateMachine) -> Self {
         Self {
             config,
             state_machine,
+            endpoints: Vec::new(),
+            last_exit: None,
+            next_endpoint: AtomicUsize::new(0),
         }
     }
 }
 
+/// Number of events buffered per subscriber before slow receivers start lagging.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Change notification published by the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryEvent {
+    /// A function was registered.
+    Registered(FunctionId),
+    /// A function was unregistered.
+    Unregistered(FunctionId),
+    /// A function's state machine changed state.
+    StateChanged {
+        id: FunctionId,
+        from: FunctionState,
+        to: FunctionState,
+    },
+    /// A function's configuration was replaced.
+    ConfigUpdated(FunctionId),
+}
+
+/// A function as captured in a [`RegistrySnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub config: FunctionConfig,
+    pub state: FunctionState,
+}
+
+/// Serializable point-in-time view of a registry: every function's config
+/// and state, sorted by ID. Endpoints are runtime-only and not included.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegistrySnapshot {
+    pub functions: Vec<SnapshotEntry>,
+}
+
+/// What [`FunctionRegistry::apply_snapshot`] changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotChanges {
+    /// Functions registered from the snapshot.
+    pub added: Vec<FunctionId>,
+    /// Functions whose config or state was replaced.
+    pub updated: Vec<FunctionId>,
+    /// Functions unregistered because the snapshot lacked them.
+    pub removed: Vec<FunctionId>,
+}
+
+impl SnapshotChanges {
+    /// Whether the registry already matched the snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
 /// Thread-safe registry for managing functions.
 /// Uses DashMap for lock-free concurrent access.
 #[derive(Debug)]
 pub struct FunctionRegistry {
     /// Map of function ID to function entry.
     functions: DashMap<FunctionId, FunctionEntry>,
+    /// Secondary index of trigger port to function ID.
+    /// Always updated while the owning `functions` entry is locked, in that
+    /// order, so lookups never observe a half-applied change.
+    ports: DashMap<Port, FunctionId>,
+    /// Broadcasts change events; sending never blocks.
+    events: broadcast::Sender<RegistryEvent>,
+    /// Held shared by every change to membership, config or state, and
+    /// exclusively by [`FunctionRegistry::snapshot`], so a snapshot never
+    /// sees half of a change that spans several entries.
+    view: RwLock<()>,
 }
 
 impl FunctionRegistry {
     /// Create a new empty registry.
     pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
         Self {
             functions: DashMap::new(),
+            ports: DashMap::new(),
+            events,
+            view: RwLock::new(()),
         }
     }
 
@@ -55,28 +158,142 @@ impl FunctionRegistry {
         Arc::new(Self::new())
     }
 
+    /// Subscribe to registry change events.
+    ///
+    /// Receivers that fall more than EVENT_CHANNEL_CAPACITY events behind
+    /// observe `RecvError::Lagged` and skip the oldest events.
+    pub fn subscribe(&self) -> broadcast::Receiver<RegistryEvent> {
+        self.events.subscribe()
+    }
+
+    /// Guard to hold while changing membership, config or state. Take it
+    /// before any map entry lock, and only once per call.
+    fn changing(&self) -> RwLockReadGuard<'_, ()> {
+        self.view.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Publish an event; having no subscribers is not an error.
+    fn emit(&self, event: RegistryEvent) {
+        let _ = self.events.send(event);
+    }
+
     /// Register a new function.
-    /// Returns HardValidationError if function already exists.
+    ///
+    /// # Errors
+    /// FunctionAlreadyExists if the ID is taken, InvalidPort if the trigger
+    /// port is bound to another function.
     pub fn register(&self, config: FunctionConfig) -> AetherResult<()> {
+        let _view = self.changing();
         let id = config.id.clone();
+        let state_machine = FunctionStateMachine::new(id.clone());
+        self.insert_new(config, state_machine)?;
+        self.emit(RegistryEvent::Registered(id));
 
-        // Check for duplicate - fail fast
-        if self.functions.contains_key(&id) {
-            return Err(AetherError::FunctionAlreadyExists(id));
+        Ok(())
+    }
+
+    /// Register a batch of functions, all or nothing.
+    ///
+    /// The batch is first checked for duplicate IDs and trigger ports within
+    /// itself. Functions are then inserted one at a time; if one conflicts
+    /// with an existing function's ID or port, the ones already inserted are
+    /// removed again and the registry is left as it was. Concurrent readers
+    /// may briefly see part of a batch that is later rolled back, but
+    /// `Registered` events are only sent once the whole batch is in.
+    ///
+    /// # Errors
+    /// DuplicateFunctionId or InvalidPort for a conflict within the batch,
+    /// FunctionAlreadyExists or InvalidPort for one with the registry.
+    pub fn register_all(&self, configs: Vec<FunctionConfig>) -> AetherResult<()> {
+        let mut ids = HashSet::with_capacity(configs.len());
+        let mut ports = HashSet::with_capacity(configs.len());
+        for config in &configs {
+            if !ids.insert(&config.id) {
+                return Err(HardValidationError::DuplicateFunctionId {
+                    id: config.id.to_string(),
+                }
+                .into());
+            }
+            if !ports.insert(config.trigger_port) {
+                return Err(HardValidationError::InvalidPort {
+                    port: config.trigger_port.value(),
+                    reason: format!(
+                        "Port {} is used twice in the batch (by '{}')",
+                        config.trigger_port, config.id
+                    ),
+                }
+                .into());
+            }
         }
 
-        let entry = FunctionEntry::new(config);
-        self.functions.insert(id, entry);
+        let _view = self.changing();
+        let mut inserted = Vec::with_capacity(configs.len());
+        for config in configs {
+            let (id, port) = (config.id.clone(), config.trigger_port);
+            let state_machine = FunctionStateMachine::new(id.clone());
+            if let Err(e) = self.insert_new(config, state_machine) {
+                for (id, port) in inserted {
+                    self.ports.remove_if(&port, |_, owner| *owner == id);
+                    self.functions.remove(&id);
+                }
+                return Err(e);
+            }
+            inserted.push((id, port));
+        }
 
+        for (id, _) in inserted {
+            self.emit(RegistryEvent::Registered(id));
+        }
         Ok(())
     }
 
+    /// Insert a function whose ID and trigger port must both be unused.
+    fn insert_new(
+        &self,
+        config: FunctionConfig,
+        state_machine: FunctionStateMachine,
+    ) -> AetherResult<()> {
+        let id = config.id.clone();
+        let port = config.trigger_port;
+
+        // Entry locks are taken functions first, then ports, as everywhere else
+        let Entry::Vacant(vacant) = self.functions.entry(id.clone()) else {
+            return Err(AetherError::FunctionAlreadyExists(id));
+        };
+        match self.ports.entry(port) {
+            Entry::Occupied(owner) => Err(HardValidationError::InvalidPort {
+                port: port.value(),
+                reason: format!(
+                    "Port {} is already bound to function '{}'",
+                    port,
+                    owner.get()
+                ),
+            }
+            .into()),
+            Entry::Vacant(port_entry) => {
+                port_entry.insert(id);
+                vacant.insert(FunctionEntry::with_state_machine(config, state_machine));
+                Ok(())
+            }
+        }
+    }
+
     /// Unregister a function.
     pub fn unregister(&self, id: &FunctionId) -> AetherResult<FunctionEntry> {
-        self.functions
-            .remove(id)
-            .map(|(_, entry)| entry)
-            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))
+        let _view = self.changing();
+        let entry = match self.functions.entry(id.clone()) {
+            Entry::Occupied(occupied) => {
+                let entry = occupied.get();
+                let ports = entry.endpoints.iter().map(|endpoint| endpoint.port);
+                for port in ports.chain([entry.config.trigger_port]) {
+                    self.ports.remove_if(&port, |_, owner| owner == id);
+                }
+                occupied.remove()
+            }
+            Entry::Vacant(_) => return Err(AetherError::FunctionNotFound(id.clone())),
+        };
+        self.emit(RegistryEvent::Unregistered(id.clone()));
+        Ok(entry)
     }
 
     /// Get the current state of a function.
@@ -89,15 +306,181 @@ impl FunctionRegistry {
 
     /// Transition a function to a new state.
     pub fn transition(&self, id: &FunctionId, target: FunctionState) -> AetherResult<()> {
+        let _view = self.changing();
+        let mut entry = self
+            .functions
+            .get_mut(id)
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))?;
+
+        let from = entry.state_machine.state();
+        entry.state_machine.transition_to(target)?;
+        // Release the shard lock before notifying subscribers
+        drop(entry);
+
+        self.emit(RegistryEvent::StateChanged {
+            id: id.clone(),
+            from,
+            to: target,
+        });
+        Ok(())
+    }
+
+    /// Transition a function to `target` only if it is currently in `expected`.
+    ///
+    /// The check and the transition happen under the entry's lock, so when
+    /// several callers race to move a function out of the same state exactly
+    /// one of them wins. Returns whether the transition happened; an invalid
+    /// `expected` to `target` transition is still an error.
+    pub fn transition_if(
+        &self,
+        id: &FunctionId,
+        expected: FunctionState,
+        target: FunctionState,
+    ) -> AetherResult<bool> {
+        let _view = self.changing();
         let mut entry = self
             .functions
             .get_mut(id)
             .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))?;
 
+        if entry.state_machine.state() != expected {
+            return Ok(false);
+        }
         entry.state_machine.transition_to(target)?;
+        // Release the shard lock before notifying subscribers
+        drop(entry);
+
+        self.emit(RegistryEvent::StateChanged {
+            id: id.clone(),
+            from: expected,
+            to: target,
+        });
+        Ok(true)
+    }
+
+    /// Get the number of handler restarts recorded for a function.
+    pub fn restart_count(&self, id: &FunctionId) -> AetherResult<u32> {
+        self.functions
+            .get(id)
+            .map(|entry| entry.state_machine.restart_count())
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))
+    }
+
+    /// Record a handler restart for a function, returning the new count.
+    pub fn record_restart(&self, id: &FunctionId) -> AetherResult<u32> {
+        let mut entry = self
+            .functions
+            .get_mut(id)
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))?;
+
+        Ok(entry.state_machine.record_restart())
+    }
+
+    /// Record how a function's handler ended.
+    pub fn record_exit(&self, id: &FunctionId, reason: ExitReason) -> AetherResult<()> {
+        let mut entry = self
+            .functions
+            .get_mut(id)
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))?;
+
+        entry.last_exit = Some(reason);
+        Ok(())
+    }
+
+    /// How a function's most recent handler ended, if one has.
+    pub fn last_exit(&self, id: &FunctionId) -> AetherResult<Option<ExitReason>> {
+        self.functions
+            .get(id)
+            .map(|entry| entry.last_exit)
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))
+    }
+
+    /// Add a handler instance serving a function.
+    ///
+    /// Replaces any endpoint already on the same port, e.g. after a restart.
+    /// Fails if the port is bound to a different function.
+    pub fn add_endpoint(&self, id: &FunctionId, endpoint: Endpoint) -> AetherResult<()> {
+        let mut entry = self
+            .functions
+            .get_mut(id)
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))?;
+
+        if let Some(owner) = self.ports.get(&endpoint.port) {
+            if *owner != *id {
+                return Err(HardValidationError::InvalidPort {
+                    port: endpoint.port.value(),
+                    reason: format!("already bound to function {}", *owner),
+                }
+                .into());
+            }
+        }
+
+        entry.endpoints.retain(|e| e.port != endpoint.port);
+        entry.endpoints.push(endpoint);
+        self.ports.insert(endpoint.port, id.clone());
         Ok(())
     }
 
+    /// Remove the handler instance on `port`, returning it if there was one.
+    pub fn remove_endpoint(&self, id: &FunctionId, port: Port) -> AetherResult<Option<Endpoint>> {
+        let mut entry = self
+            .functions
+            .get_mut(id)
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))?;
+
+        let Some(index) = entry.endpoints.iter().position(|e| e.port == port) else {
+            return Ok(None);
+        };
+        let endpoint = entry.endpoints.remove(index);
+        // The trigger port stays bound to the function with no handler on it
+        if port != entry.config.trigger_port {
+            self.ports.remove_if(&port, |_, owner| owner == id);
+        }
+        Ok(Some(endpoint))
+    }
+
+    /// Get the handler instances serving a function.
+    pub fn endpoints(&self, id: &FunctionId) -> AetherResult<Vec<Endpoint>> {
+        self.functions
+            .get(id)
+            .map(|entry| entry.endpoints.clone())
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))
+    }
+
+    /// Pick the endpoint for the next request to a function, round-robin.
+    ///
+    /// Endpoints rejected by `is_available` (e.g. an open circuit breaker)
+    /// are skipped. Returns `None` if no endpoint is available, which a
+    /// proxy should answer with 503.
+    pub fn next_endpoint(
+        &self,
+        id: &FunctionId,
+        is_available: impl Fn(&Endpoint) -> bool,
+    ) -> AetherResult<Option<Endpoint>> {
+        let entry = self
+            .functions
+            .get(id)
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))?;
+
+        let count = entry.endpoints.len();
+        if count == 0 {
+            return Ok(None);
+        }
+        let start = entry.next_endpoint.fetch_add(1, Ordering::Relaxed);
+        Ok((0..count)
+            .map(|offset| entry.endpoints[(start + offset) % count])
+            .find(|endpoint| is_available(endpoint)))
+    }
+
+    /// Find the function bound to a port: its trigger port or the port of
+    /// one of its endpoints.
+    ///
+    /// Ports are unique in a validated config; if two registered functions
+    /// share one, the most recently registered or updated one wins.
+    pub fn find_by_port(&self, port: Port) -> Option<FunctionId> {
+        self.ports.get(&port).map(|id| id.clone())
+    }
+
     /// Check if a function exists.
     pub fn contains(&self, id: &FunctionId) -> bool {
         self.functions.contains_key(id)
@@ -144,15 +527,198 @@ impl FunctionRegistry {
     }
 
     /// Update the configuration for a function (hot-reload).
+    ///
+    /// A new trigger port must not be bound to another function. The
+    /// endpoint on the old trigger port is dropped along with its binding.
     pub fn update_config(&self, config: FunctionConfig) -> AetherResult<()> {
+        let _view = self.changing();
         let mut entry = self
             .functions
             .get_mut(&config.id)
             .ok_or_else(|| AetherError::FunctionNotFound(config.id.clone()))?;
 
+        let id = config.id.clone();
+        if let Some(owner) = self.ports.get(&config.trigger_port) {
+            if *owner != id {
+                return Err(HardValidationError::InvalidPort {
+                    port: config.trigger_port.value(),
+                    reason: format!("already bound to function {}", *owner),
+                }
+                .into());
+            }
+        }
+
+        let old_port = entry.config.trigger_port;
+        if old_port != config.trigger_port {
+            entry.endpoints.retain(|e| e.port != old_port);
+            self.ports.remove_if(&old_port, |_, owner| *owner == id);
+        }
+        self.ports.insert(config.trigger_port, id.clone());
         entry.config = config;
+        drop(entry);
+
+        self.emit(RegistryEvent::ConfigUpdated(id));
         Ok(())
     }
+
+    /// Capture every function's configuration and state.
+    ///
+    /// Changes are held off while the map is read, so the snapshot reflects
+    /// one point in time, e.g. all or none of a `register_all` batch.
+    pub fn snapshot(&self) -> RegistrySnapshot {
+        let _exclusive = self.view.write().unwrap_or_else(|e| e.into_inner());
+        let mut functions: Vec<SnapshotEntry> = self
+            .functions
+            .iter()
+            .map(|r| SnapshotEntry {
+                config: r.config.clone(),
+                state: r.state_machine.state(),
+            })
+            .collect();
+        functions.sort_by(|a, b| a.config.id.as_str().cmp(b.config.id.as_str()));
+        RegistrySnapshot { functions }
+    }
+
+    /// Reconcile the registry with a snapshot, e.g. one received from a peer.
+    ///
+    /// Missing functions are registered in the snapshot's state, and
+    /// functions whose config or state differs are updated. A differing
+    /// state replaces the local state machine outright, since the peer's
+    /// transitions are not replayed; its history and restart count start
+    /// over. With `remove_absent`, functions the snapshot lacks are
+    /// unregistered first.
+    ///
+    /// Changes are applied one function at a time and stop at the first
+    /// error, e.g. a new function whose port is bound locally; earlier
+    /// changes are kept.
+    pub fn apply_snapshot(
+        &self,
+        snapshot: RegistrySnapshot,
+        remove_absent: bool,
+    ) -> AetherResult<SnapshotChanges> {
+        let mut changes = SnapshotChanges::default();
+
+        if remove_absent {
+            let keep: HashSet<&FunctionId> =
+                snapshot.functions.iter().map(|e| &e.config.id).collect();
+            for id in self.function_ids() {
+                if !keep.contains(&id) && self.unregister(&id).is_ok() {
+                    changes.removed.push(id);
+                }
+            }
+        }
+
+        for SnapshotEntry { config, state } in snapshot.functions {
+            let id = config.id.clone();
+            let Ok(current) = self.get_config(&id) else {
+                let state_machine = FunctionStateMachine::from_state(id.clone(), state);
+                {
+                    let _view = self.changing();
+                    self.insert_new(config, state_machine)?;
+                }
+                self.emit(RegistryEvent::Registered(id.clone()));
+                changes.added.push(id);
+                continue;
+            };
+
+            let mut updated = false;
+            if current != config {
+                self.update_config(config)?;
+                updated = true;
+            }
+            if self.replace_state(&id, state)? {
+                updated = true;
+            }
+            if updated {
+                changes.updated.push(id);
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Put a function in `state` without a transition check. Returns whether
+    /// the state changed.
+    fn replace_state(&self, id: &FunctionId, state: FunctionState) -> AetherResult<bool> {
+        let _view = self.changing();
+        let mut entry = self
+            .functions
+            .get_mut(id)
+            .ok_or_else(|| AetherError::FunctionNotFound(id.clone()))?;
+
+        let from = entry.state_machine.state();
+        if from == state {
+            return Ok(false);
+        }
+        entry.state_machine = FunctionStateMachine::from_state(id.clone(), state);
+        drop(entry);
+
+        self.emit(RegistryEvent::StateChanged {
+            id: id.clone(),
+            from,
+            to: state,
+        });
+        Ok(true)
+    }
+
+    /// Save every function's configuration and current state to `path` as JSON.
+    ///
+    /// The file is written to a temporary sibling first and then renamed, so
+    /// a crash mid-write never leaves a truncated registry behind.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> AetherResult<()> {
+        let path = path.as_ref();
+        let entries = self.snapshot().functions;
+
+        let json = serde_json::to_vec_pretty(&entries).map_err(|e| AetherError::ConfigParse {
+            message: format!("Failed to serialize registry: {}", e),
+        })?;
+
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, json).map_err(|e| AetherError::Io {
+            context: "writing registry state",
+            source: e,
+        })?;
+        std::fs::rename(&tmp_path, path).map_err(|e| AetherError::Io {
+            context: "renaming registry state",
+            source: e,
+        })
+    }
+
+    /// Restore a registry previously written with [`FunctionRegistry::save_to`].
+    ///
+    /// A missing file yields an empty registry; a corrupt one is a ConfigParse error.
+    pub fn load_from(path: impl AsRef<Path>) -> AetherResult<Self> {
+        let content = match std::fs::read(path.as_ref()) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => {
+                return Err(AetherError::Io {
+                    context: "reading registry state",
+                    source: e,
+                })
+            }
+        };
+
+        let entries: Vec<SnapshotEntry> =
+            serde_json::from_slice(&content).map_err(|e| AetherError::ConfigParse {
+                message: format!("Corrupt registry state: {}", e),
+            })?;
+
+        let registry = Self::new();
+        // Restored entries are not announced; nobody can have subscribed yet
+        for SnapshotEntry { config, state } in entries {
+            registry
+                .ports
+                .insert(config.trigger_port, config.id.clone());
+            let state_machine = FunctionStateMachine::from_state(config.id.clone(), state);
+            registry.functions.insert(
+                config.id.clone(),
+                FunctionEntry::with_state_machine(config, state_machine),
+            );
+        }
+
+        Ok(registry)
+    }
 }
 
 impl Default for FunctionRegistry {
@@ -164,16 +730,25 @@ impl Default for FunctionRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{MemoryLimit, Port};
+    use crate::types::MemoryLimit;
 
     fn make_config(name: &str) -> FunctionConfig {
         FunctionConfig {
             id: FunctionId::new(name).unwrap(),
             memory_limit: MemoryLimit::from_mb(128).unwrap(),
+            cpu_limit: None,
             trigger_port: Port::new(8080).unwrap(),
             handler_path: crate::types::HandlerPath::new_unchecked("/bin/echo"),
             environment: std::collections::HashMap::new(),
             timeout_ms: 30000,
+            restart_policy: Default::default(),
+            max_restarts: 5,
+            restart_backoff_max_ms: 30000,
+            max_concurrency: None,
+            ready_timeout_ms: 30000,
+            handshake: Default::default(),
+            health_check_interval_ms: None,
+            restore_mode: Default::default(),
         }
     }
 
@@ -222,13 +797,255 @@ mod tests {
         assert_eq!(registry.get_state(&id).unwrap(), FunctionState::Running);
     }
 
+    #[test]
+    fn test_transition_if() {
+        let registry = FunctionRegistry::new();
+        let config = make_config("test-func");
+        let id = config.id.clone();
+        registry.register(config).unwrap();
+
+        // Not in the expected state: nothing happens
+        assert!(!registry
+            .transition_if(&id, FunctionState::WarmSnapshot, FunctionState::Running)
+            .unwrap());
+        assert_eq!(
+            registry.get_state(&id).unwrap(),
+            FunctionState::Uninitialized
+        );
+
+        // Invalid transitions are still rejected
+        assert!(registry
+            .transition_if(&id, FunctionState::Uninitialized, FunctionState::Suspended)
+            .is_err());
+
+        assert!(registry
+            .transition_if(
+                &id,
+                FunctionState::Uninitialized,
+                FunctionState::WarmSnapshot
+            )
+            .unwrap());
+        assert!(registry
+            .transition_if(
+                &id,
+                FunctionState::Uninitialized,
+                FunctionState::WarmSnapshot
+            )
+            .is_ok_and(|moved| !moved));
+    }
+
+    #[test]
+    fn test_transition_if_single_winner() {
+        const CONTENDERS: usize = 8;
+
+        for _ in 0..50 {
+            let registry = Arc::new(FunctionRegistry::new());
+            let config = make_config("cold-func");
+            let id = config.id.clone();
+            registry.register(config).unwrap();
+            registry
+                .transition(&id, FunctionState::WarmSnapshot)
+                .unwrap();
+
+            let barrier = Arc::new(std::sync::Barrier::new(CONTENDERS));
+            let contenders: Vec<_> = (0..CONTENDERS)
+                .map(|_| {
+                    let registry = Arc::clone(&registry);
+                    let barrier = Arc::clone(&barrier);
+                    let id = id.clone();
+                    std::thread::spawn(move || {
+                        barrier.wait();
+                        registry
+                            .transition_if(&id, FunctionState::WarmSnapshot, FunctionState::Running)
+                            .unwrap()
+                    })
+                })
+                .collect();
+
+            let winners = contenders
+                .into_iter()
+                .map(|contender| contender.join().unwrap())
+                .filter(|&won| won)
+                .count();
+            assert_eq!(winners, 1);
+            assert_eq!(registry.get_state(&id).unwrap(), FunctionState::Running);
+        }
+    }
+
+    fn make_config_on(name: &str, port: u16) -> FunctionConfig {
+        FunctionConfig {
+            trigger_port: Port::new(port).unwrap(),
+            ..make_config(name)
+        }
+    }
+
+    #[test]
+    fn test_register_all() {
+        let registry = FunctionRegistry::new();
+        let mut events = registry.subscribe();
+        registry
+            .register_all(vec![make_config_on("a", 9001), make_config_on("b", 9002)])
+            .unwrap();
+        assert_eq!(registry.len(), 2);
+        assert!(matches!(
+            events.try_recv(),
+            Ok(RegistryEvent::Registered(_))
+        ));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(RegistryEvent::Registered(_))
+        ));
+
+        // Duplicates within the batch are caught before anything is inserted
+        let err = registry
+            .register_all(vec![make_config_on("c", 9003), make_config_on("c", 9004)])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AetherError::HardValidation(HardValidationError::DuplicateFunctionId { ref id }) if id == "c"
+        ));
+        let err = registry
+            .register_all(vec![make_config_on("c", 9003), make_config_on("d", 9003)])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AetherError::HardValidation(HardValidationError::InvalidPort { port: 9003, .. })
+        ));
+
+        // A conflict with the registry rolls back the earlier inserts
+        let err = registry
+            .register_all(vec![
+                make_config_on("c", 9003),
+                make_config_on("d", 9004),
+                make_config_on("a", 9005),
+            ])
+            .unwrap_err();
+        assert!(matches!(err, AetherError::FunctionAlreadyExists(ref id) if id.as_str() == "a"));
+        let err = registry
+            .register_all(vec![make_config_on("c", 9003), make_config_on("d", 9002)])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AetherError::HardValidation(HardValidationError::InvalidPort { port: 9002, .. })
+        ));
+
+        assert_eq!(registry.len(), 2);
+        assert!(!registry.contains(&FunctionId::new("c").unwrap()));
+        assert_eq!(registry.find_by_port(Port::new(9003).unwrap()), None);
+        assert_eq!(
+            registry.find_by_port(Port::new(9002).unwrap()),
+            Some(FunctionId::new("b").unwrap())
+        );
+        // Rolled-back functions were never announced
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let source = FunctionRegistry::new();
+        source.register(make_config_on("b", 9002)).unwrap();
+        source.register(make_config_on("a", 9001)).unwrap();
+        let a = FunctionId::new("a").unwrap();
+        source.transition(&a, FunctionState::Running).unwrap();
+
+        let snapshot = source.snapshot();
+        let ids: Vec<&str> = snapshot
+            .functions
+            .iter()
+            .map(|e| e.config.id.as_str())
+            .collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(snapshot.functions[0].state, FunctionState::Running);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let received: RegistrySnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(received, snapshot);
+
+        let replica = FunctionRegistry::new();
+        let changes = replica.apply_snapshot(received.clone(), false).unwrap();
+        assert_eq!(changes.added.len(), 2);
+        assert_eq!(replica.snapshot(), snapshot);
+        assert_eq!(
+            replica.find_by_port(Port::new(9001).unwrap()),
+            Some(a.clone())
+        );
+
+        // Applying the same snapshot again changes nothing
+        assert!(replica.apply_snapshot(received, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_apply_snapshot_reconciles() {
+        let local = FunctionRegistry::new();
+        local.register(make_config_on("keep", 9001)).unwrap();
+        local.register(make_config_on("stale", 9002)).unwrap();
+
+        let peer = FunctionRegistry::new();
+        let mut changed = make_config_on("keep", 9011);
+        changed.timeout_ms = 5000;
+        peer.register(changed.clone()).unwrap();
+        peer.register(make_config_on("new", 9003)).unwrap();
+        let new = FunctionId::new("new").unwrap();
+        peer.transition(&new, FunctionState::WarmSnapshot).unwrap();
+
+        // Without remove_absent, local-only functions stay
+        let changes = local.apply_snapshot(peer.snapshot(), false).unwrap();
+        assert_eq!(changes.added, vec![new.clone()]);
+        assert_eq!(changes.updated, vec![changed.id.clone()]);
+        assert!(changes.removed.is_empty());
+        assert_eq!(local.get_config(&changed.id).unwrap(), changed);
+        assert_eq!(local.get_state(&new).unwrap(), FunctionState::WarmSnapshot);
+        assert_eq!(local.find_by_port(Port::new(9001).unwrap()), None);
+        assert_eq!(local.len(), 3);
+
+        // A state change alone is an update, applied without a transition check
+        peer.transition(&new, FunctionState::Running).unwrap();
+        peer.transition(&new, FunctionState::Failed).unwrap();
+        let changes = local.apply_snapshot(peer.snapshot(), true).unwrap();
+        assert_eq!(changes.updated, vec![new.clone()]);
+        assert_eq!(changes.removed, vec![FunctionId::new("stale").unwrap()]);
+        assert_eq!(local.get_state(&new).unwrap(), FunctionState::Failed);
+        assert_eq!(local.snapshot(), peer.snapshot());
+    }
+
+    #[test]
+    fn test_snapshot_is_point_in_time() {
+        let registry = Arc::new(FunctionRegistry::new());
+
+        let writers: Vec<_> = (0..4u16)
+            .map(|writer| {
+                let registry = Arc::clone(&registry);
+                std::thread::spawn(move || {
+                    for pair in 0..100u16 {
+                        let port = 10_000 + writer * 1000 + pair * 2;
+                        registry
+                            .register_all(vec![
+                                make_config_on(&format!("w{}-p{}-a", writer, pair), port),
+                                make_config_on(&format!("w{}-p{}-b", writer, pair), port + 1),
+                            ])
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        // Batches are registered in pairs, so a consistent view is always even
+        while !writers.iter().all(|writer| writer.is_finished()) {
+            assert_eq!(registry.snapshot().functions.len() % 2, 0);
+        }
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(registry.snapshot().functions.len(), 800);
+    }
+
     #[test]
     fn test_functions_in_state() {
         let registry = FunctionRegistry::new();
 
-        registry.register(make_config("func1")).unwrap();
-        registry.register(make_config("func2")).unwrap();
-        registry.register(make_config("func3")).unwrap();
+        registry.register(make_config_on("func1", 9001)).unwrap();
+        registry.register(make_config_on("func2", 9002)).unwrap();
+        registry.register(make_config_on("func3", 9003)).unwrap();
 
         let id2 = FunctionId::new("func2").unwrap();
         registry
@@ -242,6 +1059,161 @@ mod tests {
         assert_eq!(warm.len(), 1);
     }
 
+    #[test]
+    fn test_find_by_port() {
+        let registry = FunctionRegistry::new();
+        let id = FunctionId::new("test-func").unwrap();
+        let port = |p| Port::new(p).unwrap();
+
+        registry.register(make_config("test-func")).unwrap();
+        assert_eq!(registry.find_by_port(port(8080)), Some(id.clone()));
+        assert_eq!(registry.find_by_port(port(9090)), None);
+
+        // Changing the trigger port moves the mapping
+        let mut config = make_config("test-func");
+        config.trigger_port = port(9090);
+        registry.update_config(config).unwrap();
+        assert_eq!(registry.find_by_port(port(8080)), None);
+        assert_eq!(registry.find_by_port(port(9090)), Some(id.clone()));
+
+        registry.unregister(&id).unwrap();
+        assert_eq!(registry.find_by_port(port(9090)), None);
+    }
+
+    #[test]
+    fn test_find_by_port_concurrent() {
+        use std::thread;
+
+        let registry = Arc::new(FunctionRegistry::new());
+
+        let handles: Vec<_> = (0..8u16)
+            .map(|i| {
+                let reg = Arc::clone(&registry);
+                thread::spawn(move || {
+                    let name = format!("func-{}", i);
+                    let id = FunctionId::new(&name).unwrap();
+                    for round in 0..50u16 {
+                        let mut config = make_config(&name);
+                        config.trigger_port = Port::new(10_000 + i * 100 + round).unwrap();
+                        let port = config.trigger_port;
+                        if round == 0 {
+                            reg.register(config).unwrap();
+                        } else {
+                            reg.update_config(config).unwrap();
+                        }
+                        assert_eq!(reg.find_by_port(port), Some(id.clone()));
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Only the final port of each function remains mapped
+        for i in 0..8u16 {
+            let id = FunctionId::new(format!("func-{}", i)).unwrap();
+            let final_port = registry.get_config(&id).unwrap().trigger_port;
+            assert_eq!(registry.find_by_port(final_port), Some(id));
+            assert_eq!(
+                registry.find_by_port(Port::new(10_000 + i * 100).unwrap()),
+                None
+            );
+        }
+        assert_eq!(registry.ports.len(), 8);
+    }
+
+    #[test]
+    fn test_change_events() {
+        use tokio::sync::broadcast::error::TryRecvError;
+
+        let registry = FunctionRegistry::new();
+        let id = FunctionId::new("test-func").unwrap();
+
+        // No subscribers: emitting must not fail
+        registry.register(make_config_on("other", 9001)).unwrap();
+
+        let mut rx = registry.subscribe();
+        registry.register(make_config("test-func")).unwrap();
+        registry.transition(&id, FunctionState::Running).unwrap();
+        assert!(registry
+            .transition(&id, FunctionState::WarmSnapshot)
+            .is_ok());
+        assert!(registry.transition(&id, FunctionState::Failed).is_err());
+        registry.update_config(make_config("test-func")).unwrap();
+        registry.unregister(&id).unwrap();
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            RegistryEvent::Registered(id.clone())
+        );
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            RegistryEvent::StateChanged {
+                id: id.clone(),
+                from: FunctionState::Uninitialized,
+                to: FunctionState::Running,
+            }
+        );
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            RegistryEvent::StateChanged {
+                to: FunctionState::WarmSnapshot,
+                ..
+            }
+        ));
+        // Rejected transitions emit nothing
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            RegistryEvent::ConfigUpdated(id.clone())
+        );
+        assert_eq!(rx.try_recv().unwrap(), RegistryEvent::Unregistered(id));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn test_persistence_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("registry.json");
+
+        let registry = FunctionRegistry::new();
+        registry.register(make_config_on("func1", 9001)).unwrap();
+        registry.register(make_config_on("func2", 9002)).unwrap();
+        let id1 = FunctionId::new("func1").unwrap();
+        registry.transition(&id1, FunctionState::Running).unwrap();
+        registry.save_to(&path).unwrap();
+
+        let restored = FunctionRegistry::load_from(&path).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.get_state(&id1).unwrap(), FunctionState::Running);
+        assert_eq!(
+            restored
+                .get_state(&FunctionId::new("func2").unwrap())
+                .unwrap(),
+            FunctionState::Uninitialized
+        );
+        assert_eq!(
+            restored.get_config(&id1).unwrap(),
+            registry.get_config(&id1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_persistence_missing_and_corrupt() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("registry.json");
+
+        let registry = FunctionRegistry::load_from(&path).unwrap();
+        assert!(registry.is_empty());
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(
+            FunctionRegistry::load_from(&path),
+            Err(AetherError::ConfigParse { .. })
+        ));
+    }
+
     #[test]
     fn test_concurrent_access() {
         use std::thread;
@@ -252,7 +1224,7 @@ mod tests {
             .map(|i| {
                 let reg = Arc::clone(&registry);
                 thread::spawn(move || {
-                    let config = make_config(&format!("func-{}", i));
+                    let config = make_config_on(&format!("func-{}", i), 9000 + i);
                     reg.register(config).unwrap();
                 })
             })
@@ -264,4 +1236,142 @@ mod tests {
 
         assert_eq!(registry.len(), 10);
     }
+
+    fn endpoint(port: u16, pid: u32) -> Endpoint {
+        Endpoint {
+            port: Port::new(port).unwrap(),
+            pid: ProcessId::new(pid).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_next_endpoint_round_robin() {
+        let registry = FunctionRegistry::new();
+        let config = make_config("replicated");
+        let id = config.id.clone();
+        registry.register(config).unwrap();
+
+        assert_eq!(registry.next_endpoint(&id, |_| true).unwrap(), None);
+
+        let endpoints = [
+            endpoint(8080, 100),
+            endpoint(8081, 101),
+            endpoint(8082, 102),
+        ];
+        for e in endpoints {
+            registry.add_endpoint(&id, e).unwrap();
+        }
+
+        let mut hits = std::collections::HashMap::new();
+        for _ in 0..300 {
+            let picked = registry.next_endpoint(&id, |_| true).unwrap().unwrap();
+            *hits.entry(picked.port).or_insert(0) += 1;
+        }
+        for e in endpoints {
+            assert_eq!(hits[&e.port], 100);
+        }
+
+        // Unavailable endpoints are skipped; none available yields None
+        for _ in 0..10 {
+            let picked = registry
+                .next_endpoint(&id, |e| e.port.value() != 8081)
+                .unwrap()
+                .unwrap();
+            assert_ne!(picked.port.value(), 8081);
+        }
+        assert_eq!(registry.next_endpoint(&id, |_| false).unwrap(), None);
+    }
+
+    #[test]
+    fn test_endpoint_ports() {
+        let registry = FunctionRegistry::new();
+        let config = make_config("replicated");
+        let id = config.id.clone();
+        registry.register(config).unwrap();
+        let mut other = make_config("other");
+        other.trigger_port = Port::new(9090).unwrap();
+        registry.register(other).unwrap();
+
+        // Replica ports route to their function
+        registry.add_endpoint(&id, endpoint(8081, 100)).unwrap();
+        assert_eq!(
+            registry.find_by_port(Port::new(8081).unwrap()),
+            Some(id.clone())
+        );
+
+        // A port bound to another function is rejected
+        assert!(registry.add_endpoint(&id, endpoint(9090, 101)).is_err());
+
+        // Re-adding a port replaces its endpoint
+        registry.add_endpoint(&id, endpoint(8081, 102)).unwrap();
+        assert_eq!(registry.endpoints(&id).unwrap(), vec![endpoint(8081, 102)]);
+
+        // Removing a replica port unbinds it; the trigger port stays bound
+        registry.add_endpoint(&id, endpoint(8080, 103)).unwrap();
+        let port = Port::new(8081).unwrap();
+        assert_eq!(
+            registry.remove_endpoint(&id, port).unwrap(),
+            Some(endpoint(8081, 102))
+        );
+        assert_eq!(registry.find_by_port(port), None);
+        registry
+            .remove_endpoint(&id, Port::new(8080).unwrap())
+            .unwrap();
+        assert_eq!(registry.find_by_port(Port::new(8080).unwrap()), Some(id));
+    }
+
+    #[test]
+    fn test_port_owners() {
+        let registry = FunctionRegistry::new();
+        let (a, b) = (make_config_on("a", 9001), make_config_on("b", 9002));
+        let id = a.id.clone();
+        registry.register(a).unwrap();
+        registry.register(b).unwrap();
+
+        // A taken port is refused instead of being moved to the newcomer
+        assert!(matches!(
+            registry.register(make_config_on("c", 9001)),
+            Err(AetherError::HardValidation(
+                HardValidationError::InvalidPort { .. }
+            ))
+        ));
+        assert!(registry.update_config(make_config_on("a", 9002)).is_err());
+        assert_eq!(
+            registry.find_by_port(Port::new(9001).unwrap()),
+            Some(id.clone())
+        );
+        assert_eq!(registry.len(), 2);
+
+        // Moving the trigger port drops the endpoint on the old one
+        registry.add_endpoint(&id, endpoint(9001, 100)).unwrap();
+        registry.add_endpoint(&id, endpoint(9100, 101)).unwrap();
+        registry.update_config(make_config_on("a", 9003)).unwrap();
+        assert_eq!(registry.endpoints(&id).unwrap(), vec![endpoint(9100, 101)]);
+        assert_eq!(registry.find_by_port(Port::new(9001).unwrap()), None);
+        assert_eq!(registry.find_by_port(Port::new(9003).unwrap()), Some(id));
+    }
+
+    #[test]
+    fn test_last_exit() {
+        let registry = FunctionRegistry::new();
+        let config = make_config("flaky");
+        let id = config.id.clone();
+        registry.register(config).unwrap();
+        assert_eq!(registry.last_exit(&id).unwrap(), None);
+
+        let crash = ExitReason::Signaled {
+            signal: 11,
+            core_dumped: false,
+        };
+        registry.record_exit(&id, crash).unwrap();
+        assert_eq!(registry.last_exit(&id).unwrap(), Some(crash));
+
+        // Not persisted: a snapshot carries no exit reasons
+        let restored = FunctionRegistry::new();
+        restored.apply_snapshot(registry.snapshot(), false).unwrap();
+        assert_eq!(restored.last_exit(&id).unwrap(), None);
+
+        let missing = FunctionId::new("missing").unwrap();
+        assert!(registry.record_exit(&missing, crash).is_err());
+    }
 }
@@ -4,12 +4,14 @@
 //! Shared Memory IPC module.
 //!
 //! Zero-copy inter-process communication using POSIX shared memory.
-//! Provides lock-free ring buffer for high-performance event passing.
+//! Provides lock-free ring buffers for high-performance event passing:
+//! [`RingBuffer`] for a single producer, [`MpscRingBuffer`] for several.
 
 mod region;
 mod ring_buffer;
 mod validator;
+pub(crate) mod xxhash;
 
 pub use region::SharedMemoryRegion;
-pub use ring_buffer::RingBuffer;
-pub use validator::PayloadValidator;
+pub use ring_buffer::{EntryView, MpscRingBuffer, RingBuffer, RingBufferStats, Token, WritePolicy};
+pub use validator::{ChecksumAlgorithm, PayloadType, PayloadValidator};
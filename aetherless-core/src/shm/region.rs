@@ -4,9 +4,14 @@
 //! SharedMemoryRegion - POSIX shared memory wrapper.
 //!
 //! Provides safe abstraction over mmap and shm_open for zero-copy IPC.
+//! Regions can also be backed by an anonymous memfd where `/dev/shm` is
+//! unavailable, or by huge pages on a hugetlbfs mount. Named regions can
+//! be bound to a NUMA node so their pages sit next to the handler using them.
 //! All unsafe operations are encapsulated with bounds checking.
 
 use std::ffi::CString;
+use std::os::fd::RawFd;
+use std::path::PathBuf;
 use std::ptr::NonNull;
 
 use crate::error::SharedMemoryError;
@@ -26,6 +31,34 @@ pub struct SharedMemoryRegion {
     fd: i32,
     /// Whether this instance created the SHM (and should unlink on drop).
     is_owner: bool,
+    /// What the mapping is backed by, which determines how it is unlinked.
+    backing: Backing,
+    /// Page size of the mapping in bytes.
+    page_size: usize,
+    /// Whether the mapping is locked in RAM (and must be unlocked on drop).
+    locked: bool,
+    /// NUMA node the pages are bound to, if any.
+    numa_node: Option<usize>,
+}
+
+/// `mbind` policy restricting allocations to the given nodes.
+const MPOL_BIND: libc::c_int = 2;
+
+/// `mbind` flag moving pages already allocated elsewhere.
+const MPOL_MF_MOVE: libc::c_uint = 1 << 1;
+
+/// Where the kernel lists NUMA nodes.
+const NUMA_NODE_DIR: &str = "/sys/devices/system/node";
+
+/// Backing object of a shared memory region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Backing {
+    /// POSIX shared memory object under `/dev/shm` (unlinked with `shm_unlink`).
+    Shm,
+    /// Anonymous memfd; there is nothing to unlink.
+    Memfd,
+    /// File on a hugetlbfs mount (unlinked with `unlink`).
+    HugeTlb(PathBuf),
 }
 
 // SAFETY: SharedMemoryRegion can be sent between threads as it owns its memory.
@@ -42,6 +75,12 @@ impl SharedMemoryRegion {
     /// Maximum size for a shared memory region (1 GB).
     pub const MAX_SIZE: usize = 1024 * 1024 * 1024;
 
+    /// Huge page size used by [`SharedMemoryRegion::create_hugetlb`] (2 MB).
+    pub const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+    /// Mount point of the hugetlbfs filesystem backing huge-page regions.
+    pub const HUGETLBFS_MOUNT: &'static str = "/dev/hugepages";
+
     /// Create a new shared memory region.
     ///
     /// # Arguments
@@ -51,6 +90,158 @@ impl SharedMemoryRegion {
     /// # Errors
     /// Returns SharedMemoryError if creation or mapping fails.
     pub fn create(name: &str, size: usize) -> Result<Self, SharedMemoryError> {
+        Self::create_exclusive(name, size)?.ok_or_else(|| SharedMemoryError::CreateFailed {
+            name: name.to_string(),
+            reason: "Shared memory already exists".to_string(),
+        })
+    }
+
+    /// Create a new shared memory region whose pages live on NUMA node `node`.
+    ///
+    /// The object's memory policy is set to `MPOL_BIND` for that node, so it
+    /// holds for every process mapping it. If `mbind` is unavailable (a kernel
+    /// without NUMA support, or a seccomp filter) the region keeps the default
+    /// policy and a warning is logged; [`numa_node`](Self::numa_node) then
+    /// returns `None`.
+    ///
+    /// # Errors
+    /// As [`create`](Self::create), and CreateFailed if the kernel lists NUMA
+    /// nodes but not `node`.
+    pub fn create_on_node(name: &str, size: usize, node: usize) -> Result<Self, SharedMemoryError> {
+        let nodes = std::path::Path::new(NUMA_NODE_DIR);
+        if nodes.exists() && !nodes.join(format!("node{}", node)).exists() {
+            return Err(SharedMemoryError::CreateFailed {
+                name: name.to_string(),
+                reason: format!("NUMA node {} does not exist", node),
+            });
+        }
+
+        let mut region = Self::create(name, size)?;
+        match region.bind_to_node(node) {
+            Ok(()) => {
+                region.numa_node = Some(node);
+                tracing::debug!(name = %name, node = node, "Bound shared memory region to NUMA node");
+            }
+            Err(e) => tracing::warn!(
+                name = %name,
+                node = node,
+                error = %e,
+                "mbind unavailable, shared memory region uses the default NUMA policy"
+            ),
+        }
+        Ok(region)
+    }
+
+    /// Apply `MPOL_BIND` for `node` to the whole mapping.
+    fn bind_to_node(&self, node: usize) -> std::io::Result<()> {
+        const BITS: usize = libc::c_ulong::BITS as usize;
+        let mut mask: Vec<libc::c_ulong> = vec![0; node / BITS + 1];
+        mask[node / BITS] |= 1 << (node % BITS);
+        // The kernel reads one bit fewer than maxnode
+        let max_node = (mask.len() * BITS + 1) as libc::c_ulong;
+
+        // SAFETY: ptr and size describe our own mapping; mask outlives the
+        // call and holds max_node - 1 bits
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_mbind,
+                self.ptr.as_ptr() as *mut libc::c_void,
+                self.size as libc::c_ulong,
+                MPOL_BIND,
+                mask.as_ptr(),
+                max_node,
+                MPOL_MF_MOVE,
+            )
+        };
+        if result < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Attach to a named region, creating it if it does not exist yet.
+    ///
+    /// Safe to call concurrently from producer and consumer: exactly one
+    /// caller creates the object (and unlinks it on drop), the others open it.
+    ///
+    /// # Errors
+    /// Returns SharedMemoryError::InvalidBufferState if an existing region's
+    /// size differs from `size`.
+    pub fn open_or_create(name: &str, size: usize) -> Result<Self, SharedMemoryError> {
+        // The object can vanish between a failed create and the open if its
+        // owner drops it; retry a few times before giving up
+        const ATTEMPTS: usize = 10;
+
+        for _ in 0..ATTEMPTS {
+            if let Some(region) = Self::create_exclusive(name, size)? {
+                return Ok(region);
+            }
+
+            match Self::existing_size(name)? {
+                None => continue,
+                Some(existing) if existing != size => {
+                    return Err(SharedMemoryError::InvalidBufferState {
+                        reason: format!(
+                            "Shared memory {} has size {} but {} was requested",
+                            name, existing, size
+                        ),
+                    });
+                }
+                Some(_) => return Self::open(name, size),
+            }
+        }
+
+        Err(SharedMemoryError::CreateFailed {
+            name: name.to_string(),
+            reason: "Shared memory was repeatedly removed while attaching".to_string(),
+        })
+    }
+
+    /// Get the size of an existing named object, or None if it does not exist.
+    ///
+    /// A creator sizes the object just after creating it, so a zero size is
+    /// briefly waited out rather than reported as a mismatch.
+    fn existing_size(name: &str) -> Result<Option<usize>, SharedMemoryError> {
+        let c_name =
+            CString::new(format!("/{}", name)).map_err(|e| SharedMemoryError::CreateFailed {
+                name: name.to_string(),
+                reason: format!("Invalid name: {}", e),
+            })?;
+
+        // SAFETY: c_name is a valid CString
+        let fd = unsafe { libc::shm_open(c_name.as_ptr(), libc::O_RDONLY, 0) };
+        if fd < 0 {
+            let errno = std::io::Error::last_os_error();
+            if errno.raw_os_error() == Some(libc::ENOENT) {
+                return Ok(None);
+            }
+            return Err(SharedMemoryError::CreateFailed {
+                name: name.to_string(),
+                reason: format!("shm_open failed: {}", errno),
+            });
+        }
+
+        let mut size = 0;
+        for _ in 0..100 {
+            // SAFETY: fd is valid and stat is a plain output struct
+            let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+            if unsafe { libc::fstat(fd, &mut stat) } < 0 {
+                break;
+            }
+            size = stat.st_size as usize;
+            if size != 0 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        // SAFETY: fd was opened above
+        unsafe { libc::close(fd) };
+        Ok(Some(size))
+    }
+
+    /// Create a named region with `O_EXCL`, returning None if it already exists.
+    fn create_exclusive(name: &str, size: usize) -> Result<Option<Self>, SharedMemoryError> {
         // Validate size bounds
         if size < Self::MIN_SIZE {
             return Err(SharedMemoryError::CreateFailed {
@@ -91,13 +282,9 @@ impl SharedMemoryRegion {
         };
 
         if fd < 0 {
-            // Try to open existing if EEXIST
             let errno = std::io::Error::last_os_error();
             if errno.raw_os_error() == Some(libc::EEXIST) {
-                return Err(SharedMemoryError::CreateFailed {
-                    name: name.to_string(),
-                    reason: "Shared memory already exists".to_string(),
-                });
+                return Ok(None);
             }
             return Err(SharedMemoryError::CreateFailed {
                 name: name.to_string(),
@@ -140,22 +327,220 @@ impl SharedMemoryRegion {
             });
         }
 
-        // Zero-initialize the memory
-        // SAFETY: ptr is valid, size is the correct length
-        unsafe {
-            std::ptr::write_bytes(ptr as *mut u8, 0, size);
-        }
+        // A freshly created object is zero-filled by ftruncate. Zeroing it again
+        // here would clobber data from a concurrent open_or_create attacher.
 
         let ptr = NonNull::new(ptr as *mut u8).expect("mmap returned null but not MAP_FAILED");
 
         tracing::debug!(name = %name, size = size, "Created shared memory region");
 
+        Ok(Some(Self {
+            name: name.to_string(),
+            ptr,
+            size,
+            fd,
+            is_owner: true,
+            backing: Backing::Shm,
+            page_size: system_page_size(),
+            locked: false,
+            numa_node: None,
+        }))
+    }
+
+    /// Create an anonymous shared memory region backed by `memfd_create`.
+    ///
+    /// For hosts where `shm_open` under `/dev/shm` is unavailable. The
+    /// returned fd stays owned by the region (closed on drop) and can be
+    /// handed to a child process via SCM_RIGHTS while the region is alive.
+    /// It is created close-on-exec; dup it to inherit it across `exec`.
+    ///
+    /// # Errors
+    /// Returns SharedMemoryError if creation or mapping fails.
+    pub fn create_memfd(size: usize) -> Result<(Self, RawFd), SharedMemoryError> {
+        const NAME: &str = "aetherless-memfd";
+
+        if !(Self::MIN_SIZE..=Self::MAX_SIZE).contains(&size) {
+            return Err(SharedMemoryError::CreateFailed {
+                name: NAME.to_string(),
+                reason: format!("Invalid size: {}", size),
+            });
+        }
+
+        let c_name = CString::new(NAME).expect("static name has no NUL bytes");
+
+        // SAFETY: c_name is a valid CString, MFD_CLOEXEC is a valid flag
+        let fd = unsafe { libc::memfd_create(c_name.as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(SharedMemoryError::CreateFailed {
+                name: NAME.to_string(),
+                reason: format!("memfd_create failed: {}", std::io::Error::last_os_error()),
+            });
+        }
+
+        // Set size
+        // SAFETY: fd is a valid file descriptor
+        let result = unsafe { libc::ftruncate(fd, size as libc::off_t) };
+        if result < 0 {
+            let errno = std::io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(SharedMemoryError::CreateFailed {
+                name: NAME.to_string(),
+                reason: format!("ftruncate failed: {}", errno),
+            });
+        }
+
+        // Map the memory
+        // SAFETY: fd is valid, size is validated, offset 0 is valid
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            let errno = std::io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(SharedMemoryError::MapFailed {
+                reason: format!("mmap failed: {}", errno),
+            });
+        }
+
+        // A freshly truncated memfd is already zero-filled
+        let ptr = NonNull::new(ptr as *mut u8).expect("mmap returned null but not MAP_FAILED");
+
+        tracing::debug!(fd = fd, size = size, "Created memfd shared memory region");
+
+        Ok((
+            Self {
+                name: format!("{}-{}", NAME, fd),
+                ptr,
+                size,
+                fd,
+                is_owner: false,
+                backing: Backing::Memfd,
+                page_size: system_page_size(),
+                locked: false,
+                numa_node: None,
+            },
+            fd,
+        ))
+    }
+
+    /// Create a named shared memory region backed by 2 MB huge pages.
+    ///
+    /// The backing file lives on the hugetlbfs mount at [`Self::HUGETLBFS_MOUNT`]
+    /// (`MAP_HUGETLB` is rejected for ordinary `/dev/shm` objects) and `size`
+    /// is rounded up to a multiple of [`Self::HUGE_PAGE_SIZE`]. Huge pages must
+    /// be reserved (`/proc/sys/vm/nr_hugepages`); there is no fallback to
+    /// regular pages.
+    ///
+    /// # Errors
+    /// Returns SharedMemoryError::MapFailed with a hint if no huge pages are
+    /// available, or CreateFailed if hugetlbfs is not mounted.
+    pub fn create_hugetlb(name: &str, size: usize) -> Result<Self, SharedMemoryError> {
+        let size = round_up_to_huge_page(size);
+        if !(Self::MIN_SIZE..=Self::MAX_SIZE).contains(&size) {
+            return Err(SharedMemoryError::CreateFailed {
+                name: name.to_string(),
+                reason: format!("Invalid size: {}", size),
+            });
+        }
+        if name.is_empty() || name.contains('/') {
+            return Err(SharedMemoryError::CreateFailed {
+                name: name.to_string(),
+                reason: "Name must be non-empty and contain no '/'".to_string(),
+            });
+        }
+
+        let path = PathBuf::from(Self::HUGETLBFS_MOUNT).join(name);
+        let c_path = CString::new(path.to_string_lossy().as_bytes()).map_err(|e| {
+            SharedMemoryError::CreateFailed {
+                name: name.to_string(),
+                reason: format!("Invalid name: {}", e),
+            }
+        })?;
+
+        // SAFETY: c_path is a valid CString, flags are valid POSIX flags
+        let fd = unsafe {
+            libc::open(
+                c_path.as_ptr(),
+                libc::O_CREAT | libc::O_RDWR | libc::O_EXCL | libc::O_CLOEXEC,
+                0o600,
+            )
+        };
+        if fd < 0 {
+            return Err(SharedMemoryError::CreateFailed {
+                name: name.to_string(),
+                reason: format!(
+                    "open {} failed (is hugetlbfs mounted at {}?): {}",
+                    path.display(),
+                    Self::HUGETLBFS_MOUNT,
+                    std::io::Error::last_os_error()
+                ),
+            });
+        }
+
+        // Set size
+        // SAFETY: fd is a valid file descriptor
+        let result = unsafe { libc::ftruncate(fd, size as libc::off_t) };
+        if result < 0 {
+            let errno = std::io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            unsafe { libc::unlink(c_path.as_ptr()) };
+            return Err(SharedMemoryError::CreateFailed {
+                name: name.to_string(),
+                reason: format!("ftruncate failed: {}", errno),
+            });
+        }
+
+        // Map the memory
+        // SAFETY: fd is valid, size is validated and huge-page aligned
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_HUGETLB,
+                fd,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            let errno = std::io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            unsafe { libc::unlink(c_path.as_ptr()) };
+            let hint = match errno.raw_os_error() {
+                Some(libc::ENOMEM) | Some(libc::EINVAL) => {
+                    " (no huge pages reserved? raise /proc/sys/vm/nr_hugepages)"
+                }
+                _ => "",
+            };
+            return Err(SharedMemoryError::MapFailed {
+                reason: format!("huge page mmap of {} bytes failed: {}{}", size, errno, hint),
+            });
+        }
+
+        // hugetlbfs pages are zero-filled on first fault
+        let ptr = NonNull::new(ptr as *mut u8).expect("mmap returned null but not MAP_FAILED");
+
+        tracing::debug!(name = %name, size = size, "Created huge page shared memory region");
+
         Ok(Self {
             name: name.to_string(),
             ptr,
             size,
             fd,
             is_owner: true,
+            backing: Backing::HugeTlb(path),
+            page_size: Self::HUGE_PAGE_SIZE,
+            locked: false,
+            numa_node: None,
         })
     }
 
@@ -217,6 +602,10 @@ impl SharedMemoryRegion {
             size,
             fd,
             is_owner: false,
+            backing: Backing::Shm,
+            page_size: system_page_size(),
+            locked: false,
+            numa_node: None,
         })
     }
 
@@ -230,6 +619,67 @@ impl SharedMemoryRegion {
         self.size
     }
 
+    /// Get the page size of the mapping in bytes.
+    ///
+    /// [`Self::HUGE_PAGE_SIZE`] for huge-page regions, otherwise the system page size.
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Lock the mapping in RAM so it is never paged out to swap.
+    ///
+    /// Requires `CAP_IPC_LOCK` or an `RLIMIT_MEMLOCK` (`ulimit -l`) of at
+    /// least the region size. The lock is released when the region is dropped.
+    /// Locking an already locked region is a no-op.
+    ///
+    /// # Errors
+    /// Returns SharedMemoryError::LockFailed, including the current limit.
+    pub fn lock(&mut self) -> Result<(), SharedMemoryError> {
+        if self.locked {
+            return Ok(());
+        }
+
+        // SAFETY: ptr and size describe our own mapping
+        let result = unsafe { libc::mlock(self.ptr.as_ptr() as *const libc::c_void, self.size) };
+        if result < 0 {
+            let errno = std::io::Error::last_os_error();
+
+            let mut limit = libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            };
+            // SAFETY: limit is a valid output struct
+            unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut limit) };
+
+            let hint = match errno.raw_os_error() {
+                Some(libc::ENOMEM) | Some(libc::EPERM) => {
+                    " (raise RLIMIT_MEMLOCK or grant CAP_IPC_LOCK)"
+                }
+                _ => "",
+            };
+            return Err(SharedMemoryError::LockFailed {
+                size: self.size,
+                limit_bytes: limit.rlim_cur,
+                reason: format!("mlock failed: {}{}", errno, hint),
+            });
+        }
+
+        self.locked = true;
+        tracing::debug!(name = %self.name, size = self.size, "Locked shared memory region");
+        Ok(())
+    }
+
+    /// Check whether the mapping is locked in RAM.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// NUMA node the region was bound to by
+    /// [`create_on_node`](Self::create_on_node), if binding succeeded.
+    pub fn numa_node(&self) -> Option<usize> {
+        self.numa_node
+    }
+
     /// Get a raw pointer to the shared memory.
     ///
     /// # Safety
@@ -257,6 +707,12 @@ impl SharedMemoryRegion {
 
 impl Drop for SharedMemoryRegion {
     fn drop(&mut self) {
+        // Release the RAM lock before unmapping
+        if self.locked {
+            // SAFETY: ptr and size were locked in lock()
+            unsafe { libc::munlock(self.ptr.as_ptr() as *const libc::c_void, self.size) };
+        }
+
         // Unmap the memory
         // SAFETY: ptr and size were set during creation
         let result = unsafe { libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, self.size) };
@@ -274,16 +730,41 @@ impl Drop for SharedMemoryRegion {
 
         // If we're the owner, unlink the shared memory
         if self.is_owner {
-            let shm_name = format!("/{}", self.name);
-            if let Ok(c_name) = CString::new(shm_name.as_str()) {
-                // SAFETY: c_name is a valid CString
-                unsafe { libc::shm_unlink(c_name.as_ptr()) };
-                tracing::debug!(name = %self.name, "Unlinked shared memory region");
+            match &self.backing {
+                Backing::Shm => {
+                    let shm_name = format!("/{}", self.name);
+                    if let Ok(c_name) = CString::new(shm_name.as_str()) {
+                        // SAFETY: c_name is a valid CString
+                        unsafe { libc::shm_unlink(c_name.as_ptr()) };
+                        tracing::debug!(name = %self.name, "Unlinked shared memory region");
+                    }
+                }
+                Backing::HugeTlb(path) => {
+                    let _ = std::fs::remove_file(path);
+                    tracing::debug!(name = %self.name, "Unlinked huge page shared memory region");
+                }
+                Backing::Memfd => {}
             }
         }
     }
 }
 
+/// Get the system's base page size.
+fn system_page_size() -> usize {
+    // SAFETY: sysconf has no preconditions
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if size > 0 {
+        size as usize
+    } else {
+        4096
+    }
+}
+
+/// Round `size` up to a whole number of huge pages.
+fn round_up_to_huge_page(size: usize) -> usize {
+    size.div_ceil(SharedMemoryRegion::HUGE_PAGE_SIZE) * SharedMemoryRegion::HUGE_PAGE_SIZE
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,6 +780,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_memfd_region() {
+        use crate::shm::RingBuffer;
+
+        assert!(SharedMemoryRegion::create_memfd(100).is_err());
+
+        let (region, fd) = SharedMemoryRegion::create_memfd(64 * 1024).unwrap();
+        assert!(fd >= 0);
+        assert_eq!(region.size(), 64 * 1024);
+
+        let buffer = RingBuffer::new(region).unwrap();
+        buffer.write(b"via memfd").unwrap();
+        assert_eq!(buffer.read().unwrap(), b"via memfd");
+    }
+
+    #[test]
+    fn test_hugetlb_region() {
+        let huge = SharedMemoryRegion::HUGE_PAGE_SIZE;
+        assert_eq!(round_up_to_huge_page(1), huge);
+        assert_eq!(round_up_to_huge_page(huge), huge);
+        assert_eq!(round_up_to_huge_page(huge + 1), 2 * huge);
+
+        let name = format!("test_hugetlb_{}", std::process::id());
+        match SharedMemoryRegion::create_hugetlb(&name, 64 * 1024) {
+            Ok(region) => {
+                assert_eq!(region.size(), huge);
+                assert_eq!(region.page_size(), huge);
+            }
+            // Hosts without reserved huge pages must fail loudly, not fall back
+            Err(SharedMemoryError::CreateFailed { reason, .. }) => {
+                assert!(reason.contains("hugetlbfs"))
+            }
+            Err(SharedMemoryError::MapFailed { reason }) => assert!(reason.contains("huge page")),
+            Err(other) => panic!("unexpected error: {other}"),
+        }
+
+        let (region, _) = SharedMemoryRegion::create_memfd(64 * 1024).unwrap();
+        assert!(region.page_size() >= 4096);
+        assert!(region.page_size() < huge);
+    }
+
+    #[test]
+    fn test_open_or_create_single_owner() {
+        use std::sync::{Arc, Barrier};
+
+        let name = format!("test_open_or_create_{}", std::process::id());
+        let barrier = Arc::new(Barrier::new(2));
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let name = name.clone();
+                let barrier = Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    SharedMemoryRegion::open_or_create(&name, 64 * 1024).unwrap()
+                })
+            })
+            .collect();
+        let regions: Vec<SharedMemoryRegion> =
+            handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        assert_eq!(regions.iter().filter(|r| r.is_owner).count(), 1);
+
+        // Both mappings see the same memory
+        unsafe { *regions[0].as_ptr() = 42 };
+        assert_eq!(unsafe { *regions[1].as_ptr() }, 42);
+
+        // A size mismatch against the existing object is rejected
+        assert!(matches!(
+            SharedMemoryRegion::open_or_create(&name, 128 * 1024),
+            Err(SharedMemoryError::InvalidBufferState { .. })
+        ));
+    }
+
+    #[test]
+    fn test_lock_region() {
+        let (mut region, _) = SharedMemoryRegion::create_memfd(64 * 1024).unwrap();
+        assert!(!region.is_locked());
+
+        match region.lock() {
+            Ok(()) => {
+                assert!(region.is_locked());
+                // Idempotent
+                assert!(region.lock().is_ok());
+            }
+            // Environments with a tiny RLIMIT_MEMLOCK must report the limit
+            Err(SharedMemoryError::LockFailed { size, reason, .. }) => {
+                assert_eq!(size, 64 * 1024);
+                assert!(reason.contains("mlock"));
+                assert!(!region.is_locked());
+            }
+            Err(other) => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_create_on_node() {
+        let name = format!("test-numa-{}", std::process::id());
+        let mut region = SharedMemoryRegion::create_on_node(&name, 64 * 1024, 0).unwrap();
+        // Node 0 exists wherever NUMA does; elsewhere binding falls back
+        assert!(matches!(region.numa_node(), Some(0) | None));
+        // SAFETY: this test owns the region exclusively
+        unsafe { region.as_mut_slice().fill(0xab) };
+        assert_eq!(unsafe { region.as_slice() }[1234], 0xab);
+
+        if std::path::Path::new(NUMA_NODE_DIR).exists() {
+            let missing = format!("test-numa-missing-{}", std::process::id());
+            assert!(matches!(
+                SharedMemoryRegion::create_on_node(&missing, 64 * 1024, 4095),
+                Err(SharedMemoryError::CreateFailed { reason, .. }) if reason.contains("4095")
+            ));
+        }
+    }
+
     #[test]
     fn test_shm_empty_name() {
         assert!(SharedMemoryRegion::create("", 4096).is_err());
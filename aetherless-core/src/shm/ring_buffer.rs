@@ -5,18 +5,50 @@
 //!
 //! Uses atomic head/tail pointers for wait-free single-producer single-consumer
 //! communication between the Orchestrator and Function processes.
+//!
+//! Consumers that would otherwise spin can park in [`RingBuffer::read_timeout`],
+//! which waits on a futex over the head word and is woken by the producer.
+//!
+//! The header carries a magic/version word and the payload checksum
+//! algorithm, so a consumer opening the buffer in another process can detect
+//! a mismatched layout or algorithm before reading any entries.
+//!
+//! [`MpscRingBuffer`] shares the layout and lets several producers write to
+//! one consumer, at the cost of a CAS per write.
+//!
+//! A single producer can instead use [`WritePolicy::OverwriteOldest`] to drop
+//! the oldest entries when full, for data where freshness beats completeness.
+//!
+//! Usage counters live in the header too, so either side can report
+//! [`RingBufferStats`] for the buffer as a whole.
+//!
+//! Consumers that must not lose an entry if they crash mid-processing can
+//! read in two phases with [`RingBuffer::peek`] and [`RingBuffer::commit`],
+//! for at-least-once delivery.
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use crate::error::SharedMemoryError;
-use crate::shm::SharedMemoryRegion;
+use crate::shm::{ChecksumAlgorithm, PayloadValidator, SharedMemoryRegion};
+
+/// Header size in bytes (head + tail + capacity + waiters + format + reserve +
+/// dropped + four usage counters, all u64).
+const HEADER_SIZE: usize = 88;
 
-/// Header size in bytes (head + tail + capacity as u64).
-const HEADER_SIZE: usize = 24;
+/// Magic tag identifying an Aetherless ring buffer ("AETH").
+const RING_MAGIC: u32 = 0x4145_5448;
+
+/// Layout version, bumped whenever the header or entry format changes.
+const RING_VERSION: u16 = 5;
 
 /// Alignment for entries (8 bytes).
 const ENTRY_ALIGNMENT: usize = 8;
 
+/// Spins an MPSC producer makes waiting for its turn to publish before it
+/// starts yielding the CPU.
+const PUBLISH_SPIN_LIMIT: u32 = 128;
+
 /// Ring buffer header stored at the start of shared memory.
 #[repr(C)]
 struct RingBufferHeader {
@@ -26,6 +58,81 @@ struct RingBufferHeader {
     tail: AtomicU64,
     /// Total capacity in bytes (excluding header).
     capacity: AtomicU64,
+    /// Number of consumers parked (or about to park) in `read_timeout`.
+    waiters: AtomicU64,
+    /// Magic (high 32 bits), version (next 16) and checksum algorithm (low 16).
+    format: AtomicU64,
+    /// End of the space claimed by producers (MPSC only). Runs ahead of
+    /// `head` while writes are in flight.
+    reserve: AtomicU64,
+    /// Entries discarded by an `OverwriteOldest` producer.
+    dropped: AtomicU64,
+    /// Most bytes ever published and unread at once.
+    high_watermark: AtomicU64,
+    /// Entries published.
+    total_writes: AtomicU64,
+    /// Entries consumed.
+    total_reads: AtomicU64,
+    /// Writes that failed with `RingBufferFull`.
+    full_rejections: AtomicU64,
+}
+
+/// Usage of a ring buffer since it was created, from [`RingBuffer::stats`].
+///
+/// The counters are updated with relaxed atomics and read one at a time, so
+/// a snapshot taken under load may be slightly inconsistent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RingBufferStats {
+    /// Data area size in bytes.
+    pub capacity: usize,
+    /// Bytes currently occupied by unread (or reserved) entries.
+    pub used: usize,
+    /// Most bytes ever occupied by unread entries.
+    pub high_watermark: usize,
+    /// Entries written.
+    pub total_writes: u64,
+    /// Entries read.
+    pub total_reads: u64,
+    /// Writes rejected because the buffer was full.
+    pub full_rejections: u64,
+}
+
+impl RingBufferStats {
+    /// Fraction of the capacity currently in use, from 0.0 to 1.0.
+    pub fn utilization(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.used as f64 / self.capacity as f64
+    }
+}
+
+/// Pack the header format word.
+fn encode_format(algorithm: ChecksumAlgorithm) -> u64 {
+    (u64::from(RING_MAGIC) << 32) | (u64::from(RING_VERSION) << 16) | algorithm as u64
+}
+
+/// Unpack the header format word, validating magic and version.
+fn decode_format(word: u64) -> Result<ChecksumAlgorithm, SharedMemoryError> {
+    let magic = (word >> 32) as u32;
+    let version = (word >> 16) as u16;
+
+    if magic != RING_MAGIC {
+        return Err(SharedMemoryError::InvalidBufferState {
+            reason: format!("Bad ring buffer magic: {:#010x}", magic),
+        });
+    }
+
+    if version != RING_VERSION {
+        return Err(SharedMemoryError::InvalidBufferState {
+            reason: format!(
+                "Unsupported ring buffer version: {} (expected {})",
+                version, RING_VERSION
+            ),
+        });
+    }
+
+    ChecksumAlgorithm::try_from(word as u16)
 }
 
 /// Entry header for each message in the buffer.
@@ -34,12 +141,51 @@ struct RingBufferHeader {
 struct EntryHeader {
     /// Length of the payload in bytes.
     length: u32,
-    /// CRC32 checksum of the payload.
+    /// Checksum of the payload (algorithm recorded in the buffer header).
     checksum: u32,
 }
 
 const ENTRY_HEADER_SIZE: usize = std::mem::size_of::<EntryHeader>();
 
+impl EntryHeader {
+    /// Encode the header as it is laid out in the buffer.
+    fn to_bytes(self) -> [u8; ENTRY_HEADER_SIZE] {
+        let mut bytes = [0u8; ENTRY_HEADER_SIZE];
+        bytes[..4].copy_from_slice(&self.length.to_ne_bytes());
+        bytes[4..].copy_from_slice(&self.checksum.to_ne_bytes());
+        bytes
+    }
+
+    /// Decode a header previously written with `to_bytes`.
+    fn from_bytes(bytes: [u8; ENTRY_HEADER_SIZE]) -> Self {
+        Self {
+            length: u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            checksum: u32::from_ne_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        }
+    }
+}
+
+/// What a write does when the buffer lacks space for the entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Fail with `RingBufferFull`, leaving the buffer untouched.
+    #[default]
+    FailWhenFull,
+    /// Discard the oldest unread entries until the new one fits, counting
+    /// them in [`RingBuffer::dropped_count`].
+    ///
+    /// The producer advances `tail` past the discarded entries itself, racing
+    /// the consumer for it, so this mode is single-producer only: it is not
+    /// available on [`MpscRingBuffer`], and a region must not have two
+    /// `RingBuffer` writers. A consumer that falls behind may then read an
+    /// entry while the producer overwrites it, so reads treat the data area
+    /// as racy: every header is bounded by the capacity before anything is
+    /// allocated or copied, bytes are copied with volatile loads, and the
+    /// entry is claimed with a CAS on `tail` only afterwards. A copy the
+    /// producer dropped meanwhile is discarded and the read retried.
+    OverwriteOldest,
+}
+
 /// Lock-free ring buffer for zero-copy IPC.
 ///
 /// Single-producer, single-consumer (SPSC) design using atomic operations
@@ -47,11 +193,27 @@ const ENTRY_HEADER_SIZE: usize = std::mem::size_of::<EntryHeader>();
 pub struct RingBuffer {
     /// Underlying shared memory region.
     region: SharedMemoryRegion,
+    /// Payload checksum algorithm, shared with the other side via the header.
+    checksum: ChecksumAlgorithm,
+    /// Behaviour of this side's writes when the buffer is full.
+    policy: WritePolicy,
+    /// Copy of the last peeked payload, borrowed by its [`EntryView`].
+    peeked: Vec<u8>,
 }
 
 impl RingBuffer {
     /// Create a new ring buffer in the given shared memory region.
+    ///
+    /// Payloads are protected with CRC32.
     pub fn new(region: SharedMemoryRegion) -> Result<Self, SharedMemoryError> {
+        Self::with_checksum(region, ChecksumAlgorithm::default())
+    }
+
+    /// Create a new ring buffer using the given payload checksum algorithm.
+    pub fn with_checksum(
+        region: SharedMemoryRegion,
+        checksum: ChecksumAlgorithm,
+    ) -> Result<Self, SharedMemoryError> {
         let size = region.size();
 
         if size < HEADER_SIZE + 64 {
@@ -60,7 +222,12 @@ impl RingBuffer {
             });
         }
 
-        let buffer = Self { region };
+        let buffer = Self {
+            region,
+            checksum,
+            policy: WritePolicy::default(),
+            peeked: Vec::new(),
+        };
 
         // Initialize the header
         // SAFETY: We just created the region and have exclusive access
@@ -71,12 +238,25 @@ impl RingBuffer {
             (*header)
                 .capacity
                 .store((size - HEADER_SIZE) as u64, Ordering::Release);
+            (*header).waiters.store(0, Ordering::Release);
+            (*header)
+                .format
+                .store(encode_format(checksum), Ordering::Release);
+            (*header).reserve.store(0, Ordering::Release);
+            (*header).dropped.store(0, Ordering::Release);
+            (*header).high_watermark.store(0, Ordering::Release);
+            (*header).total_writes.store(0, Ordering::Release);
+            (*header).total_reads.store(0, Ordering::Release);
+            (*header).full_rejections.store(0, Ordering::Release);
         }
 
         Ok(buffer)
     }
 
     /// Open an existing ring buffer from shared memory.
+    ///
+    /// Adopts the checksum algorithm recorded by the creator. Fails with
+    /// `InvalidBufferState` if the header magic or version does not match.
     pub fn open(region: SharedMemoryRegion) -> Result<Self, SharedMemoryError> {
         let size = region.size();
 
@@ -86,7 +266,91 @@ impl RingBuffer {
             });
         }
 
-        Ok(Self { region })
+        // SAFETY: The region is at least HEADER_SIZE bytes
+        let word = unsafe {
+            (*(region.as_ptr() as *const RingBufferHeader))
+                .format
+                .load(Ordering::Acquire)
+        };
+        let checksum = decode_format(word)?;
+
+        Ok(Self {
+            region,
+            checksum,
+            policy: WritePolicy::default(),
+            peeked: Vec::new(),
+        })
+    }
+
+    /// Open an existing ring buffer, requiring a specific checksum algorithm.
+    ///
+    /// Fails with `InvalidBufferState` if the creator chose a different one.
+    pub fn open_with_checksum(
+        region: SharedMemoryRegion,
+        expected: ChecksumAlgorithm,
+    ) -> Result<Self, SharedMemoryError> {
+        let buffer = Self::open(region)?;
+
+        if buffer.checksum != expected {
+            return Err(SharedMemoryError::InvalidBufferState {
+                reason: format!(
+                    "Checksum algorithm mismatch: buffer uses {:?}, expected {:?}",
+                    buffer.checksum, expected
+                ),
+            });
+        }
+
+        Ok(buffer)
+    }
+
+    /// Payload checksum algorithm used by this buffer.
+    pub fn checksum_algorithm(&self) -> ChecksumAlgorithm {
+        self.checksum
+    }
+
+    /// Set what this side's writes do when the buffer is full.
+    ///
+    /// The policy is local to this handle; the consumer needs none.
+    pub fn with_write_policy(mut self, policy: WritePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Behaviour of this side's writes when the buffer is full.
+    pub fn write_policy(&self) -> WritePolicy {
+        self.policy
+    }
+
+    /// Number of entries discarded by an `OverwriteOldest` producer since
+    /// the buffer was created.
+    pub fn dropped_count(&self) -> u64 {
+        // SAFETY: header is always valid
+        unsafe { (*self.header()).dropped.load(Ordering::Relaxed) }
+    }
+
+    /// Usage counters for the buffer, shared by both sides.
+    pub fn stats(&self) -> RingBufferStats {
+        // SAFETY: header is always valid
+        let header = unsafe { &*self.header() };
+        RingBufferStats {
+            capacity: self.capacity(),
+            used: self.readable_bytes(),
+            high_watermark: header.high_watermark.load(Ordering::Relaxed) as usize,
+            total_writes: header.total_writes.load(Ordering::Relaxed),
+            total_reads: header.total_reads.load(Ordering::Relaxed),
+            full_rejections: header.full_rejections.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Count a rejected write and build its error.
+    fn reject_full(&self, size: usize) -> SharedMemoryError {
+        // SAFETY: header is always valid
+        unsafe {
+            (*self.header())
+                .full_rejections
+                .fetch_add(1, Ordering::Relaxed)
+        };
+        SharedMemoryError::RingBufferFull { size }
     }
 
     /// Get pointer to the header.
@@ -123,6 +387,125 @@ impl RingBuffer {
         unsafe { (*self.header()).tail.load(Ordering::Acquire) }
     }
 
+    /// Move the read position from `current` past an entry of `entry_size`
+    /// bytes. Fails if the other side moved it first.
+    fn advance_tail(&self, current: u64, entry_size: usize) -> bool {
+        // SAFETY: header is always valid
+        unsafe {
+            (*self.header())
+                .tail
+                .compare_exchange(
+                    current,
+                    current + entry_size as u64,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .is_ok()
+        }
+    }
+
+    /// Get the MPSC reservation cursor.
+    fn reserve_cursor(&self) -> &AtomicU64 {
+        // SAFETY: header is always valid
+        unsafe { &(*self.header()).reserve }
+    }
+
+    /// Copy `src` into the data area starting at `offset`, wrapping at capacity.
+    ///
+    /// # Safety
+    /// `offset` must be below capacity and `src` must fit in the free space.
+    unsafe fn copy_in(&self, offset: usize, src: &[u8]) {
+        let data = self.data_ptr();
+        let first_chunk = std::cmp::min(src.len(), self.capacity() - offset);
+        std::ptr::copy_nonoverlapping(src.as_ptr(), data.add(offset), first_chunk);
+        if first_chunk < src.len() {
+            // Wrap around to beginning
+            std::ptr::copy_nonoverlapping(
+                src.as_ptr().add(first_chunk),
+                data,
+                src.len() - first_chunk,
+            );
+        }
+    }
+
+    /// Copy `len` bytes out of the data area starting at `offset` into `dst`,
+    /// wrapping at capacity.
+    ///
+    /// The bytes may be overwritten concurrently by an `OverwriteOldest`
+    /// producer, so they are read with volatile loads; callers validate the
+    /// copy afterwards.
+    ///
+    /// # Safety
+    /// `offset` must be below capacity, `len` must not exceed capacity, and
+    /// `dst` must be valid for `len` bytes of writes.
+    unsafe fn copy_out(&self, offset: usize, dst: *mut u8, len: usize) {
+        let data = self.data_ptr();
+        let first_chunk = std::cmp::min(len, self.capacity() - offset);
+        volatile_copy(data.add(offset), dst, first_chunk);
+        if first_chunk < len {
+            // Wrap around
+            volatile_copy(data, dst.add(first_chunk), len - first_chunk);
+        }
+    }
+
+    /// Get the futex word for the head pointer: its low 32 bits.
+    ///
+    /// Futexes operate on 32-bit words, so waiters compare against the low
+    /// half of `head`. A waiter can only miss an update if the producer
+    /// advances head by an exact multiple of 2^32 bytes while it is parked,
+    /// in which case it sleeps until its timeout.
+    fn head_futex_word(&self) -> *const u32 {
+        // SAFETY: header is always valid and head is 8-byte aligned
+        unsafe {
+            let head = std::ptr::addr_of!((*self.header()).head) as *const u32;
+            if cfg!(target_endian = "big") {
+                head.add(1)
+            } else {
+                head
+            }
+        }
+    }
+
+    /// Wake a consumer parked in `read_timeout`.
+    fn wake_reader(&self) {
+        // SAFETY: the futex word lives in the mapped header. FUTEX_WAKE without
+        // FUTEX_PRIVATE_FLAG because the mapping is shared between processes.
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                self.head_futex_word(),
+                libc::FUTEX_WAKE,
+                1,
+                std::ptr::null::<libc::timespec>(),
+                std::ptr::null::<u32>(),
+                0,
+            );
+        }
+    }
+
+    /// Park until the head word no longer equals `observed` or `timeout` passes.
+    fn wait_for_head(&self, observed: u32, timeout: Duration) {
+        let ts = libc::timespec {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_nsec: timeout.subsec_nanos() as libc::c_long,
+        };
+        // SAFETY: the futex word lives in the mapped header. The kernel
+        // re-checks it against `observed` atomically before sleeping, so an
+        // update between our load and this call returns EAGAIN immediately.
+        // EINTR, EAGAIN and ETIMEDOUT are all handled by the caller's loop.
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                self.head_futex_word(),
+                libc::FUTEX_WAIT,
+                observed,
+                &ts as *const libc::timespec,
+                std::ptr::null::<u32>(),
+                0,
+            );
+        }
+    }
+
     /// Calculate available space for writing.
     pub fn available_space(&self) -> usize {
         let head = self.head();
@@ -150,53 +533,176 @@ impl RingBuffer {
         // Calculate total entry size (header + payload, aligned)
         let entry_size = Self::align_up(ENTRY_HEADER_SIZE + payload_len, ENTRY_ALIGNMENT);
 
-        if entry_size > self.available_space() {
-            return Err(SharedMemoryError::RingBufferFull { size: payload_len });
+        if !self.make_room(entry_size) {
+            return Err(self.reject_full(payload_len));
+        }
+
+        let head = self.head();
+
+        // SAFETY: We've verified there's enough space
+        unsafe { self.write_entry_at(head, payload) };
+        self.publish(head, head + entry_size as u64, 1);
+
+        Ok(())
+    }
+
+    /// Write as many payloads as fit, publishing them with a single head update.
+    ///
+    /// Each payload becomes an ordinary entry with its own header and checksum,
+    /// so `read` consumes them one by one. Returns the number written, which
+    /// may be fewer than `payloads.len()`; SharedMemoryError::RingBufferFull
+    /// if not even the first one fits.
+    ///
+    /// With `OverwriteOldest`, older entries are dropped to fit as many
+    /// payloads as an empty buffer would hold.
+    pub fn write_batch(&self, payloads: &[&[u8]]) -> Result<usize, SharedMemoryError> {
+        if self.policy == WritePolicy::OverwriteOldest {
+            let mut needed = 0;
+            for payload in payloads {
+                let entry_size = Self::align_up(ENTRY_HEADER_SIZE + payload.len(), ENTRY_ALIGNMENT);
+                if needed + entry_size > self.capacity() {
+                    break;
+                }
+                needed += entry_size;
+            }
+            self.make_room(needed);
+        }
+
+        let head = self.head();
+        let mut available = self.available_space();
+        let mut position = head;
+        let mut written = 0;
+
+        for payload in payloads {
+            let entry_size = Self::align_up(ENTRY_HEADER_SIZE + payload.len(), ENTRY_ALIGNMENT);
+            if entry_size > available {
+                break;
+            }
+
+            // SAFETY: We've verified there's enough space for this entry
+            unsafe { self.write_entry_at(position, payload) };
+            position += entry_size as u64;
+            available -= entry_size;
+            written += 1;
+        }
+
+        if written == 0 {
+            return match payloads.first() {
+                Some(first) => Err(self.reject_full(first.len())),
+                None => Ok(0),
+            };
         }
 
-        // Calculate checksum
-        let checksum = crc32fast::hash(payload);
+        self.publish(head, position, written as u64);
+        Ok(written)
+    }
+
+    /// Ensure `entry_size` bytes are free, dropping the oldest entries if the
+    /// write policy allows it. Returns whether the space is available.
+    fn make_room(&self, entry_size: usize) -> bool {
+        while entry_size > self.available_space() {
+            if self.policy == WritePolicy::FailWhenFull || entry_size > self.capacity() {
+                return false;
+            }
+
+            // Entries between tail and head are complete: this is their only
+            // producer. A missing header or failed CAS means the consumer read
+            // the entry first, freeing space anyway.
+            let tail = self.tail();
+            let Some(header) = self.header_at(tail) else {
+                continue;
+            };
+            let dropped =
+                Self::align_up(ENTRY_HEADER_SIZE + header.length as usize, ENTRY_ALIGNMENT);
+            if self.advance_tail(tail, dropped) {
+                // SAFETY: header is always valid
+                unsafe { (*self.header()).dropped.fetch_add(1, Ordering::Relaxed) };
+            }
+        }
+        true
+    }
 
+    /// Copy an entry (header + payload) into the data area at stream position
+    /// `position` without publishing it.
+    ///
+    /// # Safety
+    /// The caller must have verified the aligned entry fits in the free space
+    /// starting at `position`.
+    unsafe fn write_entry_at(&self, position: u64, payload: &[u8]) {
         let entry_header = EntryHeader {
-            length: payload_len as u32,
-            checksum,
+            length: payload.len() as u32,
+            checksum: self.checksum.checksum(payload),
         };
 
         let capacity = self.capacity();
-        let head = self.head();
-        let offset = (head as usize) % capacity;
+        let offset = (position as usize) % capacity;
 
-        // SAFETY: We've verified there's enough space
+        // Both the entry header and the payload may straddle the end of the
+        // data area and wrap
+        self.copy_in(offset, &entry_header.to_bytes());
+        self.copy_in((offset + ENTRY_HEADER_SIZE) % capacity, payload);
+    }
+
+    /// Make the `entries` written between `old_head` and `new_head` visible
+    /// to the consumer, waking it if it is parked.
+    fn publish(&self, old_head: u64, new_head: u64, entries: u64) {
+        // Update head with release ordering
+        // SAFETY: header is always valid
         unsafe {
-            let data = self.data_ptr();
-
-            // Write entry header
-            let header_dest = data.add(offset) as *mut EntryHeader;
-            std::ptr::write_unaligned(header_dest, entry_header);
-
-            // Write payload
-            let payload_dest = data.add(offset + ENTRY_HEADER_SIZE);
-
-            // Handle wraparound
-            let first_chunk = std::cmp::min(payload_len, capacity - offset - ENTRY_HEADER_SIZE);
-            std::ptr::copy_nonoverlapping(payload.as_ptr(), payload_dest, first_chunk);
-
-            if first_chunk < payload_len {
-                // Wrap around to beginning
-                std::ptr::copy_nonoverlapping(
-                    payload.as_ptr().add(first_chunk),
-                    data,
-                    payload_len - first_chunk,
-                );
-            }
+            (*self.header_mut()).head.store(new_head, Ordering::Release);
+        }
 
-            // Update head with release ordering
-            (*self.header_mut())
-                .head
-                .store(head + entry_size as u64, Ordering::Release);
+        // Wake a parked reader only if the buffer was empty before this entry
+        // and a reader has announced itself, so the common case makes no syscall.
+        // The SeqCst fence orders the head store before the waiters/tail loads;
+        // it pairs with the fence in read_timeout so that either we see the
+        // reader's announcement and wake it, or it sees our new head and never parks.
+        fence(Ordering::SeqCst);
+        // SAFETY: header is always valid
+        let header = unsafe { &*self.header() };
+        let waiters = header.waiters.load(Ordering::Relaxed);
+        let tail = self.tail();
+        if waiters > 0 && tail == old_head {
+            self.wake_reader();
         }
 
-        Ok(())
+        header.total_writes.fetch_add(entries, Ordering::Relaxed);
+        header
+            .high_watermark
+            .fetch_max(new_head.saturating_sub(tail), Ordering::Relaxed);
+    }
+
+    /// Read a payload, blocking for up to `timeout` while the buffer is empty.
+    ///
+    /// Parks on a futex over the head word instead of spinning. Returns
+    /// SharedMemoryError::RingBufferEmpty if nothing arrives in time.
+    pub fn read_timeout(&self, timeout: Duration) -> Result<Vec<u8>, SharedMemoryError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match self.read() {
+                Err(SharedMemoryError::RingBufferEmpty) => {}
+                result => return result,
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(SharedMemoryError::RingBufferEmpty);
+            }
+
+            // Announce ourselves before re-checking head. Pairs with the fence
+            // in write: our tail store (from the last read) and the waiters
+            // increment are ordered before this head load.
+            // SAFETY: header is always valid
+            let waiters = unsafe { &(*self.header()).waiters };
+            waiters.fetch_add(1, Ordering::Relaxed);
+            fence(Ordering::SeqCst);
+            let head = self.head();
+            if head == self.tail() {
+                self.wait_for_head(head as u32, remaining);
+            }
+            waiters.fetch_sub(1, Ordering::Relaxed);
+        }
     }
 
     /// Read a payload from the buffer.
@@ -204,68 +710,192 @@ impl RingBuffer {
     /// Returns the payload bytes and validates the checksum.
     /// Returns SharedMemoryError::RingBufferEmpty if no data available.
     pub fn read(&self) -> Result<Vec<u8>, SharedMemoryError> {
-        if self.readable_bytes() < ENTRY_HEADER_SIZE {
-            return Err(SharedMemoryError::RingBufferEmpty);
+        let mut payload = Vec::new();
+        self.read_into(&mut payload)?;
+        Ok(payload)
+    }
+
+    /// Read a payload into a caller-owned buffer, reusing its allocation.
+    ///
+    /// `buf` is cleared first and holds exactly the payload on success; the
+    /// payload length is returned. The checksum is validated as in `read`, and
+    /// on any error the entry is not consumed.
+    pub fn read_into(&self, buf: &mut Vec<u8>) -> Result<usize, SharedMemoryError> {
+        loop {
+            let tail = self.tail();
+            match self.read_entry_at(tail, buf) {
+                // Claim the entry only now: an OverwriteOldest producer may
+                // have dropped and reused it while it was being copied
+                Ok(entry_size) if self.advance_tail(tail, entry_size) => {
+                    // SAFETY: header is always valid
+                    unsafe { (*self.header()).total_reads.fetch_add(1, Ordering::Relaxed) };
+                    return Ok(buf.len());
+                }
+                Ok(_) => {}
+                Err(SharedMemoryError::RingBufferEmpty) => {
+                    return Err(SharedMemoryError::RingBufferEmpty)
+                }
+                // Torn by a concurrent drop rather than corrupt
+                Err(_) if self.tail() != tail => {}
+                Err(e) => return Err(e),
+            }
+            buf.clear();
         }
+    }
 
+    /// Copy the entry at stream position `tail` into `buf` and validate it,
+    /// without consuming it. Returns the aligned entry size.
+    fn read_entry_at(&self, tail: u64, buf: &mut Vec<u8>) -> Result<usize, SharedMemoryError> {
         let capacity = self.capacity();
-        let tail = self.tail();
         let offset = (tail as usize) % capacity;
 
-        // SAFETY: We've verified there's data to read
-        unsafe {
-            let data = self.data_ptr();
+        let entry_header = self
+            .header_at(tail)
+            .ok_or(SharedMemoryError::RingBufferEmpty)?;
+        let payload_len = entry_header.length as usize;
+        let expected_checksum = entry_header.checksum;
 
-            // Read entry header
-            let header_src = data.add(offset) as *const EntryHeader;
-            let entry_header: EntryHeader = std::ptr::read_unaligned(header_src);
+        // A consumer lapped by an OverwriteOldest producer reads a stale tail,
+        // whose "header" may be payload bytes. Never trust it past capacity.
+        if self.head().saturating_sub(tail) > capacity as u64
+            || ENTRY_HEADER_SIZE + payload_len > capacity
+        {
+            return Err(SharedMemoryError::InvalidBufferState {
+                reason: format!(
+                    "Torn entry header at position {}: length {} exceeds capacity {}",
+                    tail, payload_len, capacity
+                ),
+            });
+        }
 
-            let payload_len = entry_header.length as usize;
-            let expected_checksum = entry_header.checksum;
+        // Calculate entry size
+        let entry_size = Self::align_up(ENTRY_HEADER_SIZE + payload_len, ENTRY_ALIGNMENT);
 
-            // Calculate entry size
-            let entry_size = Self::align_up(ENTRY_HEADER_SIZE + payload_len, ENTRY_ALIGNMENT);
+        // Validate we have enough data
+        if (self.head() - tail) < entry_size as u64 {
+            return Err(SharedMemoryError::InvalidBufferState {
+                reason: "Incomplete entry in buffer".to_string(),
+            });
+        }
 
-            // Validate we have enough data
-            if self.readable_bytes() < entry_size {
-                return Err(SharedMemoryError::InvalidBufferState {
-                    reason: "Incomplete entry in buffer".to_string(),
-                });
-            }
+        buf.clear();
+        buf.reserve(payload_len);
 
-            // Read payload
-            let mut payload = vec![0u8; payload_len];
-            let payload_src = data.add(offset + ENTRY_HEADER_SIZE);
-
-            // Handle wraparound
-            let first_chunk = std::cmp::min(payload_len, capacity - offset - ENTRY_HEADER_SIZE);
-            std::ptr::copy_nonoverlapping(payload_src, payload.as_mut_ptr(), first_chunk);
-
-            if first_chunk < payload_len {
-                // Wrap around
-                std::ptr::copy_nonoverlapping(
-                    data,
-                    payload.as_mut_ptr().add(first_chunk),
-                    payload_len - first_chunk,
-                );
+        // SAFETY: We've verified the whole entry is readable, and `buf` has
+        // room for payload_len bytes which copy_out fully initializes
+        unsafe {
+            // Read payload (may wrap)
+            self.copy_out(
+                (offset + ENTRY_HEADER_SIZE) % capacity,
+                buf.as_mut_ptr(),
+                payload_len,
+            );
+            buf.set_len(payload_len);
+        }
+
+        // Validate checksum - FAIL IMMEDIATELY on mismatch (no fallback)
+        if let Err(e) =
+            PayloadValidator::validate_checksum_with(buf, expected_checksum, self.checksum)
+        {
+            buf.clear();
+            return Err(e);
+        }
+
+        Ok(entry_size)
+    }
+
+    /// Read the next entry without consuming it.
+    ///
+    /// The entry stays in the buffer until its [`Token`] is passed to
+    /// [`commit`](Self::commit); if the token is dropped instead, for example
+    /// because processing failed or the consumer crashed, the next `peek` or
+    /// `read` returns the same entry again. The checksum is validated as in
+    /// `read`.
+    ///
+    /// The view borrows this handle mutably, so the borrow checker rules out
+    /// a second `peek` (or a `commit`) while it is alive. The payload is
+    /// copied into a buffer owned by the handle and reused on every peek:
+    /// a view directly into shared memory could be overwritten under it by an
+    /// `OverwriteOldest` producer.
+    ///
+    /// This relies on the single-consumer assumption: a tail held back across
+    /// peek and commit is only safe if no other handle reads the same region.
+    pub fn peek(&mut self) -> Result<(EntryView<'_>, Token), SharedMemoryError> {
+        let mut peeked = std::mem::take(&mut self.peeked);
+        let result = loop {
+            let tail = self.tail();
+            match self.read_entry_at(tail, &mut peeked) {
+                // Discard a copy the producer dropped while it was being made
+                Ok(_) if self.tail() != tail => {}
+                Ok(entry_size) => break Ok(Token { tail, entry_size }),
+                Err(SharedMemoryError::RingBufferEmpty) => {
+                    break Err(SharedMemoryError::RingBufferEmpty)
+                }
+                Err(_) if self.tail() != tail => {}
+                Err(e) => break Err(e),
             }
+        };
+        self.peeked = peeked;
+
+        let token = result?;
+        Ok((
+            EntryView {
+                payload: &self.peeked,
+            },
+            token,
+        ))
+    }
+
+    /// Consume the entry returned by the [`peek`](Self::peek) that issued
+    /// `token`.
+    ///
+    /// Fails with `InvalidBufferState` if the entry is no longer the oldest:
+    /// the token is stale (the entry was already committed or read), or an
+    /// `OverwriteOldest` producer dropped it in the meantime.
+    pub fn commit(&self, token: Token) -> Result<(), SharedMemoryError> {
+        if !self.advance_tail(token.tail, token.entry_size) {
+            return Err(SharedMemoryError::InvalidBufferState {
+                reason: format!(
+                    "Cannot commit entry at position {}: it was already consumed or dropped",
+                    token.tail
+                ),
+            });
+        }
+        // SAFETY: header is always valid
+        unsafe { (*self.header()).total_reads.fetch_add(1, Ordering::Relaxed) };
+        Ok(())
+    }
 
-            // Validate checksum - FAIL IMMEDIATELY on mismatch (no fallback)
-            let actual_checksum = crc32fast::hash(&payload);
-            if actual_checksum != expected_checksum {
-                return Err(SharedMemoryError::ChecksumMismatch {
-                    expected: expected_checksum,
-                    actual: actual_checksum,
-                });
+    /// Get the payload length of the next entry without consuming it.
+    ///
+    /// Returns None if the buffer is empty.
+    pub fn peek_len(&self) -> Option<usize> {
+        loop {
+            let tail = self.tail();
+            let header = self.header_at(tail);
+            // Discard a header torn by a concurrent drop
+            if self.tail() == tail {
+                return header.map(|header| header.length as usize);
             }
+        }
+    }
 
-            // Update tail with release ordering
-            (*self.header_mut())
-                .tail
-                .store(tail + entry_size as u64, Ordering::Release);
+    /// Read the header of the entry at stream position `tail`.
+    ///
+    /// Returns None if no entry header is readable there.
+    fn header_at(&self, tail: u64) -> Option<EntryHeader> {
+        if self.head().saturating_sub(tail) < ENTRY_HEADER_SIZE as u64 {
+            return None;
+        }
 
-            Ok(payload)
+        let offset = (tail as usize) % self.capacity();
+        let mut header_bytes = [0u8; ENTRY_HEADER_SIZE];
+        // SAFETY: We've verified at least a header's worth of data is readable
+        unsafe {
+            // Read entry header (may wrap)
+            self.copy_out(offset, header_bytes.as_mut_ptr(), ENTRY_HEADER_SIZE);
         }
+        Some(EntryHeader::from_bytes(header_bytes))
     }
 
     /// Check if the buffer is empty.
@@ -279,13 +909,721 @@ impl RingBuffer {
     }
 }
 
+/// Copy `len` bytes from shared memory that another process may be writing.
+///
+/// Uses volatile loads, word-sized where `src` is aligned, so the compiler
+/// cannot assume the bytes are stable.
+///
+/// # Safety
+/// `src` must be valid for `len` bytes of reads and `dst` for `len` bytes of
+/// writes, and the two must not overlap.
+unsafe fn volatile_copy(src: *const u8, dst: *mut u8, len: usize) {
+    const WORD: usize = std::mem::size_of::<u64>();
+    let mut copied = 0;
+    if (src as usize).is_multiple_of(WORD) {
+        while copied + WORD <= len {
+            let word = (src.add(copied) as *const u64).read_volatile();
+            (dst.add(copied) as *mut u64).write_unaligned(word);
+            copied += WORD;
+        }
+    }
+    while copied < len {
+        *dst.add(copied) = src.add(copied).read_volatile();
+        copied += 1;
+    }
+}
+
+/// An entry read by [`RingBuffer::peek`] but not yet committed.
+#[derive(Debug)]
+pub struct EntryView<'a> {
+    payload: &'a [u8],
+}
+
+impl EntryView<'_> {
+    /// The entry's payload, checksum already validated.
+    pub fn payload(&self) -> &[u8] {
+        self.payload
+    }
+}
+
+impl std::ops::Deref for EntryView<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.payload
+    }
+}
+
+/// Proof of a peeked entry, redeemed with [`RingBuffer::commit`] to consume
+/// it. Dropping the token leaves the entry in the buffer.
+#[derive(Debug)]
+#[must_use = "dropping a Token leaves the entry unconsumed"]
+pub struct Token {
+    /// Stream position of the entry.
+    tail: u64,
+    /// Aligned size of the entry.
+    entry_size: usize,
+}
+
+/// Multi-producer, single-consumer ring buffer.
+///
+/// Same layout and entry format as [`RingBuffer`], so reading is unchanged.
+/// A producer claims space by advancing the header's reservation cursor with
+/// a CAS loop, copies its entry into the claimed range, then publishes it by
+/// moving `head` over it. Entries are published in reservation order: a
+/// producer whose range starts at `r` waits until `head == r`.
+///
+/// # Linearization point
+///
+/// A write takes effect at its successful compare-exchange on the
+/// reservation cursor. That fixes its position in the stream relative to all
+/// other writes; a producer that loses the race retries from the new cursor.
+/// The consumer observes writes in exactly that order, each one once `head`
+/// has been stored past it.
+///
+/// A producer preempted between reserving and publishing holds back the
+/// entries reserved after it; their producers spin, then yield, until it
+/// catches up. With only one producer use [`RingBuffer`], whose write needs
+/// no CAS and never waits.
+///
+/// Every producer of a region must go through `MpscRingBuffer`:
+/// [`RingBuffer::write`] does not advance the reservation cursor.
+pub struct MpscRingBuffer {
+    inner: RingBuffer,
+}
+
+impl MpscRingBuffer {
+    /// Create a new multi-producer ring buffer in the given shared memory
+    /// region. Payloads are protected with CRC32.
+    pub fn new(region: SharedMemoryRegion) -> Result<Self, SharedMemoryError> {
+        Self::with_checksum(region, ChecksumAlgorithm::default())
+    }
+
+    /// Create a new multi-producer ring buffer using the given payload
+    /// checksum algorithm.
+    pub fn with_checksum(
+        region: SharedMemoryRegion,
+        checksum: ChecksumAlgorithm,
+    ) -> Result<Self, SharedMemoryError> {
+        RingBuffer::with_checksum(region, checksum).map(|inner| Self { inner })
+    }
+
+    /// Open an existing ring buffer from shared memory, as with
+    /// [`RingBuffer::open`].
+    pub fn open(region: SharedMemoryRegion) -> Result<Self, SharedMemoryError> {
+        let inner = RingBuffer::open(region)?;
+        // A buffer last written through RingBuffer has its cursor behind head
+        inner
+            .reserve_cursor()
+            .fetch_max(inner.head(), Ordering::AcqRel);
+        Ok(Self { inner })
+    }
+
+    /// Payload checksum algorithm used by this buffer.
+    pub fn checksum_algorithm(&self) -> ChecksumAlgorithm {
+        self.inner.checksum_algorithm()
+    }
+
+    /// Get the capacity of the data area.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Calculate available space for writing, counting space reserved by
+    /// in-flight writes as used.
+    pub fn available_space(&self) -> usize {
+        let reserved = self.inner.reserve_cursor().load(Ordering::Acquire);
+        let used = reserved.saturating_sub(self.inner.tail());
+        self.capacity().saturating_sub(used as usize)
+    }
+
+    /// Calculate amount of published data ready to read.
+    pub fn readable_bytes(&self) -> usize {
+        self.inner.readable_bytes()
+    }
+
+    /// Usage counters for the buffer; `used` includes reserved space.
+    pub fn stats(&self) -> RingBufferStats {
+        RingBufferStats {
+            used: self.capacity() - self.available_space(),
+            ..self.inner.stats()
+        }
+    }
+
+    /// Write a payload to the buffer. Safe to call from several threads or
+    /// processes at once.
+    ///
+    /// Returns SharedMemoryError::RingBufferFull if there isn't enough space.
+    pub fn write(&self, payload: &[u8]) -> Result<(), SharedMemoryError> {
+        let entry_size =
+            RingBuffer::align_up(ENTRY_HEADER_SIZE + payload.len(), ENTRY_ALIGNMENT) as u64;
+        let capacity = self.capacity() as u64;
+        let cursor = self.inner.reserve_cursor();
+
+        let mut start = cursor.load(Ordering::Acquire);
+        loop {
+            // Tail is loaded after `start`, so it can pass a stale `start`;
+            // the CAS below then fails and the check is redone
+            let used = start.saturating_sub(self.inner.tail());
+            if entry_size > capacity - used.min(capacity) {
+                return Err(self.inner.reject_full(payload.len()));
+            }
+
+            // Linearization point
+            match cursor.compare_exchange_weak(
+                start,
+                start + entry_size,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break,
+                Err(current) => start = current,
+            }
+        }
+
+        // SAFETY: [start, start + entry_size) is claimed by this producer
+        // alone and was free when claimed
+        unsafe { self.inner.write_entry_at(start, payload) };
+
+        // Wait for earlier reservations to be published. The acquire load of
+        // head and the release store in publish chain their entries to ours.
+        let mut spins = 0;
+        while self.inner.head() != start {
+            if spins < PUBLISH_SPIN_LIMIT {
+                spins += 1;
+                std::hint::spin_loop();
+            } else {
+                std::thread::yield_now();
+            }
+        }
+        self.inner.publish(start, start + entry_size, 1);
+
+        Ok(())
+    }
+
+    /// Read a payload from the buffer; see [`RingBuffer::read`].
+    pub fn read(&self) -> Result<Vec<u8>, SharedMemoryError> {
+        self.inner.read()
+    }
+
+    /// Read a payload into a caller-owned buffer; see [`RingBuffer::read_into`].
+    pub fn read_into(&self, buf: &mut Vec<u8>) -> Result<usize, SharedMemoryError> {
+        self.inner.read_into(buf)
+    }
+
+    /// Read a payload, blocking for up to `timeout` while the buffer is
+    /// empty; see [`RingBuffer::read_timeout`].
+    pub fn read_timeout(&self, timeout: Duration) -> Result<Vec<u8>, SharedMemoryError> {
+        self.inner.read_timeout(timeout)
+    }
+
+    /// Read the next entry without consuming it; see [`RingBuffer::peek`].
+    pub fn peek(&mut self) -> Result<(EntryView<'_>, Token), SharedMemoryError> {
+        self.inner.peek()
+    }
+
+    /// Consume a peeked entry; see [`RingBuffer::commit`].
+    pub fn commit(&self, token: Token) -> Result<(), SharedMemoryError> {
+        self.inner.commit(token)
+    }
+
+    /// Get the payload length of the next entry without consuming it.
+    pub fn peek_len(&self) -> Option<usize> {
+        self.inner.peek_len()
+    }
+
+    /// Check if the buffer has no published entries.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shm::SharedMemoryRegion;
 
     // Note: These tests require actual shared memory which may not work in all environments
     // In a real test environment, we'd mock the shared memory region
 
+    /// Repeatedly advance the write offset to within one entry header of the
+    /// end of the data area, then round-trip a payload across the wrap.
+    /// Returns the distinct gaps (bytes left before the end) that were hit.
+    fn exercise_wraparound(region_size: usize) -> std::collections::BTreeSet<usize> {
+        let name = format!("test_ring_wrap_{}_{}", std::process::id(), region_size);
+        let region = SharedMemoryRegion::create(&name, region_size).unwrap();
+        let buffer = RingBuffer::new(region).unwrap();
+        let capacity = buffer.capacity();
+
+        let mut gaps = std::collections::BTreeSet::new();
+        for lap in 0..16 {
+            // Header-only entries advance the offset one alignment step at a time
+            while capacity - (buffer.head() as usize) % capacity > ENTRY_HEADER_SIZE {
+                buffer.write(&[]).unwrap();
+                assert!(buffer.read().unwrap().is_empty());
+            }
+            gaps.insert(capacity - (buffer.head() as usize) % capacity);
+
+            let payload: Vec<u8> = (0..20u8).map(|b| b.wrapping_mul(lap + 1)).collect();
+            buffer.write(&payload).unwrap();
+            assert_eq!(buffer.read().unwrap(), payload);
+            assert!(buffer.is_empty());
+        }
+
+        gaps
+    }
+
+    #[test]
+    fn test_wraparound_aligned_capacity() {
+        // Capacity is a multiple of the entry alignment: headers land exactly
+        // at the end of the data area and payloads wrap in full
+        let gaps = exercise_wraparound(4096 + HEADER_SIZE);
+        assert_eq!(
+            gaps.into_iter().collect::<Vec<_>>(),
+            vec![ENTRY_HEADER_SIZE]
+        );
+    }
+
+    #[test]
+    fn test_wraparound_unaligned_capacity() {
+        // Capacity is not a multiple of the entry alignment: headers straddle
+        // the end of the data area
+        let gaps = exercise_wraparound(4096 + HEADER_SIZE + 5);
+        assert!(gaps.iter().any(|&gap| gap < ENTRY_HEADER_SIZE));
+    }
+
+    #[test]
+    fn test_read_timeout() {
+        let name = format!("test_ring_timeout_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 64 * 1024).unwrap();
+        let buffer = std::sync::Arc::new(RingBuffer::new(region).unwrap());
+
+        // Empty buffer times out
+        let start = Instant::now();
+        assert!(matches!(
+            buffer.read_timeout(Duration::from_millis(50)),
+            Err(SharedMemoryError::RingBufferEmpty)
+        ));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // A parked reader is woken by the producer
+        let producer = {
+            let buffer = std::sync::Arc::clone(&buffer);
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                buffer.write(b"wake up").unwrap();
+            })
+        };
+        let start = Instant::now();
+        let payload = buffer.read_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(payload, b"wake up");
+        assert!(start.elapsed() < Duration::from_secs(5));
+        producer.join().unwrap();
+
+        // Data already present is returned without waiting
+        buffer.write(b"ready").unwrap();
+        assert_eq!(buffer.read_timeout(Duration::ZERO).unwrap(), b"ready");
+    }
+
+    #[test]
+    fn test_lapped_reader_rejects_torn_header() {
+        let name = format!("test_ring_lapped_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096).unwrap();
+        let buffer = RingBuffer::new(region)
+            .unwrap()
+            .with_write_policy(WritePolicy::OverwriteOldest);
+        let capacity = buffer.capacity();
+
+        // A consumer stalls holding tail 0, then the producer drops the entry
+        // there and writes one whose payload covers offset 0 with 0xFF bytes,
+        // which read as a ~4GiB length header
+        buffer.write(b"x").unwrap();
+        let stale_tail = buffer.tail();
+        let payload = vec![0xFFu8; capacity - 2 * ENTRY_HEADER_SIZE];
+        buffer.write(&payload).unwrap();
+        assert_eq!(buffer.dropped_count(), 1);
+        assert_eq!(
+            buffer.header_at(stale_tail).unwrap().length,
+            u32::MAX,
+            "stale tail should now point into the payload"
+        );
+
+        let mut buf = Vec::new();
+        assert!(matches!(
+            buffer.read_entry_at(stale_tail, &mut buf),
+            Err(SharedMemoryError::InvalidBufferState { .. })
+        ));
+        assert!(buf.capacity() < capacity);
+
+        // A reader starting from the real tail gets the surviving entry
+        assert_eq!(buffer.read().unwrap(), payload);
+    }
+
+    #[test]
+    fn test_peek_commit() {
+        let name = format!("test_ring_peek_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096).unwrap();
+        let mut buffer = RingBuffer::new(region).unwrap();
+
+        assert!(matches!(
+            buffer.peek(),
+            Err(SharedMemoryError::RingBufferEmpty)
+        ));
+
+        buffer.write(b"first").unwrap();
+        buffer.write(b"second").unwrap();
+
+        // A dropped token leaves the entry in place
+        {
+            let (view, _token) = buffer.peek().unwrap();
+            assert_eq!(view.payload(), b"first");
+        }
+        let (view, stale) = buffer.peek().unwrap();
+        assert_eq!(&*view, b"first");
+        let (_, token) = buffer.peek().unwrap();
+        buffer.commit(token).unwrap();
+        assert_eq!(buffer.stats().total_reads, 1);
+
+        // The first token no longer names the oldest entry
+        assert!(matches!(
+            buffer.commit(stale),
+            Err(SharedMemoryError::InvalidBufferState { .. })
+        ));
+
+        let (view, token) = buffer.peek().unwrap();
+        assert_eq!(view.payload(), b"second");
+        buffer.commit(token).unwrap();
+        assert!(buffer.is_empty());
+
+        // Entries wrapping the end of the data area are viewed whole
+        let payload: Vec<u8> = (0..200u8).collect();
+        for _ in 0..64 {
+            buffer.write(&payload).unwrap();
+            let (view, token) = buffer.peek().unwrap();
+            assert_eq!(view.payload(), payload.as_slice());
+            buffer.commit(token).unwrap();
+        }
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_read_into_reuses_buffer() {
+        let name = format!("test_ring_read_into_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 64 * 1024).unwrap();
+        let buffer = RingBuffer::new(region).unwrap();
+
+        assert_eq!(buffer.peek_len(), None);
+        let mut buf = Vec::with_capacity(128);
+        assert!(matches!(
+            buffer.read_into(&mut buf),
+            Err(SharedMemoryError::RingBufferEmpty)
+        ));
+
+        buffer.write(&[7u8; 100]).unwrap();
+        buffer.write(b"short").unwrap();
+        assert_eq!(buffer.peek_len(), Some(100));
+        // Peeking does not consume
+        assert_eq!(buffer.peek_len(), Some(100));
+
+        let ptr = buf.as_ptr();
+        assert_eq!(buffer.read_into(&mut buf).unwrap(), 100);
+        assert_eq!(buf, vec![7u8; 100]);
+
+        // Previous contents are replaced, and the allocation is reused
+        assert_eq!(buffer.read_into(&mut buf).unwrap(), 5);
+        assert_eq!(buf, b"short");
+        assert_eq!(buf.as_ptr(), ptr);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_write_batch() {
+        let name = format!("test_ring_batch_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096 + HEADER_SIZE).unwrap();
+        let buffer = RingBuffer::new(region).unwrap();
+
+        assert_eq!(buffer.write_batch(&[]).unwrap(), 0);
+
+        // Interleave batches with single reads across several wraps
+        let mut expected = std::collections::VecDeque::new();
+        for round in 0..200u32 {
+            let payloads: Vec<Vec<u8>> = (0..(round % 5 + 1))
+                .map(|i| format!("r{}-e{}", round, i).into_bytes())
+                .collect();
+            let refs: Vec<&[u8]> = payloads.iter().map(|p| p.as_slice()).collect();
+            let written = buffer.write_batch(&refs).unwrap();
+            assert_eq!(written, refs.len());
+            expected.extend(payloads);
+
+            // Drain only part of what was written so entries accumulate
+            for _ in 0..(round % 3 + 1) {
+                match expected.pop_front() {
+                    Some(payload) => assert_eq!(buffer.read().unwrap(), payload),
+                    None => break,
+                }
+            }
+        }
+        while let Some(payload) = expected.pop_front() {
+            assert_eq!(buffer.read().unwrap(), payload);
+        }
+        assert!(buffer.is_empty());
+
+        // A partial batch writes only what fits
+        let big = vec![1u8; 1500];
+        let batch: Vec<&[u8]> = vec![&big, &big, &big];
+        assert_eq!(buffer.write_batch(&batch).unwrap(), 2);
+        assert!(matches!(
+            buffer.write_batch(&batch),
+            Err(SharedMemoryError::RingBufferFull { size: 1500 })
+        ));
+        assert_eq!(buffer.read().unwrap(), big);
+        assert_eq!(buffer.read().unwrap(), big);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_checksum_algorithm_in_header() {
+        let name = format!("test_ring_checksum_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096).unwrap();
+        let writer = RingBuffer::with_checksum(region, ChecksumAlgorithm::XxHash64).unwrap();
+        writer.write(b"hashed with xxh64").unwrap();
+
+        // A reader adopts the algorithm stored in the header
+        let reader = RingBuffer::open(SharedMemoryRegion::open(&name, 4096).unwrap()).unwrap();
+        assert_eq!(reader.checksum_algorithm(), ChecksumAlgorithm::XxHash64);
+        assert_eq!(reader.read().unwrap(), b"hashed with xxh64");
+
+        // Insisting on a different algorithm is rejected
+        let result = RingBuffer::open_with_checksum(
+            SharedMemoryRegion::open(&name, 4096).unwrap(),
+            ChecksumAlgorithm::Crc32,
+        );
+        assert!(matches!(
+            result,
+            Err(SharedMemoryError::InvalidBufferState { .. })
+        ));
+    }
+
+    #[test]
+    fn test_open_rejects_bad_magic() {
+        let name = format!("test_ring_magic_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096).unwrap();
+
+        // Fresh regions are zeroed and carry no magic
+        let result = RingBuffer::open(SharedMemoryRegion::open(&name, 4096).unwrap());
+        assert!(matches!(
+            result,
+            Err(SharedMemoryError::InvalidBufferState { .. })
+        ));
+        drop(region);
+    }
+
+    #[test]
+    fn test_overwrite_oldest() {
+        let name = format!("test_ring_overwrite_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096 + HEADER_SIZE).unwrap();
+        let buffer = RingBuffer::new(region)
+            .unwrap()
+            .with_write_policy(WritePolicy::OverwriteOldest);
+        assert_eq!(buffer.write_policy(), WritePolicy::OverwriteOldest);
+
+        // 16-byte entries: 256 fit in 4096 bytes
+        for i in 0..300u64 {
+            buffer.write(&i.to_le_bytes()).unwrap();
+        }
+        assert_eq!(buffer.dropped_count(), 44);
+
+        // Only the newest entries remain, in order
+        for i in 44..300u64 {
+            assert_eq!(buffer.read().unwrap(), i.to_le_bytes());
+        }
+        assert!(buffer.is_empty());
+
+        // A batch drops only what it needs
+        for i in 0..250u64 {
+            buffer.write(&i.to_le_bytes()).unwrap();
+        }
+        let payloads: Vec<[u8; 8]> = (250..260u64).map(u64::to_le_bytes).collect();
+        let refs: Vec<&[u8]> = payloads.iter().map(|p| p.as_slice()).collect();
+        assert_eq!(buffer.write_batch(&refs).unwrap(), 10);
+        assert_eq!(buffer.dropped_count(), 48);
+        assert_eq!(buffer.read().unwrap(), 4u64.to_le_bytes());
+
+        // Entries larger than the whole buffer still fail
+        assert!(matches!(
+            buffer.write(&[0u8; 8192]),
+            Err(SharedMemoryError::RingBufferFull { size: 8192 })
+        ));
+
+        // The default policy never drops
+        let region =
+            SharedMemoryRegion::create(&format!("{}_default", name), 4096 + HEADER_SIZE).unwrap();
+        let strict = RingBuffer::new(region).unwrap();
+        while strict.write(&[1u8; 8]).is_ok() {}
+        assert_eq!(strict.dropped_count(), 0);
+    }
+
+    #[test]
+    fn test_overwrite_oldest_concurrent_reader() {
+        const MESSAGES: u64 = 50_000;
+
+        let name = format!("test_ring_overwrite_race_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096 + HEADER_SIZE).unwrap();
+        let buffer = std::sync::Arc::new(
+            RingBuffer::new(region)
+                .unwrap()
+                .with_write_policy(WritePolicy::OverwriteOldest),
+        );
+
+        let producer = {
+            let buffer = std::sync::Arc::clone(&buffer);
+            std::thread::spawn(move || {
+                for seq in 0..MESSAGES {
+                    let mut payload = seq.to_le_bytes().to_vec();
+                    payload.resize(8 + (seq % 29) as usize, seq as u8);
+                    buffer.write(&payload).unwrap();
+                }
+            })
+        };
+
+        // The reader never sees a corrupt entry, and never one twice or out of order
+        let mut received = 0u64;
+        let mut last = None;
+        loop {
+            match buffer.read_timeout(Duration::from_millis(200)) {
+                Ok(payload) => {
+                    let seq = u64::from_le_bytes(payload[..8].try_into().unwrap());
+                    assert!(last.is_none_or(|last| seq > last));
+                    assert_eq!(payload.len(), 8 + (seq % 29) as usize);
+                    last = Some(seq);
+                    received += 1;
+                }
+                Err(SharedMemoryError::RingBufferEmpty) if producer.is_finished() => break,
+                Err(SharedMemoryError::RingBufferEmpty) => {}
+                Err(e) => panic!("read failed: {}", e),
+            }
+        }
+        producer.join().unwrap();
+
+        assert_eq!(last, Some(MESSAGES - 1));
+        assert_eq!(received + buffer.dropped_count(), MESSAGES);
+    }
+
+    #[test]
+    fn test_stats() {
+        let name = format!("test_ring_stats_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096 + HEADER_SIZE).unwrap();
+        let buffer = RingBuffer::new(region).unwrap();
+        assert_eq!(
+            buffer.stats(),
+            RingBufferStats {
+                capacity: 4096,
+                ..Default::default()
+            }
+        );
+
+        // Fill the buffer with 16-byte entries, then overflow it once
+        while buffer.write(&[0u8; 8]).is_ok() {}
+        buffer.write_batch(&[&[1u8; 8][..]]).unwrap_err();
+        let full = buffer.stats();
+        assert_eq!(full.used, 4096);
+        assert_eq!(full.high_watermark, 4096);
+        assert_eq!(full.total_writes, 256);
+        assert_eq!(full.full_rejections, 2);
+        assert_eq!(full.utilization(), 1.0);
+
+        // Reads free space; the high watermark stays
+        for _ in 0..192 {
+            buffer.read().unwrap();
+        }
+        let stats = buffer.stats();
+        assert_eq!(stats.used, 1024);
+        assert_eq!(stats.high_watermark, 4096);
+        assert_eq!(stats.total_reads, 192);
+        assert_eq!(stats.utilization(), 0.25);
+
+        // Counters are in shared memory, visible to the other side
+        let reader =
+            RingBuffer::open(SharedMemoryRegion::open(&name, 4096 + HEADER_SIZE).unwrap()).unwrap();
+        assert_eq!(reader.stats(), stats);
+    }
+
+    #[test]
+    fn test_mpsc_stress() {
+        const PRODUCERS: u64 = 4;
+        const MESSAGES: u64 = 20_000;
+
+        let name = format!("test_ring_mpsc_{}", std::process::id());
+        // Small enough that producers keep running into a full buffer
+        let region = SharedMemoryRegion::create(&name, 4096 + HEADER_SIZE).unwrap();
+        let buffer = std::sync::Arc::new(MpscRingBuffer::new(region).unwrap());
+
+        let producers: Vec<_> = (0..PRODUCERS)
+            .map(|producer| {
+                let buffer = std::sync::Arc::clone(&buffer);
+                std::thread::spawn(move || {
+                    for seq in 0..MESSAGES {
+                        // Varying lengths move entry boundaries across the wrap
+                        let mut payload = Vec::with_capacity(32);
+                        payload.extend_from_slice(&producer.to_le_bytes());
+                        payload.extend_from_slice(&seq.to_le_bytes());
+                        payload.resize(16 + (seq % 13) as usize, seq as u8);
+                        loop {
+                            match buffer.write(&payload) {
+                                Ok(()) => break,
+                                Err(SharedMemoryError::RingBufferFull { .. }) => {
+                                    std::thread::yield_now()
+                                }
+                                Err(e) => panic!("write failed: {}", e),
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        // Each producer's messages must arrive exactly once and in order
+        let mut next = [0u64; PRODUCERS as usize];
+        for _ in 0..PRODUCERS * MESSAGES {
+            let payload = buffer.read_timeout(Duration::from_secs(10)).unwrap();
+            let producer = u64::from_le_bytes(payload[..8].try_into().unwrap()) as usize;
+            let seq = u64::from_le_bytes(payload[8..16].try_into().unwrap());
+            assert_eq!(seq, next[producer], "producer {} out of sequence", producer);
+            assert_eq!(payload.len(), 16 + (seq % 13) as usize);
+            assert!(payload[16..].iter().all(|&b| b == seq as u8));
+            next[producer] += 1;
+        }
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        assert_eq!(next, [MESSAGES; PRODUCERS as usize]);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.available_space(), buffer.capacity());
+
+        let stats = buffer.stats();
+        assert_eq!(stats.used, 0);
+        assert_eq!(stats.total_writes, PRODUCERS * MESSAGES);
+        assert_eq!(stats.total_reads, PRODUCERS * MESSAGES);
+        assert!(stats.high_watermark <= stats.capacity);
+    }
+
+    #[test]
+    fn test_mpsc_open_after_spsc_writes() {
+        let name = format!("test_ring_mpsc_open_{}", std::process::id());
+        let region = SharedMemoryRegion::create(&name, 4096).unwrap();
+        let spsc = RingBuffer::new(region).unwrap();
+        spsc.write(b"first").unwrap();
+
+        // The reservation cursor catches up with head, so nothing is overwritten
+        let mpsc = MpscRingBuffer::open(SharedMemoryRegion::open(&name, 4096).unwrap()).unwrap();
+        assert_eq!(mpsc.available_space(), spsc.available_space());
+        mpsc.write(b"second").unwrap();
+        assert_eq!(mpsc.read().unwrap(), b"first");
+        assert_eq!(mpsc.read().unwrap(), b"second");
+        assert!(mpsc.is_empty());
+    }
+
     #[test]
     fn test_align_up() {
         assert_eq!(RingBuffer::align_up(1, 8), 8);
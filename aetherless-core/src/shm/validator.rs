@@ -7,13 +7,56 @@
 //! Fails immediately on checksum mismatch - NO fallback.
 
 use crate::error::SharedMemoryError;
+use crate::shm::xxhash::xxh64;
 
 /// Maximum payload size (16 MB).
 pub const MAX_PAYLOAD_SIZE: usize = 16 * 1024 * 1024;
 
+/// Checksum algorithm used to protect payloads.
+///
+/// Producer and consumer must agree; ring buffers record the algorithm in
+/// their shared header.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    /// CRC32 (IEEE). Hardware accelerated on most CPUs.
+    #[default]
+    Crc32 = 1,
+    /// XXH64 truncated to its low 32 bits. Faster on large payloads.
+    XxHash64 = 2,
+    /// No integrity check; the checksum is always zero.
+    None = 3,
+}
+
+impl ChecksumAlgorithm {
+    /// Compute the checksum of a payload.
+    pub fn checksum(&self, payload: &[u8]) -> u32 {
+        match self {
+            Self::Crc32 => crc32fast::hash(payload),
+            Self::XxHash64 => xxh64(payload, 0) as u32,
+            Self::None => 0,
+        }
+    }
+}
+
+impl TryFrom<u16> for ChecksumAlgorithm {
+    type Error = SharedMemoryError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Crc32),
+            2 => Ok(Self::XxHash64),
+            3 => Ok(Self::None),
+            _ => Err(SharedMemoryError::InvalidBufferState {
+                reason: format!("Unknown checksum algorithm: {}", value),
+            }),
+        }
+    }
+}
+
 /// Validator for IPC payloads.
 ///
-/// Checks integrity using CRC32 checksums and validates size limits.
+/// Checks integrity using checksums (CRC32 by default) and validates size limits.
 pub struct PayloadValidator;
 
 impl PayloadValidator {
@@ -35,9 +78,14 @@ impl PayloadValidator {
         Ok(())
     }
 
-    /// Calculate CRC32 checksum for a payload.
+    /// Calculate the default (CRC32) checksum for a payload.
     pub fn calculate_checksum(payload: &[u8]) -> u32 {
-        crc32fast::hash(payload)
+        Self::calculate_checksum_with(payload, ChecksumAlgorithm::default())
+    }
+
+    /// Calculate a payload checksum with the given algorithm.
+    pub fn calculate_checksum_with(payload: &[u8], algorithm: ChecksumAlgorithm) -> u32 {
+        algorithm.checksum(payload)
     }
 
     /// Validate a payload after reading.
@@ -45,7 +93,22 @@ impl PayloadValidator {
     /// Verifies checksum matches expected value.
     /// FAILS IMMEDIATELY on mismatch - this is a critical error with no fallback.
     pub fn validate_checksum(payload: &[u8], expected: u32) -> Result<(), SharedMemoryError> {
-        let actual = Self::calculate_checksum(payload);
+        Self::validate_checksum_with(payload, expected, ChecksumAlgorithm::default())
+    }
+
+    /// Validate a payload checksum computed with the given algorithm.
+    ///
+    /// Always succeeds for ChecksumAlgorithm::None.
+    pub fn validate_checksum_with(
+        payload: &[u8],
+        expected: u32,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<(), SharedMemoryError> {
+        if algorithm == ChecksumAlgorithm::None {
+            return Ok(());
+        }
+
+        let actual = Self::calculate_checksum_with(payload, algorithm);
 
         if actual != expected {
             return Err(SharedMemoryError::ChecksumMismatch { expected, actual });
@@ -133,6 +196,40 @@ mod tests {
         assert!(PayloadValidator::validate_checksum(payload, checksum + 1).is_err());
     }
 
+    #[test]
+    fn test_checksum_algorithms() {
+        let payload = b"Hello, World!";
+        let crc = PayloadValidator::calculate_checksum_with(payload, ChecksumAlgorithm::Crc32);
+        let xxh = PayloadValidator::calculate_checksum_with(payload, ChecksumAlgorithm::XxHash64);
+
+        assert_eq!(crc, PayloadValidator::calculate_checksum(payload));
+        assert_eq!(xxh, xxh64(payload, 0) as u32);
+        assert_ne!(crc, xxh);
+        assert_eq!(ChecksumAlgorithm::None.checksum(payload), 0);
+
+        assert!(PayloadValidator::validate_checksum_with(
+            payload,
+            xxh,
+            ChecksumAlgorithm::XxHash64
+        )
+        .is_ok());
+        assert!(PayloadValidator::validate_checksum_with(
+            payload,
+            crc,
+            ChecksumAlgorithm::XxHash64
+        )
+        .is_err());
+        assert!(
+            PayloadValidator::validate_checksum_with(payload, 123, ChecksumAlgorithm::None).is_ok()
+        );
+
+        assert_eq!(
+            ChecksumAlgorithm::try_from(2).unwrap(),
+            ChecksumAlgorithm::XxHash64
+        );
+        assert!(ChecksumAlgorithm::try_from(0).is_err());
+    }
+
     #[test]
     fn test_size_validation() {
         let small_payload = vec![0u8; 100];
@@ -3,7 +3,9 @@
 
 //! Function state machine with typed state transitions.
 //!
-//! Implements the function lifecycle: Uninitialized → WarmSnapshot → Running → Suspended.
+//! Implements the function lifecycle: Uninitialized → WarmSnapshot → Running → Suspended,
+//! with Draining for graceful shutdown and Failed for functions whose handler
+//! could not be kept alive.
 //! Invalid transitions result in StateTransitionError.
 
 use std::time::Instant;
@@ -27,6 +29,12 @@ pub enum FunctionState {
 
     /// Function is suspended (paused) but can be resumed.
     Suspended,
+
+    /// Function is finishing in-flight requests and accepts no new ones.
+    Draining,
+
+    /// Function handler crashed and exhausted its restart policy.
+    Failed,
 }
 
 impl FunctionState {
@@ -37,6 +45,8 @@ impl FunctionState {
             Self::WarmSnapshot => "WarmSnapshot",
             Self::Running => "Running",
             Self::Suspended => "Suspended",
+            Self::Draining => "Draining",
+            Self::Failed => "Failed",
         }
     }
 
@@ -53,10 +63,19 @@ impl FunctionState {
             // From Running
             (Self::Running, Self::Suspended) |
             (Self::Running, Self::WarmSnapshot) |
+            (Self::Running, Self::Failed) |
+            (Self::Running, Self::Draining) |
+            // From Draining
+            (Self::Draining, Self::Suspended) |
+            (Self::Draining, Self::Uninitialized) |
             // From Suspended
             (Self::Suspended, Self::Running) |
             (Self::Suspended, Self::WarmSnapshot) |
-            (Self::Suspended, Self::Uninitialized)
+            (Self::Suspended, Self::Uninitialized) |
+            (Self::Suspended, Self::Failed) |
+            // From Failed (manual redeploy or reset)
+            (Self::Failed, Self::Running) |
+            (Self::Failed, Self::Uninitialized)
         )
     }
 }
@@ -67,6 +86,9 @@ impl std::fmt::Display for FunctionState {
     }
 }
 
+/// Number of transitions kept in a state machine's history.
+pub const HISTORY_CAPACITY: usize = 32;
+
 /// State machine for a function's lifecycle.
 /// Enforces valid state transitions and tracks timing metrics.
 #[derive(Debug)]
@@ -75,6 +97,12 @@ pub struct FunctionStateMachine {
     current_state: FunctionState,
     last_transition: Instant,
     transition_count: u64,
+    restart_count: u32,
+    /// Recent transitions, oldest first. Holds up to twice HISTORY_CAPACITY
+    /// so the oldest half can be dropped in one go instead of shifting the
+    /// whole buffer on every transition; only the newest HISTORY_CAPACITY
+    /// are exposed.
+    history: Vec<(FunctionState, Instant)>,
 }
 
 impl FunctionStateMachine {
@@ -85,6 +113,17 @@ impl FunctionStateMachine {
             current_state: FunctionState::Uninitialized,
             last_transition: Instant::now(),
             transition_count: 0,
+            restart_count: 0,
+            history: Vec::with_capacity(2 * HISTORY_CAPACITY),
+        }
+    }
+
+    /// Create a state machine that starts in the given state, e.g. when
+    /// restoring a persisted registry.
+    pub fn from_state(function_id: FunctionId, state: FunctionState) -> Self {
+        Self {
+            current_state: state,
+            ..Self::new(function_id)
         }
     }
 
@@ -108,6 +147,22 @@ impl FunctionStateMachine {
         self.transition_count
     }
 
+    /// Get the most recent transitions (target state and time), oldest first.
+    pub fn history(&self) -> &[(FunctionState, Instant)] {
+        &self.history[self.history.len().saturating_sub(HISTORY_CAPACITY)..]
+    }
+
+    /// Get the number of times the function's handler has been restarted.
+    pub fn restart_count(&self) -> u32 {
+        self.restart_count
+    }
+
+    /// Record a handler restart and return the new restart count.
+    pub fn record_restart(&mut self) -> u32 {
+        self.restart_count += 1;
+        self.restart_count
+    }
+
     /// Attempt to transition to a new state.
     /// Returns Ok(()) if successful, or StateTransitionError if invalid.
     pub fn transition_to(&mut self, target: FunctionState) -> Result<(), StateTransitionError> {
@@ -130,10 +185,17 @@ impl FunctionStateMachine {
         self.last_transition = Instant::now();
         self.transition_count += 1;
 
+        // Drop the oldest half once full, an amortized O(1) per transition
+        if self.history.len() == 2 * HISTORY_CAPACITY {
+            self.history.drain(..HISTORY_CAPACITY);
+        }
+        self.history.push((target, self.last_transition));
+
         Ok(())
     }
 
     /// Check if the function can be invoked (is in a runnable state).
+    /// Draining functions are not invokable for new requests.
     pub fn is_invokable(&self) -> bool {
         matches!(
             self.current_state,
@@ -154,6 +216,9 @@ pub struct StateMachineMetrics {
     pub current_state: String,
     pub time_in_state_ms: u64,
     pub transition_count: u64,
+    pub restart_count: u32,
+    /// Seconds since the last recorded transition, if any.
+    pub last_transition_secs_ago: Option<u64>,
 }
 
 impl From<&FunctionStateMachine> for StateMachineMetrics {
@@ -163,6 +228,8 @@ impl From<&FunctionStateMachine> for StateMachineMetrics {
             current_state: sm.current_state.name().to_string(),
             time_in_state_ms: sm.time_in_current_state().as_millis() as u64,
             transition_count: sm.transition_count,
+            restart_count: sm.restart_count,
+            last_transition_secs_ago: sm.history.last().map(|(_, at)| at.elapsed().as_secs()),
         }
     }
 }
@@ -182,6 +249,14 @@ mod tests {
         assert_eq!(sm.transition_count(), 0);
     }
 
+    #[test]
+    fn test_from_state() {
+        let mut sm = FunctionStateMachine::from_state(make_function_id(), FunctionState::Running);
+        assert_eq!(sm.state(), FunctionState::Running);
+        assert_eq!(sm.transition_count(), 0);
+        assert!(sm.transition_to(FunctionState::Suspended).is_ok());
+    }
+
     #[test]
     fn test_valid_transitions() {
         let mut sm = FunctionStateMachine::new(make_function_id());
@@ -204,6 +279,64 @@ mod tests {
         assert_eq!(sm.state(), FunctionState::Running);
     }
 
+    #[test]
+    fn test_draining_transitions() {
+        let mut sm = FunctionStateMachine::new(make_function_id());
+        sm.transition_to(FunctionState::Running).unwrap();
+
+        // Running → Draining
+        assert!(sm.transition_to(FunctionState::Draining).is_ok());
+        assert_eq!(sm.state(), FunctionState::Draining);
+        assert!(!sm.is_invokable());
+
+        // Draining → Running (invalid)
+        assert!(sm.transition_to(FunctionState::Running).is_err());
+        assert_eq!(sm.state(), FunctionState::Draining);
+
+        // Draining → Suspended
+        assert!(sm.transition_to(FunctionState::Suspended).is_ok());
+        assert_eq!(sm.state(), FunctionState::Suspended);
+
+        // Draining → Uninitialized
+        sm.transition_to(FunctionState::Running).unwrap();
+        sm.transition_to(FunctionState::Draining).unwrap();
+        assert!(sm.transition_to(FunctionState::Uninitialized).is_ok());
+        assert_eq!(sm.state(), FunctionState::Uninitialized);
+    }
+
+    #[test]
+    fn test_transition_history() {
+        let mut sm = FunctionStateMachine::new(make_function_id());
+        assert!(sm.history().is_empty());
+        assert_eq!(
+            StateMachineMetrics::from(&sm).last_transition_secs_ago,
+            None
+        );
+
+        sm.transition_to(FunctionState::Running).unwrap();
+        assert_eq!(sm.history().len(), 1);
+        assert_eq!(sm.history()[0].0, FunctionState::Running);
+        assert_eq!(
+            StateMachineMetrics::from(&sm).last_transition_secs_ago,
+            Some(0)
+        );
+
+        // Invalid transitions are not recorded
+        assert!(sm.transition_to(FunctionState::Uninitialized).is_err());
+        assert_eq!(sm.history().len(), 1);
+
+        // Overflow drops the oldest entries
+        for _ in 0..HISTORY_CAPACITY {
+            sm.transition_to(FunctionState::Suspended).unwrap();
+            sm.transition_to(FunctionState::Running).unwrap();
+        }
+        assert_eq!(sm.history().len(), HISTORY_CAPACITY);
+        assert_eq!(sm.history()[0].0, FunctionState::Suspended);
+        assert_eq!(sm.history()[HISTORY_CAPACITY - 1].0, FunctionState::Running);
+        assert!(sm.history().windows(2).all(|w| w[0].1 <= w[1].1));
+        assert!(sm.history.len() <= 2 * HISTORY_CAPACITY);
+    }
+
     #[test]
     fn test_invalid_transitions() {
         let mut sm = FunctionStateMachine::new(make_function_id());
@@ -213,6 +346,26 @@ mod tests {
         assert_eq!(sm.state(), FunctionState::Uninitialized);
     }
 
+    #[test]
+    fn test_failed_transitions() {
+        let mut sm = FunctionStateMachine::new(make_function_id());
+
+        // Running → Failed
+        sm.transition_to(FunctionState::Running).unwrap();
+        assert!(sm.transition_to(FunctionState::Failed).is_ok());
+        assert!(!sm.is_invokable());
+
+        // Failed → WarmSnapshot (invalid)
+        assert!(sm.transition_to(FunctionState::WarmSnapshot).is_err());
+
+        // Failed → Running (redeploy)
+        assert!(sm.transition_to(FunctionState::Running).is_ok());
+
+        assert_eq!(sm.record_restart(), 1);
+        assert_eq!(sm.record_restart(), 2);
+        assert_eq!(StateMachineMetrics::from(&sm).restart_count, 2);
+    }
+
     #[test]
     fn test_is_invokable() {
         let mut sm = FunctionStateMachine::new(make_function_id());
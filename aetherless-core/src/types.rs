@@ -7,7 +7,10 @@
 //! All types validate their invariants at creation time.
 
 use std::fmt;
+use std::os::unix::process::ExitStatusExt;
 use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +20,10 @@ use crate::error::HardValidationError;
 const MIN_MEMORY_LIMIT: u64 = 1024 * 1024;
 /// Maximum allowed memory limit: 16 GB
 const MAX_MEMORY_LIMIT: u64 = 16 * 1024 * 1024 * 1024;
+/// Millicores in one full CPU
+const MILLICORES_PER_CPU: u32 = 1000;
+/// cgroup v2 `cpu.max` period in microseconds (the kernel default)
+const CPU_PERIOD_US: u64 = 100_000;
 
 /// Validated function identifier.
 /// Must be non-empty, alphanumeric with hyphens/underscores, max 64 chars.
@@ -132,10 +139,19 @@ impl From<Port> for u16 {
 
 /// Validated memory limit in bytes.
 /// Must be between MIN_MEMORY_LIMIT and MAX_MEMORY_LIMIT.
+/// Deserializes from either a byte count or a human-readable size string.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(try_from = "u64", into = "u64")]
+#[serde(try_from = "MemoryLimitRepr", into = "u64")]
 pub struct MemoryLimit(u64);
 
+/// Accepted serialized forms of a memory limit.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MemoryLimitRepr {
+    Bytes(u64),
+    Human(String),
+}
+
 impl MemoryLimit {
     /// Create a new MemoryLimit with bounds validation.
     pub fn new(bytes: u64) -> Result<Self, HardValidationError> {
@@ -171,6 +187,48 @@ impl fmt::Display for MemoryLimit {
     }
 }
 
+impl FromStr for MemoryLimit {
+    type Err = HardValidationError;
+
+    /// Parse sizes such as `"256MiB"` or `"1GB"` (suffix is case-insensitive).
+    ///
+    /// `KB`/`MB`/`GB` are treated as binary units, the same as
+    /// `memory_limit_mb`, so `"128MB"` and `"128MiB"` are equal.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| HardValidationError::InvalidFieldValue {
+            field: "memory",
+            value: s.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let trimmed = s.trim();
+        let split = trimmed
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| invalid("Missing unit (expected KB, MB, GB, KiB, MiB or GiB)"))?;
+        let (number, unit) = trimmed.split_at(split);
+
+        if number.is_empty() {
+            return Err(invalid("Missing numeric value"));
+        }
+        let multiplier: u64 = match unit.trim_start().to_ascii_lowercase().as_str() {
+            "kb" | "kib" => 1024,
+            "mb" | "mib" => 1024 * 1024,
+            "gb" | "gib" => 1024 * 1024 * 1024,
+            _ => {
+                return Err(invalid(
+                    "Unknown unit (expected KB, MB, GB, KiB, MiB or GiB)",
+                ))
+            }
+        };
+        let value: u64 = number.parse().map_err(|_| invalid("Value is too large"))?;
+        let bytes = value
+            .checked_mul(multiplier)
+            .ok_or_else(|| invalid("Value is too large"))?;
+
+        Self::new(bytes)
+    }
+}
+
 impl TryFrom<u64> for MemoryLimit {
     type Error = HardValidationError;
 
@@ -179,6 +237,17 @@ impl TryFrom<u64> for MemoryLimit {
     }
 }
 
+impl TryFrom<MemoryLimitRepr> for MemoryLimit {
+    type Error = HardValidationError;
+
+    fn try_from(value: MemoryLimitRepr) -> Result<Self, Self::Error> {
+        match value {
+            MemoryLimitRepr::Bytes(bytes) => Self::new(bytes),
+            MemoryLimitRepr::Human(s) => s.parse(),
+        }
+    }
+}
+
 impl From<MemoryLimit> for u64 {
     fn from(limit: MemoryLimit) -> Self {
         limit.0
@@ -249,6 +318,76 @@ impl From<HandlerPath> for PathBuf {
     }
 }
 
+/// Validated CPU limit in millicores (1000 = one full core).
+/// Must be positive. Config loading also caps it at the CPUs available to
+/// this process; deserialization does not, so a limit from a bigger host
+/// (e.g. in a registry snapshot) still round-trips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "u32", into = "u32")]
+pub struct CpuLimit(u32);
+
+impl CpuLimit {
+    /// Create a new CpuLimit, validated against the available CPUs.
+    pub fn new(millicores: u32) -> Result<Self, HardValidationError> {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_cpus(millicores, cpus)
+    }
+
+    /// Create a new CpuLimit, validated against `cpus` available CPUs.
+    pub fn with_cpus(millicores: u32, cpus: usize) -> Result<Self, HardValidationError> {
+        let max = u32::try_from(cpus)
+            .unwrap_or(u32::MAX)
+            .saturating_mul(MILLICORES_PER_CPU);
+        if millicores == 0 || millicores > max {
+            return Err(HardValidationError::InvalidFieldValue {
+                field: "cpu_limit_millicores",
+                value: millicores.to_string(),
+                reason: format!("Must be between 1 and {} ({} CPUs available)", max, cpus),
+            });
+        }
+        Ok(Self(millicores))
+    }
+
+    /// Get the limit in millicores.
+    pub fn millicores(&self) -> u32 {
+        self.0
+    }
+
+    /// The limit as a cgroup v2 `cpu.max` value: `"<quota> <period>"` in
+    /// microseconds.
+    pub fn cpu_max(&self) -> String {
+        let quota = CPU_PERIOD_US * u64::from(self.0) / u64::from(MILLICORES_PER_CPU);
+        format!("{} {}", quota, CPU_PERIOD_US)
+    }
+}
+
+impl fmt::Display for CpuLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}m", self.0)
+    }
+}
+
+impl TryFrom<u32> for CpuLimit {
+    type Error = HardValidationError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        if value == 0 {
+            return Err(HardValidationError::InvalidFieldValue {
+                field: "cpu_limit_millicores",
+                value: value.to_string(),
+                reason: "Must be positive".to_string(),
+            });
+        }
+        Ok(Self(value))
+    }
+}
+
+impl From<CpuLimit> for u32 {
+    fn from(limit: CpuLimit) -> Self {
+        limit.0
+    }
+}
+
 /// Validated process ID.
 /// Must be positive (non-zero).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -285,6 +424,73 @@ impl From<ProcessId> for u32 {
     }
 }
 
+/// Why a handler process is no longer running, or that it still is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExitReason {
+    /// The process has not exited.
+    Running,
+    /// The process exited on its own with this code.
+    Exited { code: i32 },
+    /// The process was terminated by a signal.
+    Signaled { signal: i32, core_dumped: bool },
+    /// The kernel OOM killer ended the process at its memory limit.
+    OutOfMemory,
+}
+
+impl ExitReason {
+    /// Classify a wait status. OOM kills arrive as plain SIGKILL; callers
+    /// that can tell (e.g. from cgroup counters) report
+    /// [`OutOfMemory`](Self::OutOfMemory) themselves.
+    pub fn from_status(status: ExitStatus) -> Self {
+        if let Some(code) = status.code() {
+            Self::Exited { code }
+        } else if let Some(signal) = status.signal() {
+            Self::Signaled {
+                signal,
+                core_dumped: status.core_dumped(),
+            }
+        } else {
+            // Stopped or continued: still alive
+            Self::Running
+        }
+    }
+
+    /// Whether the exit counts as a failure for restart policies: anything
+    /// but a zero exit code.
+    pub fn is_failure(&self) -> bool {
+        !matches!(self, Self::Running | Self::Exited { code: 0 })
+    }
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Running => write!(f, "running"),
+            Self::Exited { code } => write!(f, "exited (code {})", code),
+            Self::Signaled {
+                signal,
+                core_dumped,
+            } => {
+                let name = nix::sys::signal::Signal::try_from(*signal)
+                    .map(|s| s.as_str().to_string())
+                    .unwrap_or_else(|_| format!("signal {}", signal));
+                // Signals someone sent on purpose are not crashes
+                let verb = match name.as_str() {
+                    "SIGKILL" | "SIGTERM" | "SIGINT" | "SIGHUP" => "killed",
+                    _ => "crashed",
+                };
+                write!(f, "{} ({}", verb, name)?;
+                if *core_dumped {
+                    write!(f, ", core dumped")?;
+                }
+                write!(f, ")")
+            }
+            Self::OutOfMemory => write!(f, "killed (out of memory)"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,6 +536,56 @@ mod tests {
         assert!(MemoryLimit::new(MAX_MEMORY_LIMIT + 1).is_err());
     }
 
+    #[test]
+    fn test_cpu_limit() {
+        assert!(CpuLimit::with_cpus(0, 2).is_err());
+        assert!(CpuLimit::with_cpus(2001, 2).is_err());
+        assert_eq!(CpuLimit::with_cpus(2000, 2).unwrap().millicores(), 2000);
+
+        assert_eq!(
+            CpuLimit::with_cpus(500, 1).unwrap().cpu_max(),
+            "50000 100000"
+        );
+        assert_eq!(
+            CpuLimit::with_cpus(1500, 2).unwrap().cpu_max(),
+            "150000 100000"
+        );
+        assert_eq!(CpuLimit::with_cpus(250, 1).unwrap().to_string(), "250m");
+
+        // One core is always available
+        assert!(CpuLimit::new(1000).is_ok());
+
+        // Deserializing only requires a positive limit, whatever this host has
+        let limit: CpuLimit = serde_json::from_str("4000000").unwrap();
+        assert_eq!(limit.millicores(), 4_000_000);
+        assert!(serde_json::from_str::<CpuLimit>("0").is_err());
+    }
+
+    #[test]
+    fn test_memory_limit_from_str() {
+        let mb = |n: u64| n * 1024 * 1024;
+        assert_eq!("256MiB".parse::<MemoryLimit>().unwrap().bytes(), mb(256));
+        assert_eq!("256mb".parse::<MemoryLimit>().unwrap().bytes(), mb(256));
+        assert_eq!("1GB".parse::<MemoryLimit>().unwrap().bytes(), mb(1024));
+        assert_eq!("2048 KiB".parse::<MemoryLimit>().unwrap().bytes(), mb(2));
+        assert_eq!("1GiB".parse::<MemoryLimit>().unwrap().to_string(), "1024MB");
+
+        assert!("256".parse::<MemoryLimit>().is_err());
+        assert!("256M".parse::<MemoryLimit>().is_err());
+        assert!("1.5GB".parse::<MemoryLimit>().is_err());
+        assert!("MiB".parse::<MemoryLimit>().is_err());
+        assert!(matches!(
+            "512KiB".parse::<MemoryLimit>(),
+            Err(HardValidationError::MemoryLimitOutOfBounds { .. })
+        ));
+        assert!("32GiB".parse::<MemoryLimit>().is_err());
+
+        let from_json: MemoryLimit = serde_json::from_str("\"64MiB\"").unwrap();
+        assert_eq!(from_json, MemoryLimit::from_mb(64).unwrap());
+        let from_bytes: MemoryLimit = serde_json::from_str("67108864").unwrap();
+        assert_eq!(from_bytes, from_json);
+    }
+
     #[test]
     fn test_process_id_valid() {
         assert!(ProcessId::new(1).is_ok());
@@ -340,4 +596,45 @@ mod tests {
     fn test_process_id_invalid() {
         assert!(ProcessId::new(0).is_err());
     }
+
+    #[test]
+    fn test_exit_reason() {
+        let exited = ExitReason::from_status(ExitStatus::from_raw(1 << 8));
+        assert_eq!(exited, ExitReason::Exited { code: 1 });
+        assert_eq!(exited.to_string(), "exited (code 1)");
+        assert!(exited.is_failure());
+
+        let clean = ExitReason::from_status(ExitStatus::from_raw(0));
+        assert_eq!(clean.to_string(), "exited (code 0)");
+        assert!(!clean.is_failure());
+
+        let segv = ExitReason::from_status(ExitStatus::from_raw(libc::SIGSEGV));
+        assert_eq!(
+            segv,
+            ExitReason::Signaled {
+                signal: libc::SIGSEGV,
+                core_dumped: false
+            }
+        );
+        assert_eq!(segv.to_string(), "crashed (SIGSEGV)");
+        assert!(segv.is_failure());
+
+        // 0x80 is the core-dump bit
+        let core = ExitReason::from_status(ExitStatus::from_raw(libc::SIGABRT | 0x80));
+        assert_eq!(core.to_string(), "crashed (SIGABRT, core dumped)");
+
+        let killed = ExitReason::from_status(ExitStatus::from_raw(libc::SIGKILL));
+        assert_eq!(killed.to_string(), "killed (SIGKILL)");
+        assert_eq!(
+            ExitReason::OutOfMemory.to_string(),
+            "killed (out of memory)"
+        );
+        assert!(!ExitReason::Running.is_failure());
+
+        let json = serde_json::to_value(segv).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "signaled", "signal": 11, "core_dumped": false })
+        );
+    }
 }
@@ -198,9 +198,18 @@ fn test_registry_concurrent_access() {
                 let config = FunctionConfig {
                     id: FunctionId::new(format!("func-{}", i)).unwrap(),
                     memory_limit: MemoryLimit::from_mb(128).unwrap(),
+                    cpu_limit: None,
                     trigger_port: Port::new(3000 + i as u16).unwrap(),
                     handler_path: HandlerPath::new("/bin/echo").unwrap(),
                     timeout_ms: 30000,
+                    restart_policy: Default::default(),
+                    max_restarts: 5,
+                    restart_backoff_max_ms: 30000,
+                    max_concurrency: None,
+                    ready_timeout_ms: 30000,
+                    handshake: Default::default(),
+                    health_check_interval_ms: None,
+                    restore_mode: Default::default(),
                     environment: Default::default(),
                 };
                 reg.register(config).unwrap();
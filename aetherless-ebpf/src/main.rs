@@ -7,16 +7,16 @@
 //! Uses Aya to load and manage eBPF programs for kernel-bypass networking.
 
 use std::collections::HashMap;
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::path::Path;
 use std::sync::Arc;
 
-use aya::maps::HashMap as BpfHashMap;
+use aya::maps::{HashMap as BpfHashMap, MapData, PerCpuArray};
 use aya::programs::{Xdp, XdpFlags};
 use aya::Ebpf;
 use tokio::sync::RwLock;
 
-use aetherless_core::{AetherError, EbpfError, Port, ProcessId};
+use aetherless_core::{AetherError, EbpfError, Port, ProcessId, XdpAttachMode};
 
 /// Key for the port-to-PID BPF map.
 /// Layout must match the eBPF program's key structure.
@@ -29,17 +29,193 @@ pub struct PortKey {
 
 unsafe impl aya::Pod for PortKey {}
 
+/// `PortValue::family` for an IPv4 target (`AF_INET`).
+pub const ADDR_FAMILY_V4: u16 = 2;
+/// `PortValue::family` for an IPv6 target (`AF_INET6`).
+pub const ADDR_FAMILY_V6: u16 = 10;
+
 /// Value for the port-to-PID BPF map.
 /// Contains the target process ID and socket address.
+///
+/// Layout (24 bytes) must match the eBPF program's value structure:
+///
+/// | offset | size | field      | contents                               |
+/// |--------|------|------------|----------------------------------------|
+/// | 0      | 4    | `pid`      | target process ID, host byte order     |
+/// | 4      | 2    | `family`   | `ADDR_FAMILY_V4` or `ADDR_FAMILY_V6`   |
+/// | 6      | 2    | `_padding` | zero                                   |
+/// | 8      | 16   | `addr`     | address octets in network byte order   |
+///
+/// IPv4 targets occupy `addr[0..4]` with the remaining bytes zeroed, so the
+/// kernel side can read a single `u32` when `family == AF_INET`.
 #[repr(C)]
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct PortValue {
     pub pid: u32,
-    pub addr: u32, // IPv4 address in network byte order
+    pub family: u16,
+    pub _padding: u16,
+    pub addr: [u8; 16],
 }
 
+const _: () = assert!(std::mem::size_of::<PortValue>() == 24);
+
 unsafe impl aya::Pod for PortValue {}
 
+impl PortValue {
+    /// Build a value targeting an IPv4 address.
+    pub fn v4(pid: u32, addr: Ipv4Addr) -> Self {
+        let mut octets = [0u8; 16];
+        octets[..4].copy_from_slice(&addr.octets());
+        Self {
+            pid,
+            family: ADDR_FAMILY_V4,
+            _padding: 0,
+            addr: octets,
+        }
+    }
+
+    /// Build a value targeting an IPv6 address.
+    pub fn v6(pid: u32, addr: Ipv6Addr) -> Self {
+        Self {
+            pid,
+            family: ADDR_FAMILY_V6,
+            _padding: 0,
+            addr: addr.octets(),
+        }
+    }
+
+    /// Build a value for either address family.
+    pub fn new(pid: u32, addr: IpAddr) -> Self {
+        match addr {
+            IpAddr::V4(v4) => Self::v4(pid, v4),
+            IpAddr::V6(v6) => Self::v6(pid, v6),
+        }
+    }
+
+    /// The target address, decoded according to `family`.
+    pub fn ip(&self) -> IpAddr {
+        if self.family == ADDR_FAMILY_V4 {
+            IpAddr::V4(Ipv4Addr::new(
+                self.addr[0],
+                self.addr[1],
+                self.addr[2],
+                self.addr[3],
+            ))
+        } else {
+            IpAddr::V6(Ipv6Addr::from(self.addr))
+        }
+    }
+}
+
+impl Default for PortValue {
+    fn default() -> Self {
+        Self::v4(0, Ipv4Addr::UNSPECIFIED)
+    }
+}
+
+/// Name of the optional per-port counter map (`PERCPU_ARRAY` indexed by port).
+const PORT_STATS_MAP: &str = "port_stats_map";
+
+/// Per-port traffic counters maintained by the XDP program.
+/// Layout must match the eBPF program's value structure.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PacketCounters {
+    /// Packets redirected.
+    pub packets: u64,
+    /// Bytes redirected.
+    pub bytes: u64,
+}
+
+unsafe impl aya::Pod for PacketCounters {}
+
+impl PacketCounters {
+    /// Sum per-CPU counter values into one total.
+    fn sum<'a>(values: impl IntoIterator<Item = &'a PacketCounters>) -> Self {
+        values.into_iter().fold(Self::default(), |total, v| Self {
+            packets: total.packets.wrapping_add(v.packets),
+            bytes: total.bytes.wrapping_add(v.bytes),
+        })
+    }
+}
+
+/// The `XdpFlags` that request the given attach mode from the kernel.
+fn attach_flags(mode: XdpAttachMode) -> XdpFlags {
+    match mode {
+        XdpAttachMode::Auto => XdpFlags::default(),
+        XdpAttachMode::Generic => XdpFlags::SKB_MODE,
+        XdpAttachMode::Native => XdpFlags::DRV_MODE,
+        XdpAttachMode::Offload => XdpFlags::HW_MODE,
+    }
+}
+
+/// Name of the port-to-PID redirect map (`HASH` keyed by [`PortKey`]).
+const PORT_REDIRECT_MAP: &str = "port_redirect_map";
+
+/// Open the redirect map of a loaded BPF object.
+fn open_redirect_map(
+    bpf: &mut Ebpf,
+) -> Result<BpfHashMap<&mut MapData, PortKey, PortValue>, AetherError> {
+    let map = bpf.map_mut(PORT_REDIRECT_MAP).ok_or_else(|| {
+        AetherError::Ebpf(EbpfError::MapNotFound {
+            name: PORT_REDIRECT_MAP.to_string(),
+        })
+    })?;
+
+    BpfHashMap::try_from(map).map_err(|e| {
+        AetherError::Ebpf(EbpfError::MapOperationFailed {
+            operation: "open".to_string(),
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Differences found between the userspace port mirror and the kernel map.
+///
+/// Ports are named from the kernel's point of view: `added` are missing from
+/// the kernel and would be added by a repair, `removed` are only in the
+/// kernel and would be removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconcileReport {
+    /// Ports in the mirror but not in the kernel map.
+    pub added: Vec<u16>,
+    /// Ports in the kernel map but not in the mirror.
+    pub removed: Vec<u16>,
+    /// Ports present in both with a different target.
+    pub divergent: Vec<u16>,
+    /// Whether the mirror was written back to the kernel.
+    pub repaired: bool,
+}
+
+impl ReconcileReport {
+    /// Compare the mirror against a snapshot of the kernel map.
+    fn compare(mirror: &HashMap<u16, PortValue>, kernel: &HashMap<u16, PortValue>) -> Self {
+        let mut report = Self::default();
+        for (port, value) in mirror {
+            match kernel.get(port) {
+                None => report.added.push(*port),
+                Some(kernel_value) if kernel_value != value => report.divergent.push(*port),
+                Some(_) => {}
+            }
+        }
+        report.removed = kernel
+            .keys()
+            .filter(|port| !mirror.contains_key(port))
+            .copied()
+            .collect();
+
+        report.added.sort_unstable();
+        report.removed.sort_unstable();
+        report.divergent.sort_unstable();
+        report
+    }
+
+    /// Whether the mirror and kernel map agree.
+    pub fn is_consistent(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.divergent.is_empty()
+    }
+}
+
 /// eBPF program manager for XDP-based packet redirection.
 ///
 /// Manages the lifecycle of XDP programs and BPF maps for
@@ -85,6 +261,7 @@ impl XdpManager {
     /// # Arguments
     /// * `program_path` - Path to the compiled BPF object file (.o)
     /// * `program_name` - Name of the XDP program section in the object
+    /// * `attach_mode` - XDP attach mode to request
     ///
     /// # Errors
     /// Returns EbpfError if loading or attaching fails.
@@ -95,11 +272,41 @@ impl XdpManager {
         &mut self,
         program_path: P,
         program_name: &str,
+        attach_mode: XdpAttachMode,
     ) -> Result<(), AetherError> {
         let path = program_path.as_ref();
 
-        // Load the BPF object file
-        let mut bpf = Ebpf::load_file(path).map_err(|e| {
+        let data = std::fs::read(path).map_err(|e| {
+            AetherError::Ebpf(EbpfError::LoadFailed {
+                reason: format!("Failed to read BPF object '{}': {}", path.display(), e),
+            })
+        })?;
+
+        self.load_program_bytes(&data, program_name, attach_mode)?;
+
+        tracing::debug!(path = %path.display(), "XDP program loaded from file");
+        Ok(())
+    }
+
+    /// Load an XDP program from an in-memory BPF object.
+    ///
+    /// Lets a binary embed its program with `include_bytes!` instead of
+    /// shipping a separate `.o` file.
+    ///
+    /// # Errors
+    /// Returns EbpfError if the object is malformed, or loading or
+    /// attaching fails. An attach failure is not retried in another mode.
+    ///
+    /// # Privileges
+    /// Requires CAP_BPF and CAP_NET_ADMIN capabilities (typically root).
+    pub fn load_program_bytes(
+        &mut self,
+        data: &[u8],
+        program_name: &str,
+        attach_mode: XdpAttachMode,
+    ) -> Result<(), AetherError> {
+        // Load the BPF object
+        let mut bpf = Ebpf::load(data).map_err(|e| {
             AetherError::Ebpf(EbpfError::LoadFailed {
                 reason: format!("Failed to load BPF object '{}': {}", program_name, e),
             })
@@ -127,18 +334,21 @@ impl XdpManager {
         })?;
 
         program
-            .attach(&self.interface, XdpFlags::default())
+            .attach(&self.interface, attach_flags(attach_mode))
             .map_err(|e| {
                 AetherError::Ebpf(EbpfError::AttachFailed {
                     interface: self.interface.clone(),
-                    reason: format!("Failed to attach XDP program: {}", e),
+                    reason: format!(
+                        "Failed to attach XDP program in {} mode: {}",
+                        attach_mode, e
+                    ),
                 })
             })?;
 
         tracing::info!(
             interface = %self.interface,
             program = %program_name,
-            path = %path.display(),
+            mode = %attach_mode,
             "XDP program loaded and attached"
         );
 
@@ -153,20 +363,20 @@ impl XdpManager {
     /// # Arguments
     /// * `port` - TCP port to route
     /// * `pid` - Process ID of the handler
-    /// * `addr` - IPv4 address to route to (default: 127.0.0.1)
+    /// * `addr` - IPv4 or IPv6 address to route to (default: 127.0.0.1)
     pub async fn register_port(
         &mut self,
         port: Port,
         pid: ProcessId,
-        addr: Option<Ipv4Addr>,
+        addr: Option<IpAddr>,
     ) -> Result<(), AetherError> {
         let key = PortKey {
             port: port.value(),
             _padding: 0,
         };
-        let value = PortValue {
-            pid: pid.value(),
-            addr: addr.unwrap_or(Ipv4Addr::LOCALHOST).into(),
+        let value = match addr {
+            Some(addr) => PortValue::new(pid.value(), addr),
+            None => PortValue::v4(pid.value(), Ipv4Addr::LOCALHOST),
         };
 
         // Update userspace mirror
@@ -177,18 +387,7 @@ impl XdpManager {
 
         // Update BPF map if loaded
         if let Some(ref mut bpf) = self.bpf {
-            let mut bpf_map: BpfHashMap<_, PortKey, PortValue> =
-                BpfHashMap::try_from(bpf.map_mut("port_redirect_map").ok_or_else(|| {
-                    AetherError::Ebpf(EbpfError::MapNotFound {
-                        name: "port_redirect_map".to_string(),
-                    })
-                })?)
-                .map_err(|e| {
-                    AetherError::Ebpf(EbpfError::MapOperationFailed {
-                        operation: "open".to_string(),
-                        reason: e.to_string(),
-                    })
-                })?;
+            let mut bpf_map = open_redirect_map(bpf)?;
 
             bpf_map.insert(key, value, 0).map_err(|e| {
                 AetherError::Ebpf(EbpfError::MapOperationFailed {
@@ -198,7 +397,67 @@ impl XdpManager {
             })?;
         }
 
-        tracing::info!(port = %port, pid = %pid, "Registered port mapping");
+        tracing::info!(port = %port, pid = %pid, addr = %value.ip(), "Registered port mapping");
+        Ok(())
+    }
+
+    /// Register many port mappings at once.
+    ///
+    /// Takes the mirror lock and opens the BPF map once for the whole batch.
+    /// If a kernel insert fails, every entry applied so far is rolled back in
+    /// both the mirror and the kernel map, and `EbpfError::MapUpdateFailed`
+    /// names the port that failed.
+    pub async fn register_ports(
+        &mut self,
+        entries: &[(Port, ProcessId, Option<IpAddr>)],
+    ) -> Result<(), AetherError> {
+        let mut mirror = self.port_map.write().await;
+        let mut bpf_map = match self.bpf {
+            Some(ref mut bpf) => Some(open_redirect_map(bpf)?),
+            None => None,
+        };
+
+        let mut previous: Vec<(u16, Option<PortValue>)> = Vec::with_capacity(entries.len());
+        for (port, pid, addr) in entries {
+            let key = PortKey {
+                port: port.value(),
+                _padding: 0,
+            };
+            let value = match addr {
+                Some(addr) => PortValue::new(pid.value(), *addr),
+                None => PortValue::v4(pid.value(), Ipv4Addr::LOCALHOST),
+            };
+
+            if let Some(ref mut bpf_map) = bpf_map {
+                if let Err(e) = bpf_map.insert(key, value, 0) {
+                    // Undo in reverse so a port listed twice gets its original value back
+                    for (rolled_back, old) in previous.into_iter().rev() {
+                        let key = PortKey {
+                            port: rolled_back,
+                            _padding: 0,
+                        };
+                        match old {
+                            Some(old) => {
+                                let _ = bpf_map.insert(key, old, 0);
+                                mirror.insert(rolled_back, old);
+                            }
+                            None => {
+                                let _ = bpf_map.remove(&key);
+                                mirror.remove(&rolled_back);
+                            }
+                        }
+                    }
+                    return Err(AetherError::Ebpf(EbpfError::MapUpdateFailed {
+                        port: *port,
+                        reason: e.to_string(),
+                    }));
+                }
+            }
+
+            previous.push((port.value(), mirror.insert(port.value(), value)));
+        }
+
+        tracing::info!(count = entries.len(), "Registered port mappings");
         Ok(())
     }
 
@@ -217,18 +476,7 @@ impl XdpManager {
 
         // Update BPF map if loaded
         if let Some(ref mut bpf) = self.bpf {
-            let mut bpf_map: BpfHashMap<_, PortKey, PortValue> =
-                BpfHashMap::try_from(bpf.map_mut("port_redirect_map").ok_or_else(|| {
-                    AetherError::Ebpf(EbpfError::MapNotFound {
-                        name: "port_redirect_map".to_string(),
-                    })
-                })?)
-                .map_err(|e| {
-                    AetherError::Ebpf(EbpfError::MapOperationFailed {
-                        operation: "open".to_string(),
-                        reason: e.to_string(),
-                    })
-                })?;
+            let mut bpf_map = open_redirect_map(bpf)?;
 
             // Ignore error if key doesn't exist
             let _ = bpf_map.remove(&key);
@@ -238,10 +486,79 @@ impl XdpManager {
         Ok(())
     }
 
-    /// Get the process ID for a port from the userspace cache.
-    pub async fn lookup_port(&self, port: Port) -> Option<u32> {
+    /// Get the process ID and target address for a port from the userspace cache.
+    pub async fn lookup_port(&self, port: Port) -> Option<(u32, IpAddr)> {
         let map = self.port_map.read().await;
-        map.get(&port.value()).map(|v| v.pid)
+        map.get(&port.value()).map(|v| (v.pid, v.ip()))
+    }
+
+    /// Compare the userspace mirror with the kernel redirect map.
+    ///
+    /// With `repair` set, the mirror is treated as the source of truth:
+    /// missing and divergent entries are written to the kernel and
+    /// kernel-only entries are removed. Returns an empty report if no
+    /// program is loaded.
+    pub async fn reconcile(&mut self, repair: bool) -> Result<ReconcileReport, AetherError> {
+        let Some(ref mut bpf) = self.bpf else {
+            return Ok(ReconcileReport::default());
+        };
+        let mut bpf_map = open_redirect_map(bpf)?;
+
+        let mut kernel = HashMap::new();
+        for entry in bpf_map.iter() {
+            let (key, value) = entry.map_err(|e| {
+                AetherError::Ebpf(EbpfError::MapOperationFailed {
+                    operation: "iterate".to_string(),
+                    reason: e.to_string(),
+                })
+            })?;
+            kernel.insert(key.port, value);
+        }
+
+        let mirror = self.port_map.read().await;
+        let mut report = ReconcileReport::compare(&mirror, &kernel);
+
+        if repair && !report.is_consistent() {
+            for port in report.added.iter().chain(&report.divergent) {
+                let key = PortKey {
+                    port: *port,
+                    _padding: 0,
+                };
+                bpf_map.insert(key, mirror[port], 0).map_err(|e| {
+                    AetherError::Ebpf(EbpfError::MapOperationFailed {
+                        operation: "insert".to_string(),
+                        reason: e.to_string(),
+                    })
+                })?;
+            }
+            for port in &report.removed {
+                let key = PortKey {
+                    port: *port,
+                    _padding: 0,
+                };
+                bpf_map.remove(&key).map_err(|e| {
+                    AetherError::Ebpf(EbpfError::MapOperationFailed {
+                        operation: "remove".to_string(),
+                        reason: e.to_string(),
+                    })
+                })?;
+            }
+            report.repaired = true;
+        }
+
+        if report.is_consistent() {
+            tracing::debug!("Port map consistent with kernel");
+        } else {
+            tracing::warn!(
+                added = ?report.added,
+                removed = ?report.removed,
+                divergent = ?report.divergent,
+                repaired = report.repaired,
+                "Port map diverged from kernel"
+            );
+        }
+
+        Ok(report)
     }
 
     /// Get all registered port mappings.
@@ -250,14 +567,58 @@ impl XdpManager {
         map.iter().map(|(k, v)| (*k, v.pid)).collect()
     }
 
+    /// Read the packet and byte counters of every registered port.
+    ///
+    /// Per-CPU values are summed. Returns an empty map if no program is
+    /// loaded or the program was built without the counter map.
+    pub async fn packet_stats(&self) -> Result<HashMap<u16, PacketCounters>, AetherError> {
+        let Some(ref bpf) = self.bpf else {
+            return Ok(HashMap::new());
+        };
+        let Some(map) = bpf.map(PORT_STATS_MAP) else {
+            return Ok(HashMap::new());
+        };
+
+        let counters: PerCpuArray<_, PacketCounters> = PerCpuArray::try_from(map).map_err(|e| {
+            AetherError::Ebpf(EbpfError::MapOperationFailed {
+                operation: "open".to_string(),
+                reason: e.to_string(),
+            })
+        })?;
+
+        let ports: Vec<u16> = self.port_map.read().await.keys().copied().collect();
+        let mut stats = HashMap::with_capacity(ports.len());
+        for port in ports {
+            let values = counters.get(&u32::from(port), 0).map_err(|e| {
+                AetherError::Ebpf(EbpfError::MapOperationFailed {
+                    operation: "lookup".to_string(),
+                    reason: e.to_string(),
+                })
+            })?;
+            stats.insert(port, PacketCounters::sum(values.iter()));
+        }
+
+        Ok(stats)
+    }
+
     /// Get statistics about the XDP manager.
     pub async fn stats(&self) -> XdpStats {
+        let totals = match self.packet_stats().await {
+            Ok(per_port) => PacketCounters::sum(per_port.values()),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to read packet counters");
+                PacketCounters::default()
+            }
+        };
+
         let map = self.port_map.read().await;
         XdpStats {
             registered_ports: map.len(),
             interface: self.interface.clone(),
             loaded: self.loaded,
             ports: map.keys().copied().collect(),
+            packets: totals.packets,
+            bytes: totals.bytes,
         }
     }
 
@@ -285,6 +646,10 @@ pub struct XdpStats {
     pub interface: String,
     pub loaded: bool,
     pub ports: Vec<u16>,
+    /// Packets redirected across all registered ports.
+    pub packets: u64,
+    /// Bytes redirected across all registered ports.
+    pub bytes: u64,
 }
 
 /// CLI entry point for the eBPF loader.
@@ -299,18 +664,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!();
 
     // Parse command line arguments
-    let args: Vec<String> = std::env::args().collect();
+    let mut args: Vec<String> = std::env::args().collect();
+
+    let mut attach_mode = XdpAttachMode::default();
+    if let Some(pos) = args.iter().position(|a| a == "--mode") {
+        let Some(value) = args.get(pos + 1) else {
+            return Err("--mode requires a value (auto, generic, native or offload)".into());
+        };
+        attach_mode = value.parse()?;
+        args.drain(pos..=pos + 1);
+    }
 
     if args.len() < 2 {
-        println!("Usage: aetherless-ebpf <interface> [bpf_object_path]");
+        println!("Usage: aetherless-ebpf <interface> [bpf_object_path] [--mode <mode>]");
         println!();
         println!("Arguments:");
         println!("  <interface>        Network interface (e.g., eth0, lo)");
         println!("  [bpf_object_path]  Path to compiled BPF object (optional)");
+        println!("  --mode <mode>      XDP attach mode: auto, generic (skb),");
+        println!("                     native (driver) or offload (hardware)");
         println!();
         println!("Examples:");
         println!("  aetherless-ebpf eth0");
         println!("  aetherless-ebpf eth0 /path/to/xdp_redirect.o");
+        println!("  aetherless-ebpf eth0 /path/to/xdp_redirect.o --mode native");
         println!();
         println!("Note: Requires CAP_BPF and CAP_NET_ADMIN (run as root)");
         return Ok(());
@@ -324,9 +701,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let bpf_path = &args[2];
         println!("Loading XDP program from: {}", bpf_path);
 
-        match manager.load_program(bpf_path, "xdp_redirect") {
+        match manager.load_program(bpf_path, "xdp_redirect", attach_mode) {
             Ok(()) => {
-                println!("✓ XDP program loaded and attached to {}", interface);
+                println!(
+                    "✓ XDP program loaded and attached to {} ({} mode)",
+                    interface, attach_mode
+                );
             }
             Err(e) => {
                 eprintln!("✗ Failed to load XDP program: {}", e);
@@ -347,6 +727,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Interface: {}", stats.interface);
     println!("  XDP Loaded: {}", stats.loaded);
     println!("  Registered Ports: {}", stats.registered_ports);
+    println!("  Packets: {} ({} bytes)", stats.packets, stats.bytes);
 
     // Keep running until Ctrl+C
     if manager.is_loaded() {
@@ -381,7 +762,68 @@ mod tests {
         manager.register_port(port, pid, None).await.unwrap();
 
         let lookup = manager.lookup_port(port).await;
-        assert_eq!(lookup, Some(1234));
+        assert_eq!(lookup, Some((1234, IpAddr::V4(Ipv4Addr::LOCALHOST))));
+    }
+
+    #[tokio::test]
+    async fn test_port_registration_ipv6() {
+        let mut manager = XdpManager::new("lo");
+
+        let port = Port::new(8080).unwrap();
+        let pid = ProcessId::new(1234).unwrap();
+        let addr: IpAddr = "fd00::1".parse().unwrap();
+
+        manager.register_port(port, pid, Some(addr)).await.unwrap();
+
+        assert_eq!(manager.lookup_port(port).await, Some((1234, addr)));
+    }
+
+    #[test]
+    fn test_port_value_layout() {
+        assert_eq!(std::mem::size_of::<PortValue>(), 24);
+        assert_eq!(std::mem::offset_of!(PortValue, family), 4);
+        assert_eq!(std::mem::offset_of!(PortValue, addr), 8);
+
+        let v4 = PortValue::v4(7, Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(v4.family, ADDR_FAMILY_V4);
+        assert_eq!(&v4.addr[..4], &[10, 0, 0, 1]);
+        assert!(v4.addr[4..].iter().all(|&b| b == 0));
+        assert_eq!(v4.ip(), IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+
+        let addr: Ipv6Addr = "2001:db8::42".parse().unwrap();
+        let v6 = PortValue::new(7, IpAddr::V6(addr));
+        assert_eq!(v6.family, ADDR_FAMILY_V6);
+        assert_eq!(v6.addr, addr.octets());
+        assert_eq!(v6.ip(), IpAddr::V6(addr));
+    }
+
+    #[tokio::test]
+    async fn test_register_ports_batch() {
+        let mut manager = XdpManager::new("lo");
+
+        let v6: IpAddr = "::1".parse().unwrap();
+        let entries: Vec<_> = (0..100u16)
+            .map(|i| {
+                let addr = if i % 2 == 0 { None } else { Some(v6) };
+                (
+                    Port::new(8000 + i).unwrap(),
+                    ProcessId::new(1000 + u32::from(i)).unwrap(),
+                    addr,
+                )
+            })
+            .collect();
+
+        manager.register_ports(&entries).await.unwrap();
+
+        assert_eq!(manager.list_ports().await.len(), 100);
+        assert_eq!(
+            manager.lookup_port(Port::new(8000).unwrap()).await,
+            Some((1000, IpAddr::V4(Ipv4Addr::LOCALHOST)))
+        );
+        assert_eq!(
+            manager.lookup_port(Port::new(8099).unwrap()).await,
+            Some((1099, v6))
+        );
     }
 
     #[tokio::test]
@@ -429,5 +871,127 @@ mod tests {
         assert_eq!(stats.registered_ports, 1);
         assert!(!stats.loaded);
         assert!(stats.ports.contains(&3000));
+        assert_eq!(stats.packets, 0);
+        assert_eq!(stats.bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_packet_stats_without_program() {
+        let mut manager = XdpManager::new("lo");
+        manager
+            .register_port(Port::new(8080).unwrap(), ProcessId::new(100).unwrap(), None)
+            .await
+            .unwrap();
+
+        assert!(manager.packet_stats().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_load_program_bytes_malformed() {
+        let mut manager = XdpManager::new("lo");
+
+        let result = manager.load_program_bytes(
+            b"definitely not an ELF object",
+            "xdp_redirect",
+            XdpAttachMode::Auto,
+        );
+        assert!(matches!(
+            result,
+            Err(AetherError::Ebpf(EbpfError::LoadFailed { .. }))
+        ));
+        assert!(!manager.is_loaded());
+    }
+
+    #[test]
+    fn test_load_program_missing_file() {
+        let mut manager = XdpManager::new("lo");
+
+        let result = manager.load_program(
+            "/nonexistent/xdp_redirect.o",
+            "xdp_redirect",
+            XdpAttachMode::Native,
+        );
+        assert!(matches!(
+            result,
+            Err(AetherError::Ebpf(EbpfError::LoadFailed { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_without_program() {
+        let mut manager = XdpManager::new("lo");
+        manager
+            .register_port(Port::new(8080).unwrap(), ProcessId::new(100).unwrap(), None)
+            .await
+            .unwrap();
+
+        let report = manager.reconcile(true).await.unwrap();
+        assert!(report.is_consistent());
+        assert!(!report.repaired);
+    }
+
+    #[test]
+    fn test_reconcile_report_compare() {
+        let localhost = Ipv4Addr::LOCALHOST;
+        let mirror = HashMap::from([
+            (8080, PortValue::v4(100, localhost)),
+            (8081, PortValue::v4(101, localhost)),
+            (8082, PortValue::v4(102, localhost)),
+        ]);
+        let kernel = HashMap::from([
+            (8080, PortValue::v4(100, localhost)),
+            (8081, PortValue::v4(999, localhost)),
+            (9000, PortValue::v4(200, localhost)),
+        ]);
+
+        let report = ReconcileReport::compare(&mirror, &kernel);
+        assert_eq!(report.added, vec![8082]);
+        assert_eq!(report.removed, vec![9000]);
+        assert_eq!(report.divergent, vec![8081]);
+        assert!(!report.is_consistent());
+        assert!(ReconcileReport::compare(&mirror, &mirror).is_consistent());
+    }
+
+    #[test]
+    fn test_attach_flags() {
+        assert_eq!(
+            attach_flags(XdpAttachMode::Auto).bits(),
+            XdpFlags::default().bits()
+        );
+        assert_eq!(
+            attach_flags(XdpAttachMode::Generic).bits(),
+            XdpFlags::SKB_MODE.bits()
+        );
+        assert_eq!(
+            attach_flags(XdpAttachMode::Native).bits(),
+            XdpFlags::DRV_MODE.bits()
+        );
+        assert_eq!(
+            attach_flags(XdpAttachMode::Offload).bits(),
+            XdpFlags::HW_MODE.bits()
+        );
+    }
+
+    #[test]
+    fn test_packet_counters_sum() {
+        let per_cpu = [
+            PacketCounters {
+                packets: 3,
+                bytes: 180,
+            },
+            PacketCounters::default(),
+            PacketCounters {
+                packets: 2,
+                bytes: 1500,
+            },
+        ];
+
+        assert_eq!(
+            PacketCounters::sum(&per_cpu),
+            PacketCounters {
+                packets: 5,
+                bytes: 1680,
+            }
+        );
     }
 }
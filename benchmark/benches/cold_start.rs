@@ -4,14 +4,19 @@
 //! Cold start latency benchmarks.
 //!
 //! Measures cold start performance of Aetherless compared to baseline approaches.
-//! Key metrics: process spawn time, initialization time, time to READY signal.
+//! Key metrics: process spawn time, initialization time, time to READY signal,
+//! and time to first response after an eager or lazy CRIU restore.
 
 use aetherless_benchmark::{
     harness::BenchmarkHarness, BenchmarkCategory, BenchmarkReport, BenchmarkResult, JsonReporter,
 };
+use aetherless_core::criu::{CriuOptions, SnapshotManager};
+use aetherless_core::{FunctionId, RestoreMode};
 use criterion::{criterion_group, criterion_main, Criterion};
-use std::io::Read;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
 use std::os::unix::net::UnixListener;
+use std::path::Path;
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 use tempfile::TempDir;
@@ -149,6 +154,157 @@ fn bench_nodejs_process_spawn(c: &mut Criterion) {
     });
 }
 
+/// HTTP handler for the CRIU restore benchmark. It touches 64 MiB before
+/// serving, so a lazy restore has pages left to fault in afterwards.
+const RESTORE_HANDLER: &str = r#"import sys
+from http.server import HTTPServer, BaseHTTPRequestHandler
+
+heap = bytearray(64 * 1024 * 1024)
+for i in range(0, len(heap), 4096):
+    heap[i] = 1
+
+class Handler(BaseHTTPRequestHandler):
+    def do_GET(self):
+        self.send_response(200)
+        self.send_header('Content-Length', '2')
+        self.end_headers()
+        self.wfile.write(b'ok')
+    def log_message(self, *args): pass
+
+HTTPServer(('127.0.0.1', int(sys.argv[1])), Handler).serve_forever()
+"#;
+
+/// Whether the handler on `port` answers a GET within `timeout`.
+fn first_response(port: u16, timeout: Duration) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if let Ok(mut stream) = TcpStream::connect(("127.0.0.1", port)) {
+            let mut response = Vec::new();
+            if stream.write_all(b"GET / HTTP/1.0\r\n\r\n").is_ok()
+                && stream.read_to_end(&mut response).is_ok()
+                && response.starts_with(b"HTTP/1.0 200")
+            {
+                return true;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    false
+}
+
+/// A handler dumped with CRIU, ready to be restored repeatedly.
+struct DumpedHandler {
+    manager: SnapshotManager,
+    function_id: FunctionId,
+    port: u16,
+    _dir: TempDir,
+}
+
+impl DumpedHandler {
+    /// Start the handler and dump it. Fails without CRIU or root.
+    fn new() -> Result<Self, String> {
+        let dir = tempfile::Builder::new()
+            .tempdir_in("/dev/shm")
+            .or_else(|_| TempDir::new())
+            .map_err(|e| e.to_string())?;
+        let handler = dir.path().join("handler.py");
+        std::fs::write(&handler, RESTORE_HANDLER).map_err(|e| e.to_string())?;
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .map_err(|e| e.to_string())?
+            .port();
+
+        let mut manager =
+            SnapshotManager::new(dir.path().join("snapshots"), 10_000, CriuOptions::default())
+                .map_err(|e| e.to_string())?;
+        let function_id = FunctionId::new("bench-restore").map_err(|e| e.to_string())?;
+
+        let mut child = Command::new("python3")
+            .arg(&handler)
+            .arg(port.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        if !first_response(port, Duration::from_secs(10)) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("handler did not start".to_string());
+        }
+
+        let dumped = manager.dump(&function_id, child.id());
+        // CRIU kills the handler after dumping; reap it so its PID is
+        // free for the restores
+        let _ = child.kill();
+        let _ = child.wait();
+        dumped.map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            manager,
+            function_id,
+            port,
+            _dir: dir,
+        })
+    }
+
+    /// Restore the handler and time it until its first response.
+    fn restore_until_response(&mut self) -> Duration {
+        let start = Instant::now();
+        let pid = self
+            .manager
+            .restore(&self.function_id, 0)
+            .expect("CRIU restore failed");
+        assert!(
+            first_response(self.port, Duration::from_secs(10)),
+            "restored handler did not respond"
+        );
+        let elapsed = start.elapsed();
+
+        // The next restore needs the PID back
+        let _ = Command::new("kill").arg("-9").arg(pid.to_string()).status();
+        let proc_dir = format!("/proc/{}", pid);
+        while Path::new(&proc_dir).exists() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        elapsed
+    }
+}
+
+/// Benchmark first-response latency after an eager vs a lazy CRIU restore.
+fn bench_criu_restore_modes(c: &mut Criterion) {
+    let mut handler = match DumpedHandler::new() {
+        Ok(handler) => handler,
+        Err(e) => {
+            println!("CRIU restore unavailable ({}), skipping benchmark", e);
+            return;
+        }
+    };
+
+    let mut group = c.benchmark_group("cold_start_criu_restore");
+    group.sample_size(20);
+
+    for (name, mode) in [("eager", RestoreMode::Eager), ("lazy", RestoreMode::Lazy)] {
+        let function_id = handler.function_id.clone();
+        handler.manager.set_restore_mode(&function_id, mode);
+        let effective = handler
+            .manager
+            .prepare_restore(&function_id)
+            .map(|job| job.mode());
+        if effective.ok() != Some(mode) {
+            println!("{} restore unavailable, skipping", name);
+            continue;
+        }
+
+        group.bench_function(name, |b| {
+            b.iter_custom(|iters| (0..iters).map(|_| handler.restore_until_response()).sum());
+        });
+    }
+
+    group.finish();
+}
+
 /// Generate JSON report with cold start data.
 #[allow(dead_code)]
 fn generate_json_report() {
@@ -214,6 +370,7 @@ criterion_group!(
     bench_python_process_spawn,
     bench_python_http_cold_start,
     bench_nodejs_process_spawn,
+    bench_criu_restore_modes,
 );
 
 criterion_main!(benches);
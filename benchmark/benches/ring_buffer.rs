@@ -14,6 +14,9 @@ use std::time::Duration;
 /// Payload sizes to benchmark (in bytes).
 const PAYLOAD_SIZES: &[usize] = &[64, 256, 1024, 4096, 16384, 65536];
 
+/// How long each sustained-throughput run lasts.
+const THROUGHPUT_DURATION: Duration = Duration::from_secs(1);
+
 /// Benchmark ring buffer write operations.
 fn bench_ring_buffer_write(c: &mut Criterion) {
     let mut group = c.benchmark_group("ring_buffer_write");
@@ -66,6 +69,38 @@ fn bench_ring_buffer_read(c: &mut Criterion) {
     group.finish();
 }
 
+/// Compare allocating `read` against buffer-reusing `read_into` at small payloads.
+fn bench_ring_buffer_read_into(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ring_buffer_read_vs_read_into");
+    group.measurement_time(Duration::from_secs(5));
+
+    let size = 64;
+    group.throughput(Throughput::Bytes(size as u64));
+
+    let name = format!("bench_read_into_{}", std::process::id());
+    let region =
+        SharedMemoryRegion::create(&name, 1024 * 1024).expect("Failed to create SHM region");
+    let buffer = RingBuffer::new(region).expect("Failed to create ring buffer");
+    let payload = vec![0xABu8; size];
+
+    group.bench_function(BenchmarkId::new("read", size), |b| {
+        b.iter(|| {
+            buffer.write(&payload).expect("Write failed");
+            black_box(buffer.read().expect("Read failed"));
+        });
+    });
+
+    let mut out = Vec::with_capacity(size);
+    group.bench_function(BenchmarkId::new("read_into", size), |b| {
+        b.iter(|| {
+            buffer.write(&payload).expect("Write failed");
+            black_box(buffer.read_into(&mut out).expect("Read failed"));
+        });
+    });
+
+    group.finish();
+}
+
 /// Benchmark full write-read cycle latency.
 fn bench_ring_buffer_roundtrip(c: &mut Criterion) {
     let mut group = c.benchmark_group("ring_buffer_roundtrip");
@@ -92,6 +127,65 @@ fn bench_ring_buffer_roundtrip(c: &mut Criterion) {
     group.finish();
 }
 
+/// Measure sustained write+read throughput at one payload size.
+///
+/// Each operation writes a payload and reads it back, so the buffer never
+/// fills up; both directions count towards the byte total.
+fn measure_throughput(size: usize, duration: Duration) -> BenchmarkResult {
+    use aetherless_benchmark::harness::BenchmarkHarness;
+
+    let name = format!("bench_tp_{}_{}", size, std::process::id());
+    let region =
+        SharedMemoryRegion::create(&name, 1024 * 1024).expect("Failed to create SHM region");
+    let buffer = RingBuffer::new(region).expect("Failed to create ring buffer");
+    let payload = vec![0xABu8; size];
+    let bytes_per_op = size as u64 * 2;
+
+    let harness = BenchmarkHarness::new().warmup(100);
+    let (operations, duration_ns) = harness.run_throughput(duration, || {
+        buffer.write(black_box(&payload)).expect("Write failed");
+        black_box(buffer.read().expect("Read failed"));
+        bytes_per_op
+    });
+
+    BenchmarkResult::throughput(
+        format!("ring_buffer_throughput_{}", size),
+        BenchmarkCategory::RingBuffer,
+        operations,
+        operations * bytes_per_op,
+        duration_ns,
+    )
+    .with_metadata("payload_size_bytes", size)
+}
+
+/// Sustained throughput at each payload size, saved as a JSON report.
+///
+/// Criterion measures per-iteration time; this drives `run_throughput` for
+/// a fixed duration instead, to report MB/s over a continuous stream.
+fn bench_ring_buffer_throughput(_c: &mut Criterion) {
+    use aetherless_benchmark::metrics::ThroughputMetrics;
+
+    let mut report = BenchmarkReport::new();
+    for &size in PAYLOAD_SIZES {
+        let result = measure_throughput(size, THROUGHPUT_DURATION);
+        if let Some(throughput) = &result.throughput {
+            println!(
+                "ring_buffer_throughput/{}: {} ({:.0} ops/s)",
+                size,
+                ThroughputMetrics::format_bytes_per_sec(throughput.bytes_per_sec),
+                throughput.messages_per_sec
+            );
+        }
+        report.add_result(result);
+    }
+
+    if let Ok(reporter) = JsonReporter::default_location() {
+        if let Ok(path) = reporter.save(&report) {
+            println!("Saved throughput report to: {:?}", path);
+        }
+    }
+}
+
 /// Benchmark CRC32 checksum calculation overhead.
 fn bench_crc32_overhead(c: &mut Criterion) {
     let mut group = c.benchmark_group("crc32_overhead");
@@ -156,7 +250,9 @@ criterion_group!(
     benches,
     bench_ring_buffer_write,
     bench_ring_buffer_read,
+    bench_ring_buffer_read_into,
     bench_ring_buffer_roundtrip,
+    bench_ring_buffer_throughput,
     bench_crc32_overhead,
 );
 
@@ -180,4 +276,15 @@ mod tests {
         let result = buffer.read().expect("Read failed");
         assert_eq!(result, payload);
     }
+
+    #[test]
+    fn test_measure_throughput() {
+        let result = measure_throughput(1024, Duration::from_millis(50));
+
+        let throughput = result.throughput.expect("throughput metrics");
+        assert!(throughput.total_messages > 0);
+        assert_eq!(throughput.total_bytes, throughput.total_messages * 2048);
+        assert!(throughput.bytes_per_sec > 0.0);
+        assert!(result.latency.is_none());
+    }
 }
@@ -3,9 +3,11 @@
 
 //! CLI tool to run all benchmarks and generate reports.
 
-use aetherless_benchmark::{BenchmarkCategory, BenchmarkReport, BenchmarkResult, JsonReporter};
-use clap::Parser;
-use std::path::PathBuf;
+use aetherless_benchmark::{
+    BenchmarkCategory, BenchmarkReport, BenchmarkResult, JsonReporter, MarkdownReporter,
+};
+use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "run_benchmarks")]
@@ -26,11 +28,43 @@ struct Args {
     /// Run in quick mode (fewer iterations)
     #[arg(long)]
     quick: bool,
+
+    /// Also write a Markdown summary next to the JSON report
+    #[arg(long)]
+    markdown: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compare a report against a baseline and exit non-zero on regressions
+    Compare {
+        /// Baseline JSON report
+        baseline: PathBuf,
+
+        /// Current JSON report
+        current: PathBuf,
+
+        /// Allowed increase in median/p99 latency, in percent
+        #[arg(short, long, default_value_t = 10.0)]
+        threshold: f64,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    if let Some(Command::Compare {
+        baseline,
+        current,
+        threshold,
+    }) = args.command
+    {
+        return compare_reports(&baseline, &current, threshold);
+    }
+
     let iterations = if args.quick { 10 } else { args.iterations };
 
     println!("Aetherless Benchmark Suite");
@@ -71,6 +105,10 @@ fn main() -> anyhow::Result<()> {
     let path = reporter.save(&report)?;
     println!();
     println!("Benchmark report saved to: {:?}", path);
+    if args.markdown {
+        let path = MarkdownReporter::new(&args.output)?.save(&report)?;
+        println!("Markdown summary saved to: {:?}", path);
+    }
     println!();
 
     // Print summary
@@ -208,6 +246,40 @@ fn run_ipc_benchmarks(report: &mut BenchmarkReport, iterations: u64) {
     }
 }
 
+fn compare_reports(baseline: &Path, current: &Path, threshold: f64) -> anyhow::Result<()> {
+    use aetherless_benchmark::LatencyMetrics;
+
+    let baseline = JsonReporter::load(baseline)?;
+    let current = JsonReporter::load(current)?;
+
+    let unmatched = current.unmatched(&baseline);
+    for name in &unmatched.added {
+        println!("new:     {}", name);
+    }
+    for name in &unmatched.removed {
+        println!("removed: {}", name);
+    }
+
+    let regressions = current.compare(&baseline, threshold);
+    if regressions.is_empty() {
+        println!("No regressions beyond {}%", threshold);
+        return Ok(());
+    }
+
+    println!("Regressions beyond {}%:", threshold);
+    for r in &regressions {
+        println!(
+            "  {} {}: {} -> {} (+{:.1}%)",
+            r.name,
+            r.metric,
+            LatencyMetrics::format_latency(r.baseline_ns),
+            LatencyMetrics::format_latency(r.current_ns),
+            r.change_pct
+        );
+    }
+    anyhow::bail!("{} latency regression(s) detected", regressions.len())
+}
+
 fn print_summary(report: &BenchmarkReport) {
     use aetherless_benchmark::LatencyMetrics;
 
@@ -6,8 +6,12 @@
 //! Provides utilities for measuring execution time with high precision
 //! and collecting samples for statistical analysis.
 
+use std::sync::Barrier;
+use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::hdr::HdrLatencyRecorder;
+
 /// A benchmark harness for measuring operation latency.
 pub struct BenchmarkHarness {
     /// Number of warmup iterations before measurement
@@ -71,6 +75,73 @@ impl BenchmarkHarness {
         samples
     }
 
+    /// Like [`run`](Self::run), but record into a histogram instead of
+    /// keeping every sample, for runs too long to hold in memory.
+    pub fn run_hdr<F>(&self, mut operation: F) -> HdrLatencyRecorder
+    where
+        F: FnMut(),
+    {
+        for _ in 0..self.warmup_iterations {
+            operation();
+        }
+
+        let mut recorder = HdrLatencyRecorder::new();
+        for _ in 0..self.measurement_iterations {
+            let start = Instant::now();
+            operation();
+            recorder.record_duration(start.elapsed());
+        }
+
+        recorder
+    }
+
+    /// Run a benchmark on several threads at once to measure contention.
+    ///
+    /// `op_factory` is called with each thread index (`0..threads`) to build
+    /// that thread's operation, so workers can bind their own resources.
+    /// Every worker runs its warmup, waits for the others, then times
+    /// `measurement_iterations` operations. Returns all samples, in thread
+    /// order; the length is `threads * measurement_iterations`.
+    pub fn run_concurrent<F, Factory>(&self, threads: usize, op_factory: Factory) -> Vec<u64>
+    where
+        F: FnMut(),
+        Factory: Fn(usize) -> F + Sync,
+    {
+        let barrier = Barrier::new(threads);
+
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|index| {
+                    let barrier = &barrier;
+                    let op_factory = &op_factory;
+                    scope.spawn(move || {
+                        let mut operation = op_factory(index);
+
+                        // Warmup phase
+                        for _ in 0..self.warmup_iterations {
+                            operation();
+                        }
+                        barrier.wait();
+
+                        // Measurement phase
+                        let mut samples = Vec::with_capacity(self.measurement_iterations as usize);
+                        for _ in 0..self.measurement_iterations {
+                            let start = Instant::now();
+                            operation();
+                            samples.push(start.elapsed().as_nanos() as u64);
+                        }
+                        samples
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("benchmark worker panicked"))
+                .collect()
+        })
+    }
+
     /// Run a benchmark with setup and teardown phases.
     ///
     /// Setup is called before each iteration, teardown after.
@@ -198,7 +269,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::thread;
+    use std::sync::atomic::{AtomicU64, Ordering};
 
     #[test]
     fn test_harness_basic() {
@@ -215,6 +286,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_hdr() {
+        let harness = BenchmarkHarness::new().warmup(5).iterations(20);
+        let mut calls = 0;
+
+        let recorder = harness.run_hdr(|| {
+            calls += 1;
+            thread::sleep(Duration::from_micros(100));
+        });
+
+        assert_eq!(calls, 25);
+        assert_eq!(recorder.len(), 20);
+        assert!(recorder.to_metrics().min_ns >= 100_000);
+    }
+
+    #[test]
+    fn test_run_concurrent() {
+        let harness = BenchmarkHarness::new().warmup(3).iterations(25);
+        let calls: Vec<AtomicU64> = (0..4).map(|_| AtomicU64::new(0)).collect();
+
+        let samples = harness.run_concurrent(4, |index| {
+            let calls = &calls[index];
+            move || {
+                calls.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        assert_eq!(samples.len(), 4 * 25);
+        // Each worker ran its own warmup plus measurement iterations
+        for counter in &calls {
+            assert_eq!(counter.load(Ordering::Relaxed), 3 + 25);
+        }
+    }
+
     #[test]
     fn test_timer() {
         let timer = Timer::start();
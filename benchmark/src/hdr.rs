@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2025 Ankit Kumar Pandey
+
+//! Streaming latency recording with a high-dynamic-range histogram.
+//!
+//! [`LatencyMetrics::from_samples`] keeps every sample, which does not scale
+//! to soak runs of millions of iterations. [`HdrLatencyRecorder`] counts
+//! samples into log-linear buckets instead: values below 2048ns are exact,
+//! larger ones are bucketed with under 0.1% relative error, and memory stays
+//! fixed at a few hundred KiB however many samples are recorded.
+
+use std::time::Duration;
+
+use crate::metrics::LatencyMetrics;
+
+/// Bits of sub-bucket resolution; 2^11 sub-buckets gives 3 significant digits.
+const SUB_BUCKET_BITS: u32 = 11;
+
+/// Values below this are counted exactly.
+const SUB_BUCKET_COUNT: usize = 1 << SUB_BUCKET_BITS;
+
+/// Sub-buckets added by each further power of two.
+const HALF_COUNT: usize = SUB_BUCKET_COUNT / 2;
+
+/// Buckets needed to cover all of `u64`.
+const BUCKET_COUNT: usize = (64 - SUB_BUCKET_BITS as usize + 1) * HALF_COUNT + HALF_COUNT;
+
+/// Records latencies online and summarizes them as [`LatencyMetrics`]
+/// without retaining raw samples.
+#[derive(Debug, Clone)]
+pub struct HdrLatencyRecorder {
+    counts: Vec<u64>,
+    total: u64,
+    min_ns: u64,
+    max_ns: u64,
+    /// Running mean and sum of squared deviations (Welford)
+    mean_ns: f64,
+    m2: f64,
+}
+
+impl HdrLatencyRecorder {
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; BUCKET_COUNT],
+            total: 0,
+            min_ns: u64::MAX,
+            max_ns: 0,
+            mean_ns: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// Record one latency in nanoseconds.
+    pub fn record(&mut self, ns: u64) {
+        self.counts[bucket_index(ns)] += 1;
+        self.total += 1;
+        self.min_ns = self.min_ns.min(ns);
+        self.max_ns = self.max_ns.max(ns);
+
+        let delta = ns as f64 - self.mean_ns;
+        self.mean_ns += delta / self.total as f64;
+        self.m2 += delta * (ns as f64 - self.mean_ns);
+    }
+
+    /// Record one latency.
+    pub fn record_duration(&mut self, latency: Duration) {
+        self.record(latency.as_nanos().min(u64::MAX as u128) as u64);
+    }
+
+    /// Add every sample recorded by `other`, e.g. from another thread.
+    pub fn merge(&mut self, other: &Self) {
+        if other.total == 0 {
+            return;
+        }
+        for (count, added) in self.counts.iter_mut().zip(&other.counts) {
+            *count += added;
+        }
+
+        // Chan et al.'s parallel combination of mean and variance
+        let total = self.total + other.total;
+        let delta = other.mean_ns - self.mean_ns;
+        self.m2 +=
+            other.m2 + delta * delta * (self.total as f64 * other.total as f64) / total as f64;
+        self.mean_ns += delta * other.total as f64 / total as f64;
+        self.total = total;
+        self.min_ns = self.min_ns.min(other.min_ns);
+        self.max_ns = self.max_ns.max(other.max_ns);
+    }
+
+    /// Number of samples recorded.
+    pub fn len(&self) -> u64 {
+        self.total
+    }
+
+    /// Whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Latency at quantile `q` (0.0-1.0), using the same rank as
+    /// [`LatencyMetrics::from_samples`]. 0 when empty.
+    pub fn value_at_quantile(&self, q: f64) -> u64 {
+        if self.total == 0 {
+            return 0;
+        }
+        let last = self.total - 1;
+        let rank = ((q.clamp(0.0, 1.0) * last as f64).round() as u64).min(last);
+
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen > rank {
+                return highest_equivalent(index).clamp(self.min_ns, self.max_ns);
+            }
+        }
+        self.max_ns
+    }
+
+    /// Summarize the recorded samples. Raw samples are not available, so
+    /// `samples` is `None` and nothing is trimmed.
+    pub fn to_metrics(&self) -> LatencyMetrics {
+        if self.total == 0 {
+            return LatencyMetrics::from_samples(Vec::new(), false);
+        }
+
+        LatencyMetrics {
+            min_ns: self.min_ns,
+            max_ns: self.max_ns,
+            mean_ns: self.mean_ns,
+            median_ns: self.value_at_quantile(0.50),
+            p95_ns: self.value_at_quantile(0.95),
+            p99_ns: self.value_at_quantile(0.99),
+            p999_ns: self.value_at_quantile(0.999),
+            std_dev_ns: (self.m2 / self.total as f64).sqrt(),
+            trim_fraction: 0.0,
+            samples: None,
+        }
+    }
+}
+
+impl Default for HdrLatencyRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bucket counting `value`.
+fn bucket_index(value: u64) -> usize {
+    if value < SUB_BUCKET_COUNT as u64 {
+        return value as usize;
+    }
+    let msb = 63 - value.leading_zeros();
+    let shift = msb - (SUB_BUCKET_BITS - 1);
+    shift as usize * HALF_COUNT + (value >> shift) as usize
+}
+
+/// Largest value counted by bucket `index`.
+fn highest_equivalent(index: usize) -> u64 {
+    if index < SUB_BUCKET_COUNT {
+        return index as u64;
+    }
+    let shift = (index / HALF_COUNT - 1) as u32;
+    let sub = (index - shift as usize * HALF_COUNT) as u64;
+    (sub << shift) + ((1u64 << shift) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_bounds() {
+        for value in [0, 1, 2047, 2048, 2049, 4095, 4096, 1_000_000, u64::MAX] {
+            let index = bucket_index(value);
+            assert!(index < BUCKET_COUNT);
+            let high = highest_equivalent(index);
+            assert!(high >= value, "{} -> {}", value, high);
+            // Under 0.1% relative error
+            assert!(
+                (high - value) as f64 <= value as f64 / 1024.0,
+                "{} -> {}",
+                value,
+                high
+            );
+        }
+        assert_eq!(highest_equivalent(bucket_index(2048)), 2049);
+        assert_eq!(bucket_index(u64::MAX), BUCKET_COUNT - 1);
+    }
+
+    #[test]
+    fn test_matches_exact_metrics() {
+        // Spread over several orders of magnitude, with a slow tail
+        let samples: Vec<u64> = (0..100_000u64)
+            .map(|i| 1_000 + (i * 7_919) % 50_000 + if i % 997 == 0 { 5_000_000 } else { 0 })
+            .collect();
+
+        let mut recorder = HdrLatencyRecorder::new();
+        for &ns in &samples {
+            recorder.record(ns);
+        }
+        let hdr = recorder.to_metrics();
+        let exact = LatencyMetrics::from_samples(samples, false);
+
+        assert_eq!(hdr.min_ns, exact.min_ns);
+        assert_eq!(hdr.max_ns, exact.max_ns);
+        assert!((hdr.mean_ns - exact.mean_ns).abs() < 1e-6 * exact.mean_ns);
+        assert!((hdr.std_dev_ns - exact.std_dev_ns).abs() < 1e-6 * exact.std_dev_ns);
+        for (approx, exact) in [
+            (hdr.median_ns, exact.median_ns),
+            (hdr.p95_ns, exact.p95_ns),
+            (hdr.p99_ns, exact.p99_ns),
+            (hdr.p999_ns, exact.p999_ns),
+        ] {
+            assert!(approx >= exact);
+            assert!((approx - exact) as f64 <= exact as f64 / 1024.0);
+        }
+        assert!(hdr.samples.is_none());
+    }
+
+    #[test]
+    fn test_empty_and_merge() {
+        let empty = HdrLatencyRecorder::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.to_metrics().p99_ns, 0);
+
+        let mut a = HdrLatencyRecorder::new();
+        let mut b = HdrLatencyRecorder::new();
+        let mut both = HdrLatencyRecorder::new();
+        for ns in 1..=1000u64 {
+            let target = if ns % 3 == 0 { &mut a } else { &mut b };
+            target.record(ns * 10);
+            both.record(ns * 10);
+        }
+        a.merge(&b);
+        a.merge(&empty);
+
+        let (merged, expected) = (a.to_metrics(), both.to_metrics());
+        assert_eq!(a.len(), 1000);
+        assert_eq!(merged.min_ns, expected.min_ns);
+        assert_eq!(merged.max_ns, expected.max_ns);
+        assert_eq!(merged.p99_ns, expected.p99_ns);
+        assert!((merged.mean_ns - expected.mean_ns).abs() < 1e-9);
+        assert!((merged.std_dev_ns - expected.std_dev_ns).abs() < 1e-6);
+
+        let mut timed = HdrLatencyRecorder::default();
+        timed.record_duration(Duration::from_micros(3));
+        assert_eq!(timed.to_metrics().median_ns, 3_000);
+    }
+}
@@ -15,14 +15,20 @@
 //!
 //! # Data Output
 //!
-//! All benchmarks output JSON files with standardized metrics for visualization.
+//! All benchmarks output JSON files with standardized metrics for visualization;
+//! `CsvReporter` and `MarkdownReporter` write the same results as flat CSV
+//! and as Markdown tables. Long soak runs can record into an
+//! `HdrLatencyRecorder` instead of keeping every sample.
 
 pub mod harness;
+pub mod hdr;
 pub mod metrics;
 pub mod reporter;
 
 pub use harness::BenchmarkHarness;
+pub use hdr::HdrLatencyRecorder;
 pub use metrics::{
-    BenchmarkCategory, BenchmarkReport, BenchmarkResult, LatencyMetrics, SystemInfo,
+    BenchmarkCategory, BenchmarkReport, BenchmarkResult, LatencyMetrics, Regression,
+    RegressionMetric, SystemInfo, UnmatchedResults,
 };
-pub use reporter::JsonReporter;
+pub use reporter::{CsvReporter, JsonReporter, MarkdownReporter};
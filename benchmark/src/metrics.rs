@@ -56,8 +56,15 @@ pub struct LatencyMetrics {
     pub p95_ns: u64,
     /// 99th percentile latency in nanoseconds
     pub p99_ns: u64,
+    /// 99.9th percentile latency in nanoseconds
+    #[serde(default)]
+    pub p999_ns: u64,
     /// Standard deviation in nanoseconds
     pub std_dev_ns: f64,
+    /// Fraction of samples discarded from each end before computing
+    /// `mean_ns` and `std_dev_ns` (0.0 when untrimmed)
+    #[serde(default)]
+    pub trim_fraction: f64,
     /// Raw sample data for visualization (optional, may be truncated)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub samples: Option<Vec<u64>>,
@@ -65,7 +72,23 @@ pub struct LatencyMetrics {
 
 impl LatencyMetrics {
     /// Calculate metrics from a vector of latency samples (in nanoseconds).
-    pub fn from_samples(mut samples: Vec<u64>, keep_raw: bool) -> Self {
+    pub fn from_samples(samples: Vec<u64>, keep_raw: bool) -> Self {
+        Self::from_samples_trimmed(samples, 0.0, keep_raw)
+    }
+
+    /// Like [`LatencyMetrics::from_samples`], but discard the lowest and
+    /// highest `trim_pct` percent of samples before computing the mean and
+    /// standard deviation.
+    ///
+    /// Min, max and percentiles are still computed over all samples.
+    /// `trim_pct` is clamped to `0.0..50.0`; at least one sample is kept.
+    pub fn from_samples_trimmed(mut samples: Vec<u64>, trim_pct: f64, keep_raw: bool) -> Self {
+        let trim_fraction = if trim_pct.is_finite() {
+            trim_pct.clamp(0.0, 49.999) / 100.0
+        } else {
+            0.0
+        };
+
         if samples.is_empty() {
             return Self {
                 min_ns: 0,
@@ -74,7 +97,9 @@ impl LatencyMetrics {
                 median_ns: 0,
                 p95_ns: 0,
                 p99_ns: 0,
+                p999_ns: 0,
                 std_dev_ns: 0.0,
+                trim_fraction,
                 samples: None,
             };
         }
@@ -84,21 +109,25 @@ impl LatencyMetrics {
 
         let min_ns = samples[0];
         let max_ns = samples[len - 1];
-        let sum: u64 = samples.iter().sum();
-        let mean_ns = sum as f64 / len as f64;
-        let median_ns = samples[len / 2];
-        let p95_ns = samples[(len as f64 * 0.95) as usize];
-        let p99_ns = samples[(len as f64 * 0.99) as usize];
-
-        // Calculate standard deviation
-        let variance: f64 = samples
+        let median_ns = percentile(&samples, 0.50);
+        let p95_ns = percentile(&samples, 0.95);
+        let p99_ns = percentile(&samples, 0.99);
+        let p999_ns = percentile(&samples, 0.999);
+
+        // Mean and standard deviation over the trimmed samples
+        let cut = ((len as f64 * trim_fraction) as usize).min((len - 1) / 2);
+        let kept = &samples[cut..len - cut];
+        let sum: u64 = kept.iter().sum();
+        let mean_ns = sum as f64 / kept.len() as f64;
+
+        let variance: f64 = kept
             .iter()
             .map(|&x| {
                 let diff = x as f64 - mean_ns;
                 diff * diff
             })
             .sum::<f64>()
-            / len as f64;
+            / kept.len() as f64;
         let std_dev_ns = variance.sqrt();
 
         // Optionally keep raw samples (truncate if too large for visualization)
@@ -120,7 +149,9 @@ impl LatencyMetrics {
             median_ns,
             p95_ns,
             p99_ns,
+            p999_ns,
             std_dev_ns,
+            trim_fraction,
             samples: raw_samples,
         }
     }
@@ -139,6 +170,17 @@ impl LatencyMetrics {
     }
 }
 
+/// Value at quantile `q` (0.0..=1.0) of non-empty, sorted samples.
+///
+/// Uses the nearest rank on a `0..len-1` scale, `round(q * (len - 1))`,
+/// so the index never exceeds `len - 1`. With one sample every quantile is
+/// that sample; with two, quantiles at or above 0.5 pick the larger one.
+fn percentile(sorted: &[u64], q: f64) -> u64 {
+    let last = sorted.len() - 1;
+    let rank = (q * last as f64).round() as usize;
+    sorted[rank.min(last)]
+}
+
 /// Throughput metrics for IPC and network benchmarks.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThroughputMetrics {
@@ -318,6 +360,116 @@ impl BenchmarkReport {
     pub fn add_result(&mut self, result: BenchmarkResult) {
         self.results.push(result);
     }
+
+    /// Find latency regressions relative to a baseline report.
+    ///
+    /// Results are matched by name and their `median_ns` and `p99_ns` are
+    /// compared. A metric regresses when it grew by more than `threshold_pct`
+    /// percent. Results without latency metrics, or with a zero baseline,
+    /// are skipped; see [`BenchmarkReport::unmatched`] for names present in
+    /// only one report.
+    pub fn compare(&self, baseline: &BenchmarkReport, threshold_pct: f64) -> Vec<Regression> {
+        let baseline_latency: HashMap<&str, &LatencyMetrics> = baseline
+            .results
+            .iter()
+            .filter_map(|r| r.latency.as_ref().map(|l| (r.name.as_str(), l)))
+            .collect();
+
+        let mut regressions = Vec::new();
+        for result in &self.results {
+            let (Some(current), Some(base)) =
+                (&result.latency, baseline_latency.get(result.name.as_str()))
+            else {
+                continue;
+            };
+
+            for (metric, base_ns, current_ns) in [
+                (RegressionMetric::Median, base.median_ns, current.median_ns),
+                (RegressionMetric::P99, base.p99_ns, current.p99_ns),
+            ] {
+                if base_ns == 0 {
+                    continue;
+                }
+                let change_pct = (current_ns as f64 - base_ns as f64) / base_ns as f64 * 100.0;
+                if change_pct > threshold_pct {
+                    regressions.push(Regression {
+                        name: result.name.clone(),
+                        metric,
+                        baseline_ns: base_ns,
+                        current_ns,
+                        change_pct,
+                    });
+                }
+            }
+        }
+
+        regressions
+    }
+
+    /// Names of results present in only one of this report and `baseline`.
+    pub fn unmatched(&self, baseline: &BenchmarkReport) -> UnmatchedResults {
+        let names = |report: &BenchmarkReport| -> Vec<String> {
+            report.results.iter().map(|r| r.name.clone()).collect()
+        };
+        let current = names(self);
+        let previous = names(baseline);
+
+        UnmatchedResults {
+            added: current
+                .iter()
+                .filter(|n| !previous.contains(n))
+                .cloned()
+                .collect(),
+            removed: previous
+                .iter()
+                .filter(|n| !current.contains(n))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Latency metric compared during regression detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegressionMetric {
+    /// `median_ns`
+    Median,
+    /// `p99_ns`
+    P99,
+}
+
+impl std::fmt::Display for RegressionMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegressionMetric::Median => write!(f, "median"),
+            RegressionMetric::P99 => write!(f, "p99"),
+        }
+    }
+}
+
+/// A latency metric that grew beyond the allowed threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Regression {
+    /// Name of the benchmark
+    pub name: String,
+    /// Metric that regressed
+    pub metric: RegressionMetric,
+    /// Value in the baseline report, in nanoseconds
+    pub baseline_ns: u64,
+    /// Value in the current report, in nanoseconds
+    pub current_ns: u64,
+    /// Percent change from baseline (positive is slower)
+    pub change_pct: f64,
+}
+
+/// Benchmarks that could not be matched between two reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnmatchedResults {
+    /// Present only in the current report
+    pub added: Vec<String>,
+    /// Present only in the baseline report
+    pub removed: Vec<String>,
 }
 
 impl Default for BenchmarkReport {
@@ -342,6 +494,75 @@ mod tests {
         assert!(metrics.samples.is_none());
     }
 
+    #[test]
+    fn test_percentiles_single_and_two_samples() {
+        let one = LatencyMetrics::from_samples(vec![42], false);
+        assert_eq!(
+            (one.median_ns, one.p95_ns, one.p99_ns, one.p999_ns),
+            (42, 42, 42, 42)
+        );
+
+        let two = LatencyMetrics::from_samples(vec![20, 10], false);
+        assert_eq!(two.min_ns, 10);
+        assert_eq!(two.median_ns, 20);
+        assert_eq!(two.p99_ns, 20);
+        assert_eq!(two.p999_ns, 20);
+    }
+
+    #[test]
+    fn test_percentiles_hundred_samples() {
+        let metrics = LatencyMetrics::from_samples((1..=100).collect(), false);
+        assert_eq!(metrics.p95_ns, 95);
+        assert_eq!(metrics.p99_ns, 99);
+        assert_eq!(metrics.p999_ns, 100);
+        assert_eq!(metrics.max_ns, 100);
+    }
+
+    #[test]
+    fn test_percentiles_uniform_distribution() {
+        let mut samples: Vec<u64> = (1..=1000).collect();
+        samples.reverse();
+        let metrics = LatencyMetrics::from_samples(samples, false);
+
+        assert_eq!(metrics.median_ns, 501);
+        assert_eq!(metrics.p95_ns, 950);
+        assert_eq!(metrics.p99_ns, 990);
+        assert_eq!(metrics.p999_ns, 999);
+    }
+
+    #[test]
+    fn test_trimmed_mean_ignores_outlier() {
+        let mut samples = vec![1_000u64; 99];
+        samples.push(1_000_000_000);
+
+        let untrimmed = LatencyMetrics::from_samples(samples.clone(), false);
+        assert!(untrimmed.mean_ns > 10_000_000.0);
+        assert_eq!(untrimmed.trim_fraction, 0.0);
+
+        let trimmed = LatencyMetrics::from_samples_trimmed(samples, 5.0, false);
+        assert!((trimmed.mean_ns - 1_000.0).abs() < 1.0);
+        assert!(trimmed.std_dev_ns < 1.0);
+        assert!((trimmed.trim_fraction - 0.05).abs() < f64::EPSILON);
+        // Extremes and percentiles still see every sample
+        assert_eq!(trimmed.max_ns, 1_000_000_000);
+        assert_eq!(trimmed.p999_ns, 1_000_000_000);
+    }
+
+    #[test]
+    fn test_trim_keeps_at_least_one_sample() {
+        let metrics = LatencyMetrics::from_samples_trimmed(vec![10, 20, 30], 49.0, false);
+        assert_eq!(metrics.mean_ns, 20.0);
+
+        let single = LatencyMetrics::from_samples_trimmed(vec![7], 100.0, false);
+        assert_eq!(single.mean_ns, 7.0);
+    }
+
+    #[test]
+    fn test_keep_raw_downsamples_large_inputs() {
+        let metrics = LatencyMetrics::from_samples((0..20_000).collect(), true);
+        assert_eq!(metrics.samples.unwrap().len(), 1000);
+    }
+
     #[test]
     fn test_latency_format() {
         assert_eq!(LatencyMetrics::format_latency(500), "500ns");
@@ -380,4 +601,34 @@ mod tests {
         assert!(json.contains("cold_start"));
         assert!(json.contains("payload_size"));
     }
+
+    #[test]
+    fn test_compare_against_baseline() {
+        let latency = |name: &str, samples: Vec<u64>| {
+            BenchmarkResult::latency(name, BenchmarkCategory::Ipc, samples, false)
+        };
+
+        let mut baseline = BenchmarkReport::new();
+        baseline.add_result(latency("steady", vec![100; 10]));
+        baseline.add_result(latency("slower", vec![100; 10]));
+        baseline.add_result(latency("retired", vec![100; 10]));
+
+        let mut current = BenchmarkReport::new();
+        current.add_result(latency("steady", vec![105; 10]));
+        current.add_result(latency("slower", vec![150; 10]));
+        current.add_result(latency("fresh", vec![100; 10]));
+
+        let regressions = current.compare(&baseline, 10.0);
+        assert_eq!(regressions.len(), 2);
+        assert!(regressions.iter().all(|r| r.name == "slower"));
+        assert_eq!(regressions[0].metric, RegressionMetric::Median);
+        assert_eq!(regressions[1].metric, RegressionMetric::P99);
+        assert_eq!(regressions[0].baseline_ns, 100);
+        assert_eq!(regressions[0].current_ns, 150);
+        assert!((regressions[0].change_pct - 50.0).abs() < 0.01);
+
+        let unmatched = current.unmatched(&baseline);
+        assert_eq!(unmatched.added, vec!["fresh".to_string()]);
+        assert_eq!(unmatched.removed, vec!["retired".to_string()]);
+    }
 }
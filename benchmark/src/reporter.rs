@@ -1,14 +1,16 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright 2025 Ankit Kumar Pandey
 
-//! JSON report generation for benchmark results.
+//! JSON, CSV and Markdown report generation for benchmark results.
 //!
-//! Handles saving benchmark data to timestamped JSON files for later visualization.
+//! Handles saving benchmark data to timestamped JSON files for later visualization,
+//! flat CSV files for spreadsheets and R, and Markdown tables for pull requests.
 
-use crate::metrics::BenchmarkReport;
+use crate::metrics::{BenchmarkReport, BenchmarkResult, LatencyMetrics, ThroughputMetrics};
 use chrono::Utc;
+use std::fmt::Write as _;
 use std::fs::{self, File};
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -47,15 +49,7 @@ impl JsonReporter {
     ///
     /// Returns the path to the created file.
     pub fn save(&self, report: &BenchmarkReport) -> Result<PathBuf, ReporterError> {
-        let timestamp = Utc::now().format("%Y-%m-%dT%H-%M-%SZ");
-        let category = report
-            .results
-            .first()
-            .map(|r| r.category.to_string())
-            .unwrap_or_else(|| "mixed".to_string());
-
-        let filename = format!("{}_{}.json", category, timestamp);
-        let filepath = self.output_dir.join(&filename);
+        let filepath = self.output_dir.join(report_filename(report, "json"));
 
         let file = File::create(&filepath)?;
         let writer = BufWriter::new(file);
@@ -122,6 +116,253 @@ impl JsonReporter {
     }
 }
 
+/// Timestamped `<category>_<timestamp>.<extension>` filename for a report.
+fn report_filename(report: &BenchmarkReport, extension: &str) -> String {
+    let timestamp = Utc::now().format("%Y-%m-%dT%H-%M-%SZ");
+    let category = report
+        .results
+        .first()
+        .map(|r| r.category.to_string())
+        .unwrap_or_else(|| "mixed".to_string());
+
+    format!("{}_{}.{}", category, timestamp, extension)
+}
+
+/// Column header written by [`CsvReporter`].
+pub const CSV_HEADER: &str = "name,category,iterations,\
+min_ns,mean_ns,median_ns,p95_ns,p99_ns,p999_ns,max_ns,std_dev_ns,\
+messages_per_sec,bytes_per_sec,total_messages,total_bytes,duration_ns";
+
+/// CSV reporter for benchmark results.
+///
+/// Writes one row per result. Latency columns are left empty for
+/// throughput-only results and vice versa.
+pub struct CsvReporter {
+    /// Output directory for benchmark data
+    output_dir: PathBuf,
+}
+
+impl CsvReporter {
+    /// Create a new CSV reporter with the specified output directory.
+    pub fn new(output_dir: impl AsRef<Path>) -> Result<Self, ReporterError> {
+        let output_dir = output_dir.as_ref().to_path_buf();
+        fs::create_dir_all(&output_dir)?;
+        Ok(Self { output_dir })
+    }
+
+    /// Create a reporter using the default data directory.
+    pub fn default_location() -> Result<Self, ReporterError> {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let data_dir = Path::new(manifest_dir).join("data");
+        Self::new(data_dir)
+    }
+
+    /// Save a benchmark report to a CSV file.
+    ///
+    /// Returns the path to the created file.
+    pub fn save(&self, report: &BenchmarkReport) -> Result<PathBuf, ReporterError> {
+        let filepath = self.output_dir.join(report_filename(report, "csv"));
+
+        let mut writer = BufWriter::new(File::create(&filepath)?);
+        writeln!(writer, "{}", CSV_HEADER)?;
+        for result in &report.results {
+            writeln!(writer, "{}", csv_row(result))?;
+        }
+        writer.flush()?;
+
+        Ok(filepath)
+    }
+}
+
+/// Render one result as a CSV row matching [`CSV_HEADER`].
+fn csv_row(result: &BenchmarkResult) -> String {
+    let mut fields = vec![
+        csv_escape(&result.name),
+        result.category.to_string(),
+        result.iterations.to_string(),
+    ];
+
+    match &result.latency {
+        Some(l) => fields.extend([
+            l.min_ns.to_string(),
+            l.mean_ns.to_string(),
+            l.median_ns.to_string(),
+            l.p95_ns.to_string(),
+            l.p99_ns.to_string(),
+            l.p999_ns.to_string(),
+            l.max_ns.to_string(),
+            l.std_dev_ns.to_string(),
+        ]),
+        None => fields.extend(std::iter::repeat_n(String::new(), 8)),
+    }
+
+    match &result.throughput {
+        Some(t) => fields.extend([
+            t.messages_per_sec.to_string(),
+            t.bytes_per_sec.to_string(),
+            t.total_messages.to_string(),
+            t.total_bytes.to_string(),
+            t.duration_ns.to_string(),
+        ]),
+        None => fields.extend(std::iter::repeat_n(String::new(), 5)),
+    }
+
+    fields.join(",")
+}
+
+/// Quote a field if it contains a separator, quote or line break.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Markdown reporter for benchmark results.
+///
+/// Renders a system information block followed by one section per
+/// category, with separate tables for latency and throughput results.
+pub struct MarkdownReporter {
+    /// Output directory for benchmark data
+    output_dir: PathBuf,
+}
+
+impl MarkdownReporter {
+    /// Create a new Markdown reporter with the specified output directory.
+    pub fn new(output_dir: impl AsRef<Path>) -> Result<Self, ReporterError> {
+        let output_dir = output_dir.as_ref().to_path_buf();
+        fs::create_dir_all(&output_dir)?;
+        Ok(Self { output_dir })
+    }
+
+    /// Create a reporter using the default data directory.
+    pub fn default_location() -> Result<Self, ReporterError> {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let data_dir = Path::new(manifest_dir).join("data");
+        Self::new(data_dir)
+    }
+
+    /// Save a benchmark report to a Markdown file.
+    ///
+    /// Returns the path to the created file.
+    pub fn save(&self, report: &BenchmarkReport) -> Result<PathBuf, ReporterError> {
+        let filepath = self.output_dir.join(report_filename(report, "md"));
+        fs::write(&filepath, Self::render(report))?;
+        Ok(filepath)
+    }
+
+    /// Render a benchmark report as Markdown.
+    pub fn render(report: &BenchmarkReport) -> String {
+        let mut out = String::new();
+        let info = &report.system_info;
+
+        // Writing to a String cannot fail
+        let _ = writeln!(out, "## Benchmark Results\n");
+        let _ = writeln!(
+            out,
+            "- **Suite:** {} v{}",
+            report.benchmark_suite, report.version
+        );
+        let _ = writeln!(
+            out,
+            "- **Date:** {}",
+            report.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        let _ = writeln!(out, "- **Host:** {}", info.hostname);
+        let _ = writeln!(
+            out,
+            "- **OS:** {} {} (kernel {})",
+            info.os,
+            info.os_version,
+            info.kernel_version.as_deref().unwrap_or("unknown")
+        );
+        let _ = writeln!(
+            out,
+            "- **CPU:** {} ({} cores)",
+            info.cpu_model, info.cpu_cores
+        );
+        let _ = writeln!(
+            out,
+            "- **Memory:** {:.1} GiB",
+            info.memory_bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+        );
+
+        // Group by category, keeping first-seen order
+        let mut categories = Vec::new();
+        for result in &report.results {
+            if !categories.contains(&result.category) {
+                categories.push(result.category);
+            }
+        }
+
+        for category in categories {
+            let results: Vec<&BenchmarkResult> = report
+                .results
+                .iter()
+                .filter(|r| r.category == category)
+                .collect();
+            let _ = writeln!(out, "\n### {}", category);
+
+            let latency: Vec<_> = results
+                .iter()
+                .filter_map(|r| r.latency.as_ref().map(|l| (*r, l)))
+                .collect();
+            if !latency.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "\n| Benchmark | Iterations | Min | Median | p95 | p99 | p999 | Max |"
+                );
+                let _ = writeln!(out, "|---|---:|---:|---:|---:|---:|---:|---:|");
+                for (result, l) in latency {
+                    let _ = writeln!(
+                        out,
+                        "| {} | {} | {} | {} | {} | {} | {} | {} |",
+                        markdown_escape(&result.name),
+                        result.iterations,
+                        LatencyMetrics::format_latency(l.min_ns),
+                        LatencyMetrics::format_latency(l.median_ns),
+                        LatencyMetrics::format_latency(l.p95_ns),
+                        LatencyMetrics::format_latency(l.p99_ns),
+                        LatencyMetrics::format_latency(l.p999_ns),
+                        LatencyMetrics::format_latency(l.max_ns),
+                    );
+                }
+            }
+
+            let throughput: Vec<_> = results
+                .iter()
+                .filter_map(|r| r.throughput.as_ref().map(|t| (*r, t)))
+                .collect();
+            if !throughput.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "\n| Benchmark | Messages | Messages/s | Throughput | Duration |"
+                );
+                let _ = writeln!(out, "|---|---:|---:|---:|---:|");
+                for (result, t) in throughput {
+                    let _ = writeln!(
+                        out,
+                        "| {} | {} | {:.0} | {} | {} |",
+                        markdown_escape(&result.name),
+                        t.total_messages,
+                        t.messages_per_sec,
+                        ThroughputMetrics::format_bytes_per_sec(t.bytes_per_sec),
+                        LatencyMetrics::format_latency(t.duration_ns),
+                    );
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Escape characters that would break a Markdown table cell.
+fn markdown_escape(field: &str) -> String {
+    field.replace('|', "\\|").replace('\n', " ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,4 +412,71 @@ mod tests {
         // At least 1 report should exist (2 if timestamps differ)
         assert!(!reports.is_empty());
     }
+
+    #[test]
+    fn test_csv_reporter_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let reporter = CsvReporter::new(temp_dir.path()).unwrap();
+
+        let mut report = BenchmarkReport::new();
+        report.add_result(BenchmarkResult::latency(
+            "restore, warm",
+            BenchmarkCategory::ColdStart,
+            vec![100, 200, 300],
+            false,
+        ));
+        report.add_result(BenchmarkResult::throughput(
+            "shm",
+            BenchmarkCategory::Ipc,
+            1000,
+            1_000_000,
+            1_000_000_000,
+        ));
+
+        let path = reporter.save(&report).unwrap();
+        assert_eq!(path.extension().unwrap(), "csv");
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "\"restore, warm\",cold_start,3,100,200,200,300,300,300,300,81.64965809277261,,,,,"
+        );
+        assert_eq!(
+            lines[2],
+            "shm,ipc,1000,,,,,,,,,1000,1000000,1000,1000000,1000000000"
+        );
+    }
+
+    #[test]
+    fn test_markdown_render() {
+        let mut report = BenchmarkReport::new();
+        report.add_result(BenchmarkResult::latency(
+            "restore",
+            BenchmarkCategory::ColdStart,
+            vec![1_500, 2_500],
+            false,
+        ));
+        report.add_result(BenchmarkResult::throughput(
+            "shm",
+            BenchmarkCategory::Ipc,
+            1000,
+            2_000_000,
+            1_000_000_000,
+        ));
+
+        let markdown = MarkdownReporter::render(&report);
+        assert!(markdown.contains(&format!("- **Host:** {}", report.system_info.hostname)));
+        assert!(markdown.contains("\n### cold_start\n"));
+        assert!(markdown
+            .contains("| restore | 2 | 1.50μs | 2.50μs | 2.50μs | 2.50μs | 2.50μs | 2.50μs |"));
+        assert!(markdown.contains("\n### ipc\n"));
+        assert!(markdown.contains("| Benchmark | Messages | Messages/s | Throughput | Duration |"));
+        assert!(markdown.contains("| shm | 1000 | 1000 | 2.00 MB/s | 1.00s |"));
+        // The ipc section has no latency results, so no latency table
+        let ipc = markdown.split("### ipc").nth(1).unwrap();
+        assert!(!ipc.contains("p99"));
+    }
 }